@@ -0,0 +1,344 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2023 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+	collections::BTreeMap,
+	fmt, fs,
+	io::{self, BufReader, BufWriter, Read, Write},
+	path::PathBuf,
+	str::FromStr,
+};
+
+use clap::ValueEnum;
+use serde::{
+	de::{self, DeserializeSeed, IgnoredAny, MapAccess, Visitor},
+	Deserialize, Deserializer,
+};
+// Substrate
+use sp_core::{hashing::keccak_256, H160, H256, U256};
+// Frontier
+use fp_evm::GenesisAccount;
+
+/// Cli tool to convert a Geth state dump into the `evm` genesis section of a chain spec.
+///
+/// Input is streamed, so arbitrarily large dumps can be converted with memory bound by the
+/// largest single account.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ImportGethStateCmd {
+	/// Path to the Geth state dump or genesis alloc file.
+	#[arg(required = true)]
+	pub input: PathBuf,
+
+	/// Specify the input format.
+	///
+	/// Can be one of `dump | iterative | alloc`.
+	#[arg(long, value_enum, ignore_case = true, default_value_t = StateFormat::Dump)]
+	pub format: StateFormat,
+
+	/// Specify where to write the `evm` genesis section.
+	///
+	/// - When `Some`, path to file.
+	/// - When `None`, write to stdout.
+	#[arg(long)]
+	pub output: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateFormat {
+	/// `geth dump` output, a single object holding an `accounts` map.
+	Dump,
+	/// `geth dump --iterative` output, one account object per line.
+	Iterative,
+	/// Genesis `alloc` map of address to account.
+	Alloc,
+}
+
+impl ImportGethStateCmd {
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let input = BufReader::new(fs::File::open(&self.input)?);
+		let count = match &self.output {
+			Some(path) => convert(input, self.format, BufWriter::new(fs::File::create(path)?))?,
+			None => convert(input, self.format, BufWriter::new(io::stdout().lock()))?,
+		};
+		// Stdout may hold the genesis section, report on stderr.
+		eprintln!("Imported {} accounts", count);
+		Ok(())
+	}
+}
+
+/// Stream the accounts of a Geth state `input` into an `{"evm":{"accounts":{..}}}` genesis
+/// section written to `output`. Returns the number of accounts written.
+pub fn convert<R: Read, W: Write>(
+	input: R,
+	format: StateFormat,
+	output: W,
+) -> sc_cli::Result<usize> {
+	let mut writer = GenesisWriter::new(output)?;
+	let mut deserializer = serde_json::Deserializer::from_reader(input);
+	match format {
+		StateFormat::Dump => {
+			DumpSeed(&mut writer)
+				.deserialize(&mut deserializer)
+				.map_err(|e| format!("Failed to read state dump: {}", e))?;
+			deserializer
+				.end()
+				.map_err(|e| format!("Failed to read state dump: {}", e))?;
+		}
+		StateFormat::Alloc => {
+			AccountsSeed(&mut writer)
+				.deserialize(&mut deserializer)
+				.map_err(|e| format!("Failed to read genesis alloc: {}", e))?;
+			deserializer
+				.end()
+				.map_err(|e| format!("Failed to read genesis alloc: {}", e))?;
+		}
+		StateFormat::Iterative => {
+			for line in deserializer.into_iter::<GethAccount>() {
+				let account = line.map_err(|e| format!("Failed to read state dump: {}", e))?;
+				match account.address.clone() {
+					Some(address) => writer.write(&address, account)?,
+					// The leading line only holds the state root.
+					None if account.balance.is_none() => {}
+					None => {
+						return Err(
+							"Account without address, dump was made without preimages".into()
+						)
+					}
+				}
+			}
+		}
+	}
+	writer.finish()
+}
+
+/// Account as found in Geth state dumps and genesis alloc files.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GethAccount {
+	#[serde(default)]
+	balance: Option<Quantity>,
+	#[serde(default)]
+	nonce: Option<Quantity>,
+	#[serde(default)]
+	code: Option<String>,
+	#[serde(default)]
+	code_hash: Option<String>,
+	#[serde(default)]
+	storage: BTreeMap<String, String>,
+	/// Only present in iterative dumps.
+	#[serde(default)]
+	address: Option<String>,
+}
+
+/// A number encoded as a JSON number, a decimal string or a `0x` prefixed hex string.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Quantity {
+	Number(u64),
+	String(String),
+}
+
+impl Quantity {
+	fn to_u256(&self) -> Result<U256, String> {
+		match self {
+			Quantity::Number(n) => Ok(U256::from(*n)),
+			Quantity::String(s) => {
+				let parsed = match s.strip_prefix("0x") {
+					Some(hex) => U256::from_str_radix(hex, 16).ok(),
+					None => U256::from_dec_str(s).ok(),
+				};
+				parsed.ok_or_else(|| format!("Invalid quantity `{}`", s))
+			}
+		}
+	}
+}
+
+impl GethAccount {
+	fn into_genesis(self, address: &str) -> Result<(H160, GenesisAccount), String> {
+		let address = H160::from_str(address.trim_start_matches("0x")).map_err(|_| {
+			format!(
+				"Invalid address `{}`, dump was made without preimages",
+				address
+			)
+		})?;
+
+		let code = match &self.code {
+			Some(code) => hex::decode(code.trim_start_matches("0x"))
+				.map_err(|_| format!("Invalid code for {:?}", address))?,
+			None => Vec::new(),
+		};
+		if let Some(code_hash) = &self.code_hash {
+			let expected = parse_word(code_hash)
+				.map_err(|_| format!("Invalid code hash for {:?}", address))?;
+			let actual = H256::from(keccak_256(&code));
+			if expected != actual {
+				return Err(format!(
+					"Code hash mismatch for {:?}: expected {:?}, got {:?}",
+					address, expected, actual
+				));
+			}
+		}
+
+		let mut storage = BTreeMap::new();
+		for (key, value) in &self.storage {
+			let key = parse_word(key)
+				.map_err(|_| format!("Invalid storage key `{}` for {:?}", key, address))?;
+			let value = parse_word(value)
+				.map_err(|_| format!("Invalid storage value `{}` for {:?}", value, address))?;
+			// Zero values are absent from the EVM storage.
+			if !value.is_zero() {
+				storage.insert(key, value);
+			}
+		}
+
+		Ok((
+			address,
+			GenesisAccount {
+				nonce: self
+					.nonce
+					.as_ref()
+					.map(Quantity::to_u256)
+					.transpose()?
+					.unwrap_or_default(),
+				balance: self
+					.balance
+					.as_ref()
+					.map(Quantity::to_u256)
+					.transpose()?
+					.unwrap_or_default(),
+				storage,
+				code,
+			},
+		))
+	}
+}
+
+/// Parse a hex encoded word of up to 32 bytes, left padding it with zeros. Geth dumps
+/// storage values RLP decoded, without leading zero bytes.
+fn parse_word(value: &str) -> Result<H256, ()> {
+	let value = value.trim_start_matches("0x");
+	let bytes = if value.len() % 2 == 1 {
+		hex::decode(format!("0{}", value))
+	} else {
+		hex::decode(value)
+	}
+	.map_err(|_| ())?;
+	if bytes.len() > 32 {
+		return Err(());
+	}
+
+	let mut word = H256::zero();
+	word.as_bytes_mut()[32 - bytes.len()..].copy_from_slice(&bytes);
+	Ok(word)
+}
+
+/// Writes the genesis section one account at a time.
+struct GenesisWriter<W: Write> {
+	output: W,
+	count: usize,
+}
+
+impl<W: Write> GenesisWriter<W> {
+	fn new(mut output: W) -> io::Result<Self> {
+		output.write_all(br#"{"evm":{"accounts":{"#)?;
+		Ok(Self { output, count: 0 })
+	}
+
+	fn write(&mut self, address: &str, account: GethAccount) -> Result<(), String> {
+		let (address, account) = account.into_genesis(address)?;
+		self.write_account(&address, &account)
+			.map_err(|e| format!("Failed to write account {:?}: {}", address, e))
+	}
+
+	fn write_account(&mut self, address: &H160, account: &GenesisAccount) -> io::Result<()> {
+		if self.count > 0 {
+			self.output.write_all(b",")?;
+		}
+		serde_json::to_writer(&mut self.output, address)?;
+		self.output.write_all(b":")?;
+		serde_json::to_writer(&mut self.output, account)?;
+		self.count += 1;
+		Ok(())
+	}
+
+	fn finish(mut self) -> sc_cli::Result<usize> {
+		self.output.write_all(b"}}}\n")?;
+		self.output.flush()?;
+		Ok(self.count)
+	}
+}
+
+/// Visits the top level object of a `geth dump`, streaming its `accounts` map.
+struct DumpSeed<'a, W: Write>(&'a mut GenesisWriter<W>);
+
+impl<'de, 'a, W: Write> DeserializeSeed<'de> for DumpSeed<'a, W> {
+	type Value = ();
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, 'a, W: Write> Visitor<'de> for DumpSeed<'a, W> {
+	type Value = ();
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("a geth state dump")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+		while let Some(key) = map.next_key::<String>()? {
+			if key == "accounts" {
+				map.next_value_seed(AccountsSeed(&mut *self.0))?;
+			} else {
+				map.next_value::<IgnoredAny>()?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Visits a map of address to account, writing each account as soon as it is read.
+struct AccountsSeed<'a, W: Write>(&'a mut GenesisWriter<W>);
+
+impl<'de, 'a, W: Write> DeserializeSeed<'de> for AccountsSeed<'a, W> {
+	type Value = ();
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, 'a, W: Write> Visitor<'de> for AccountsSeed<'a, W> {
+	type Value = ();
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("a map of address to account")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+		while let Some(address) = map.next_key::<String>()? {
+			let account = map.next_value::<GethAccount>()?;
+			self.0.write(&address, account).map_err(de::Error::custom)?;
+		}
+		Ok(())
+	}
+}
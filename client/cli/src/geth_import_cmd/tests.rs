@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2023 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+// Substrate
+use sp_core::{H160, H256, U256};
+// Frontier
+use fp_evm::GenesisAccount;
+
+use super::{convert, StateFormat};
+
+const EMPTY_CODE_HASH: &str = "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+
+fn convert_str(input: &str, format: StateFormat) -> Result<BTreeMap<H160, GenesisAccount>, String> {
+	let mut output = Vec::new();
+	let count = convert(input.as_bytes(), format, &mut output).map_err(|e| e.to_string())?;
+	let section: serde_json::Value = serde_json::from_slice(&output).expect("valid json");
+	let accounts: BTreeMap<H160, GenesisAccount> =
+		serde_json::from_value(section["evm"]["accounts"].clone()).expect("valid accounts");
+	assert_eq!(accounts.len(), count);
+	Ok(accounts)
+}
+
+fn code_hash() -> String {
+	format!(
+		"{:?}",
+		H256::from(sp_core::hashing::keccak_256(&[0x60, 0x80]))
+	)
+}
+
+fn address(n: u64) -> H160 {
+	H160::from_low_u64_be(n)
+}
+
+#[test]
+fn dump_is_converted() {
+	let input = format!(
+		r#"{{
+			"root": "0x0000000000000000000000000000000000000000000000000000000000000001",
+			"accounts": {{
+				"0x0000000000000000000000000000000000000001": {{
+					"balance": "1000000000000000000000",
+					"nonce": 150,
+					"root": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+					"codeHash": "{}"
+				}},
+				"0x0000000000000000000000000000000000000002": {{
+					"balance": "0",
+					"nonce": 1,
+					"root": "0x0000000000000000000000000000000000000000000000000000000000000002",
+					"codeHash": "{}",
+					"code": "0x6080",
+					"storage": {{
+						"0x0000000000000000000000000000000000000000000000000000000000000000": "2a",
+						"0x0000000000000000000000000000000000000000000000000000000000000001": "00"
+					}}
+				}}
+			}}
+		}}"#,
+		EMPTY_CODE_HASH,
+		code_hash()
+	);
+
+	let accounts = convert_str(&input, StateFormat::Dump).expect("dump converts");
+	assert_eq!(
+		accounts.get(&address(1)),
+		Some(&GenesisAccount {
+			nonce: U256::from(150),
+			balance: U256::from_dec_str("1000000000000000000000").unwrap(),
+			storage: Default::default(),
+			code: Vec::new(),
+		})
+	);
+	assert_eq!(
+		accounts.get(&address(2)),
+		Some(&GenesisAccount {
+			nonce: U256::from(1),
+			balance: U256::zero(),
+			// Zero values are dropped, others are left padded.
+			storage: [(H256::zero(), H256::from_low_u64_be(42))]
+				.into_iter()
+				.collect(),
+			code: vec![0x60, 0x80],
+		})
+	);
+}
+
+#[test]
+fn iterative_dump_is_converted() {
+	let input = format!(
+		"{}\n{}\n",
+		r#"{"root":"0x0000000000000000000000000000000000000000000000000000000000000001"}"#,
+		r#"{"balance":"7","nonce":3,"address":"0x0000000000000000000000000000000000000001"}"#,
+	);
+
+	let accounts = convert_str(&input, StateFormat::Iterative).expect("dump converts");
+	assert_eq!(accounts.len(), 1);
+	assert_eq!(accounts[&address(1)].balance, U256::from(7));
+	assert_eq!(accounts[&address(1)].nonce, U256::from(3));
+}
+
+#[test]
+fn alloc_is_converted() {
+	let input = r#"{
+		"0000000000000000000000000000000000000001": {
+			"balance": "0xde0b6b3a7640000",
+			"nonce": "0x2",
+			"code": "0x6080",
+			"storage": {
+				"0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000003"
+			}
+		}
+	}"#;
+
+	let accounts = convert_str(input, StateFormat::Alloc).expect("alloc converts");
+	let account = &accounts[&address(1)];
+	assert_eq!(account.balance, U256::from(1_000_000_000_000_000_000u64));
+	assert_eq!(account.nonce, U256::from(2));
+	assert_eq!(account.code, vec![0x60, 0x80]);
+	assert_eq!(
+		account.storage.get(&H256::from_low_u64_be(1)),
+		Some(&H256::from_low_u64_be(3))
+	);
+}
+
+#[test]
+fn code_hash_mismatch_fails() {
+	let input = format!(
+		r#"{{"accounts":{{"0x0000000000000000000000000000000000000001":{{"balance":"0","nonce":0,"code":"0x6080","codeHash":"{}"}}}}}}"#,
+		EMPTY_CODE_HASH
+	);
+
+	let err = convert_str(&input, StateFormat::Dump).unwrap_err();
+	assert!(
+		err.contains("Code hash mismatch"),
+		"unexpected error: {}",
+		err
+	);
+}
+
+#[test]
+fn missing_preimage_fails() {
+	let input = r#"{"accounts":{"pre(0x01)":{"balance":"0","nonce":0}}}"#;
+
+	let err = convert_str(input, StateFormat::Dump).unwrap_err();
+	assert!(
+		err.contains("without preimages"),
+		"unexpected error: {}",
+		err
+	);
+}
@@ -19,5 +19,9 @@
 #![deny(unused_crate_dependencies)]
 
 mod frontier_db_cmd;
+mod geth_import_cmd;
 
-pub use self::frontier_db_cmd::FrontierDbCmd;
+pub use self::{
+	frontier_db_cmd::FrontierDbCmd,
+	geth_import_cmd::{ImportGethStateCmd, StateFormat},
+};
@@ -245,6 +245,22 @@ pub trait EthApi {
 	/// Sends signed transaction, returning its hash.
 	#[method(name = "eth_sendRawTransaction")]
 	async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<H256>;
+
+	// ########################################################################
+	// Sign
+	// ########################################################################
+
+	/// Signs `data` prefixed with "\x19Ethereum Signed Message:\n" and its length.
+	#[method(name = "eth_sign")]
+	fn sign(&self, address: H160, data: Bytes) -> RpcResult<Bytes>;
+
+	/// Signs a transaction without submitting it, returning its raw encoding.
+	#[method(name = "eth_signTransaction")]
+	async fn sign_transaction(&self, request: TransactionRequest) -> RpcResult<Bytes>;
+
+	/// Signs EIP-712 typed structured data.
+	#[method(name = "eth_signTypedData_v4")]
+	fn sign_typed_data_v4(&self, address: H160, typed_data: TypedData) -> RpcResult<Bytes>;
 }
 
 /// Eth filters rpc api (polling).
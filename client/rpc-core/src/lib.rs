@@ -23,6 +23,7 @@ pub mod types;
 mod eth;
 mod eth_pubsub;
 mod net;
+mod personal;
 mod txpool;
 mod web3;
 
@@ -30,6 +31,7 @@ pub use self::{
 	eth::{EthApiServer, EthFilterApiServer},
 	eth_pubsub::EthPubSubApiServer,
 	net::NetApiServer,
+	personal::PersonalApiServer,
 	txpool::TxPoolApiServer,
 	web3::Web3ApiServer,
 };
@@ -0,0 +1,51 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Personal rpc interface.
+
+use ethereum_types::H160;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+use crate::types::Bytes;
+
+/// Personal rpc interface, for accounts held in a local keystore.
+#[rpc(server)]
+pub trait PersonalApi {
+	/// Returns the accounts of the keystore.
+	#[method(name = "personal_listAccounts")]
+	fn list_accounts(&self) -> RpcResult<Vec<H160>>;
+
+	/// Decrypts the key of `address` and keeps it in memory for `duration` seconds, 300 by
+	/// default. A duration of 0 keeps it unlocked until it is locked again.
+	#[method(name = "personal_unlockAccount")]
+	fn unlock_account(
+		&self,
+		address: H160,
+		password: String,
+		duration: Option<u64>,
+	) -> RpcResult<bool>;
+
+	/// Removes the decrypted key of `address` from memory.
+	#[method(name = "personal_lockAccount")]
+	fn lock_account(&self, address: H160) -> RpcResult<bool>;
+
+	/// Signs `data` like `eth_sign`, decrypting the key of `address` with `password` for this
+	/// call only.
+	#[method(name = "personal_sign")]
+	fn sign(&self, data: Bytes, address: H160, password: String) -> RpcResult<Bytes>;
+}
@@ -18,7 +18,9 @@
 
 //! tx pool rpc interface
 
-use ethereum_types::U256;
+use std::collections::HashMap;
+
+use ethereum_types::{H160, U256};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 
 use crate::types::*;
@@ -32,6 +34,17 @@ pub trait TxPoolApi {
 	#[method(name = "txpool_inspect")]
 	fn inspect(&self) -> RpcResult<TxPoolResult<TransactionMap<Summary>>>;
 
+	/// Transactions of `address` in the pool, by nonce.
+	#[method(name = "txpool_contentFrom")]
+	fn content_from(
+		&self,
+		address: H160,
+	) -> RpcResult<TxPoolResult<HashMap<U256, TxPoolTransaction>>>;
+
+	/// Summary of the transactions of `address` in the pool, by nonce.
+	#[method(name = "txpool_inspectFrom")]
+	fn inspect_from(&self, address: H160) -> RpcResult<TxPoolResult<HashMap<U256, Summary>>>;
+
 	#[method(name = "txpool_status")]
 	fn status(&self) -> RpcResult<TxPoolResult<U256>>;
 }
@@ -32,6 +32,7 @@ mod sync;
 mod transaction;
 mod transaction_request;
 mod txpool;
+mod typed_data;
 mod work;
 
 pub mod pubsub;
@@ -57,5 +58,6 @@ pub use self::{
 	transaction::{LocalTransactionStatus, RichRawTransaction, Transaction},
 	transaction_request::{TransactionMessage, TransactionRequest},
 	txpool::{Get, Summary, TransactionMap, TxPoolResult, TxPoolTransaction},
+	typed_data::{TypedData, TypedDataField, TypedDataPayload},
 	work::Work,
 };
@@ -18,11 +18,11 @@
 
 //! Pub-Sub types.
 
-use ethereum_types::H256;
+use ethereum_types::{H160, H256};
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{from_value, Value};
 
-use crate::types::{Filter, Log, RichHeader};
+use crate::types::{Filter, Log, RichHeader, Transaction, VariadicValue};
 
 /// Subscription result.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -33,6 +33,8 @@ pub enum Result {
 	Log(Box<Log>),
 	/// Transaction hash
 	TransactionHash(H256),
+	/// Full pending transaction
+	Transaction(Box<Transaction>),
 	/// SyncStatus
 	SyncState(PubSubSyncStatus),
 }
@@ -63,6 +65,7 @@ impl Serialize for Result {
 			Result::Header(ref header) => header.serialize(serializer),
 			Result::Log(ref log) => log.serialize(serializer),
 			Result::TransactionHash(ref hash) => hash.serialize(serializer),
+			Result::Transaction(ref transaction) => transaction.serialize(serializer),
 			Result::SyncState(ref sync) => sync.serialize(serializer),
 		}
 	}
@@ -91,6 +94,47 @@ pub enum Params {
 	None,
 	/// Log parameters.
 	Logs(Filter),
+	/// Pending transactions parameters.
+	Transactions(TransactionsParams),
+}
+
+/// Parameters of the `newPendingTransactions` subscription, either a plain boolean for
+/// `fullTransactions` like Geth, or an object.
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsParams {
+	/// Stream full transaction objects instead of hashes.
+	#[serde(default)]
+	pub full_transactions: bool,
+	/// Only stream transactions sent from these addresses.
+	pub from_address: Option<VariadicValue<H160>>,
+	/// Only stream transactions sent to these addresses.
+	pub to_address: Option<VariadicValue<H160>>,
+}
+
+impl TransactionsParams {
+	/// Whether the address filters are set, requiring the sender to be recovered.
+	pub fn has_address_filter(&self) -> bool {
+		self.from_address.is_some() || self.to_address.is_some()
+	}
+
+	/// Whether a transaction from `from` to `to` passes the address filters. Contract
+	/// creations never match a `toAddress` filter.
+	pub fn matches(&self, from: &H160, to: Option<&H160>) -> bool {
+		fn matches_address(filter: &Option<VariadicValue<H160>>, address: Option<&H160>) -> bool {
+			match (filter, address) {
+				(None, _) | (Some(VariadicValue::Null), _) => true,
+				(Some(VariadicValue::Single(expected)), Some(address)) => expected == address,
+				(Some(VariadicValue::Multiple(expected)), Some(address)) => {
+					expected.contains(address)
+				}
+				(_, None) => false,
+			}
+		}
+
+		matches_address(&self.from_address, Some(from)) && matches_address(&self.to_address, to)
+	}
 }
 
 impl<'a> Deserialize<'a> for Params {
@@ -104,8 +148,81 @@ impl<'a> Deserialize<'a> for Params {
 			return Ok(Params::None);
 		}
 
-		from_value(v)
+		if let Value::Bool(full_transactions) = v {
+			return Ok(Params::Transactions(TransactionsParams {
+				full_transactions,
+				..Default::default()
+			}));
+		}
+
+		from_value(v.clone())
 			.map(Params::Logs)
+			.or_else(|e| from_value(v).map(Params::Transactions).map_err(|_| e))
 			.map_err(|e| D::Error::custom(format!("Invalid Pub-Sub parameters: {}", e)))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::str::FromStr;
+
+	#[test]
+	fn params_deserialization() {
+		let alice = H160::from_str("1000000000000000000000000000000000000001").unwrap();
+		let bob = H160::from_str("1000000000000000000000000000000000000002").unwrap();
+
+		assert_eq!(
+			serde_json::from_str::<Params>("null").unwrap(),
+			Params::None
+		);
+		assert_eq!(
+			serde_json::from_str::<Params>("true").unwrap(),
+			Params::Transactions(TransactionsParams {
+				full_transactions: true,
+				..Default::default()
+			})
+		);
+		assert!(matches!(
+			serde_json::from_str::<Params>(
+				r#"{"address":"0x1000000000000000000000000000000000000001"}"#
+			)
+			.unwrap(),
+			Params::Logs(_)
+		));
+		assert_eq!(
+			serde_json::from_str::<Params>(
+				r#"{"fullTransactions":true,"fromAddress":"0x1000000000000000000000000000000000000001","toAddress":["0x1000000000000000000000000000000000000001","0x1000000000000000000000000000000000000002"]}"#
+			)
+			.unwrap(),
+			Params::Transactions(TransactionsParams {
+				full_transactions: true,
+				from_address: Some(VariadicValue::Single(alice)),
+				to_address: Some(VariadicValue::Multiple(vec![alice, bob])),
+			})
+		);
+		assert!(serde_json::from_str::<Params>(r#"{"unknown":true}"#).is_err());
+	}
+
+	#[test]
+	fn transactions_params_matching() {
+		let alice = H160::from_str("1000000000000000000000000000000000000001").unwrap();
+		let bob = H160::from_str("1000000000000000000000000000000000000002").unwrap();
+
+		let params = TransactionsParams::default();
+		assert!(!params.has_address_filter());
+		assert!(params.matches(&alice, Some(&bob)));
+		assert!(params.matches(&alice, None));
+
+		let params = TransactionsParams {
+			from_address: Some(VariadicValue::Single(alice)),
+			to_address: Some(VariadicValue::Multiple(vec![bob])),
+			..Default::default()
+		};
+		assert!(params.has_address_filter());
+		assert!(params.matches(&alice, Some(&bob)));
+		assert!(!params.matches(&bob, Some(&bob)));
+		assert!(!params.matches(&alice, Some(&alice)));
+		assert!(!params.matches(&alice, None));
+	}
+}
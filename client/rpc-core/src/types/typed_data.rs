@@ -0,0 +1,93 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2015-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeMap;
+
+use serde::{de::Error, Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Member of an EIP-712 struct type.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+pub struct TypedDataField {
+	pub name: String,
+	#[serde(rename = "type")]
+	pub type_: String,
+}
+
+/// EIP-712 typed structured data, as passed to `eth_signTypedData_v4`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedDataPayload {
+	pub types: BTreeMap<String, Vec<TypedDataField>>,
+	pub primary_type: String,
+	pub domain: Value,
+	#[serde(default)]
+	pub message: Value,
+}
+
+/// EIP-712 typed data, accepted either as a JSON object or as a string holding one, which is
+/// what most wallets send.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedData(pub TypedDataPayload);
+
+impl<'de> Deserialize<'de> for TypedData {
+	fn deserialize<D>(deserializer: D) -> Result<TypedData, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		match Value::deserialize(deserializer)? {
+			Value::String(s) => serde_json::from_str(&s)
+				.map(TypedData)
+				.map_err(D::Error::custom),
+			v => serde_json::from_value(v)
+				.map(TypedData)
+				.map_err(D::Error::custom),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn typed_data_deserialization_works() {
+		let object = r#"{
+			"types": {
+				"EIP712Domain": [{ "name": "name", "type": "string" }],
+				"Mail": [{ "name": "contents", "type": "string" }]
+			},
+			"primaryType": "Mail",
+			"domain": { "name": "Ether Mail" },
+			"message": { "contents": "Hello, Bob!" }
+		}"#;
+		let from_object: TypedData = serde_json::from_str(object).unwrap();
+		assert_eq!(from_object.0.primary_type, "Mail");
+		assert_eq!(
+			from_object.0.types["Mail"],
+			vec![TypedDataField {
+				name: "contents".into(),
+				type_: "string".into(),
+			}]
+		);
+
+		let string = serde_json::to_string(object).unwrap();
+		let from_string: TypedData = serde_json::from_str(&string).unwrap();
+		assert_eq!(from_string, from_object);
+	}
+}
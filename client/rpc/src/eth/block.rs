@@ -23,7 +23,6 @@ use jsonrpsee::core::RpcResult;
 // Substrate
 use sc_client_api::backend::{Backend, StorageProvider};
 use sc_transaction_pool::ChainApi;
-use sc_transaction_pool_api::InPoolTransaction;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::hashing::keccak_256;
@@ -156,46 +155,19 @@ where
 				}
 			}
 			None if number == BlockNumber::Pending => {
-				let api = client.runtime_api();
-				let best_hash = client.info().best_hash;
-
-				// Get current in-pool transactions
-				let mut xts: Vec<<B as BlockT>::Extrinsic> = Vec::new();
-				// ready validated pool
-				xts.extend(
-					graph
-						.validated_pool()
-						.ready()
-						.map(|in_pool_tx| in_pool_tx.data().clone())
-						.collect::<Vec<<B as BlockT>::Extrinsic>>(),
-				);
-
-				// future validated pool
-				xts.extend(
-					graph
-						.validated_pool()
-						.futures()
-						.iter()
-						.map(|(_hash, extrinsic)| extrinsic.clone())
-						.collect::<Vec<<B as BlockT>::Extrinsic>>(),
-				);
-
-				let (block, statuses) = api
-					.pending_block(best_hash, xts)
-					.map_err(|_| internal_err(format!("Runtime access error at {}", best_hash)))?;
-
-				let base_fee = api.gas_price(best_hash).ok();
-
-				match (block, statuses) {
-					(Some(block), Some(statuses)) => Ok(Some(rich_block_build(
-						block,
-						statuses.into_iter().map(Option::Some).collect(),
+				match self
+					.pending_block
+					.current(client.as_ref(), graph.as_ref())?
+				{
+					Some(pending) => Ok(Some(rich_block_build(
+						pending.block.clone(),
+						pending.statuses.iter().cloned().map(Option::Some).collect(),
 						None,
 						full,
-						base_fee,
+						pending.base_fee,
 						true,
 					))),
-					_ => Ok(None),
+					None => Ok(None),
 				}
 			}
 			None => {
@@ -249,10 +221,10 @@ where
 		number: BlockNumber,
 	) -> RpcResult<Option<U256>> {
 		if let BlockNumber::Pending = number {
-			// get the pending transactions count
-			return Ok(Some(U256::from(
-				self.graph.validated_pool().ready().count(),
-			)));
+			return Ok(self
+				.pending_block
+				.current(self.client.as_ref(), self.graph.as_ref())?
+				.map(|pending| U256::from(pending.block.transactions.len())));
 		}
 
 		let id = match frontier_backend_client::native_block_id::<B, C>(
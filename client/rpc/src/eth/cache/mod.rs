@@ -22,6 +22,7 @@ use std::{
 	collections::{BTreeMap, HashMap},
 	marker::PhantomData,
 	sync::{Arc, Mutex},
+	time::Duration,
 };
 
 use ethereum::BlockV2 as EthereumBlock;
@@ -34,6 +35,7 @@ use sc_client_api::{
 	client::BlockchainEvents,
 };
 use sc_service::SpawnTaskHandle;
+use sc_transaction_pool::{ChainApi, Pool};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto};
@@ -44,6 +46,7 @@ use fp_rpc::{EthereumRuntimeRPCApi, TransactionStatus};
 use fp_storage::EthereumStorageSchema;
 
 use self::lru_cache::LRUCacheByteLimited;
+use crate::eth::PendingBlockCache;
 
 type WaitList<Hash, T> = HashMap<Hash, Vec<oneshot::Sender<Option<T>>>>;
 
@@ -296,6 +299,47 @@ where
 		}
 	}
 
+	/// Keep the pending block up to date, checking on every imported block and every
+	/// `refresh_interval`, so that RPC calls rarely have to build it themselves.
+	pub async fn pending_block_task<A>(
+		client: Arc<C>,
+		graph: Arc<Pool<A>>,
+		pending_block: PendingBlockCache<B>,
+		refresh_interval: Duration,
+	) where
+		C: Send + Sync,
+		A: ChainApi<Block = B> + 'static,
+	{
+		let imports = client.import_notification_stream().map(|_| ());
+		let ticks = futures::stream::unfold(
+			tokio::time::interval(refresh_interval),
+			|mut interval| async move {
+				interval.tick().await;
+				Some(((), interval))
+			},
+		);
+		let mut refresh_st = futures::stream::select(imports, Box::pin(ticks));
+
+		while refresh_st.next().await.is_some() {
+			// Building the block executes the ready transactions, keep it off the async executor.
+			let (client, graph, pending_block) =
+				(client.clone(), graph.clone(), pending_block.clone());
+			match tokio::task::spawn_blocking(move || {
+				pending_block.current(client.as_ref(), graph.as_ref())
+			})
+			.await
+			{
+				Ok(Ok(_)) => {}
+				Ok(Err(err)) => {
+					log::debug!(target: "eth-pending", "Failed to build the pending block: {:?}", err)
+				}
+				Err(err) => {
+					log::debug!(target: "eth-pending", "Pending block task failed: {:?}", err)
+				}
+			}
+		}
+	}
+
 	pub async fn fee_history_task(
 		client: Arc<C>,
 		overrides: Arc<OverrideHandle<B>>,
@@ -119,7 +119,11 @@ where
 			None => {
 				// Not mapped in the db, assume pending.
 				let hash = self.client.info().best_hash;
-				let api = pending_runtime_api(self.client.as_ref(), self.graph.as_ref())?;
+				let api = pending_runtime_api(
+					self.client.as_ref(),
+					self.graph.as_ref(),
+					&self.pending_block,
+				)?;
 				(hash, api)
 			}
 		};
@@ -433,7 +437,8 @@ where
 			None => {
 				// Not mapped in the db, assume pending.
 				let hash = client.info().best_hash;
-				let api = pending_runtime_api(client.as_ref(), self.graph.as_ref())?;
+				let api =
+					pending_runtime_api(client.as_ref(), self.graph.as_ref(), &self.pending_block)?;
 				(hash, api)
 			}
 		};
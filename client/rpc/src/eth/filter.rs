@@ -35,7 +35,10 @@ use sp_runtime::{
 use fc_rpc_core::{types::*, EthFilterApiServer};
 use fp_rpc::{EthereumRuntimeRPCApi, TransactionStatus};
 
-use crate::{eth::cache::EthBlockDataCacheTask, frontier_backend_client, internal_err, TxPool};
+use crate::{
+	eth::{cache::EthBlockDataCacheTask, PendingBlockCache},
+	frontier_backend_client, internal_err, TxPool,
+};
 
 pub struct EthFilter<B: BlockT, C, BE, A: ChainApi> {
 	client: Arc<C>,
@@ -45,6 +48,7 @@ pub struct EthFilter<B: BlockT, C, BE, A: ChainApi> {
 	max_stored_filters: usize,
 	max_past_logs: u32,
 	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	pending_block: PendingBlockCache<B>,
 	_marker: PhantomData<BE>,
 }
 
@@ -57,6 +61,7 @@ impl<B: BlockT, C, BE, A: ChainApi> EthFilter<B, C, BE, A> {
 		max_stored_filters: usize,
 		max_past_logs: u32,
 		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+		pending_block: PendingBlockCache<B>,
 	) -> Self {
 		Self {
 			client,
@@ -66,6 +71,7 @@ impl<B: BlockT, C, BE, A: ChainApi> EthFilter<B, C, BE, A> {
 			max_stored_filters,
 			max_past_logs,
 			block_data_cache,
+			pending_block,
 			_marker: PhantomData,
 		}
 	}
@@ -79,6 +85,25 @@ where
 	C: HeaderBackend<B> + 'static,
 	A: ChainApi<Block = B> + 'static,
 {
+	/// Add the logs of the pending block if `filter` ends at the `pending` tag.
+	fn pending_logs(&self, ret: &mut Vec<Log>, filter: &Filter) -> RpcResult<()> {
+		if filter.to_block != Some(BlockNumber::Pending) {
+			return Ok(());
+		}
+		if let Some(pending) = self
+			.pending_block
+			.current(self.client.as_ref(), self.tx_pool.graph())?
+		{
+			let from = ret.len();
+			filter_block_logs(ret, filter, pending.block.clone(), pending.statuses.clone());
+			// The pending block has no hash yet.
+			for log in &mut ret[from..] {
+				log.block_hash = None;
+			}
+		}
+		Ok(())
+	}
+
 	fn create_filter(&self, filter_type: FilterType) -> RpcResult<U256> {
 		let block_number =
 			UniqueSaturatedInto::<u64>::unique_saturated_into(self.client.info().best_number);
@@ -393,7 +418,9 @@ where
 			.unwrap_or(best_number);
 
 		let mut ret: Vec<Log> = Vec::new();
-		if backend.is_indexed() {
+		if filter.from_block == Some(BlockNumber::Pending) {
+			// Only the pending block is in range.
+		} else if backend.is_indexed() {
 			let _ = filter_range_logs_indexed(
 				client.as_ref(),
 				backend.as_ref(),
@@ -417,6 +444,7 @@ where
 			)
 			.await?;
 		}
+		self.pending_logs(&mut ret, &filter)?;
 		Ok(ret)
 	}
 
@@ -482,7 +510,9 @@ where
 				.map(|s| s.unique_saturated_into())
 				.unwrap_or(best_number);
 
-			if backend.is_indexed() {
+			if filter.from_block == Some(BlockNumber::Pending) {
+				// Only the pending block is in range.
+			} else if backend.is_indexed() {
 				let _ = filter_range_logs_indexed(
 					client.as_ref(),
 					backend.as_ref(),
@@ -506,6 +536,7 @@ where
 				)
 				.await?;
 			}
+			self.pending_logs(&mut ret, &filter)?;
 		}
 		Ok(ret)
 	}
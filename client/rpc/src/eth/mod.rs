@@ -24,6 +24,8 @@ mod fee;
 mod filter;
 pub mod format;
 mod mining;
+mod pending;
+mod sign;
 mod state;
 mod submit;
 mod transaction;
@@ -42,7 +44,10 @@ use sp_api::{ApiRef, CallApiAt, Core, HeaderT, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::hashing::keccak_256;
-use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
+use sp_runtime::{
+	traits::{Block as BlockT, UniqueSaturatedInto},
+	Percent,
+};
 // Frontier
 use fc_rpc_core::{types::*, EthApiServer};
 use fc_storage::OverrideHandle;
@@ -51,12 +56,13 @@ use fp_rpc::{
 	RuntimeStorageOverride, TransactionStatus,
 };
 
-use crate::{internal_err, public_key, signer::EthSigner};
+use crate::{internal_err, public_key, signer::EthSigner, TxPool};
 
 pub use self::{
 	cache::{EthBlockDataCacheTask, EthTask},
 	execute::EstimateGasAdapter,
 	filter::EthFilter,
+	pending::{PendingBlock, PendingBlockCache},
 };
 
 // Configuration trait for RPC configuration.
@@ -74,19 +80,26 @@ impl<B: BlockT, C> EthConfig<B, C> for () {
 pub struct Eth<B: BlockT, C, P, CT, BE, A: ChainApi, EC: EthConfig<B, C>> {
 	pool: Arc<P>,
 	graph: Arc<Pool<A>>,
+	tx_pool: TxPool<B, C, A>,
 	client: Arc<C>,
 	convert_transaction: Option<CT>,
 	sync: Arc<SyncingService<B>>,
 	is_authority: bool,
 	signers: Vec<Box<dyn EthSigner>>,
+	/// Whether `eth_sign`, `eth_signTransaction` and `eth_signTypedData_v4` are served.
+	unsafe_signing_rpc: bool,
 	overrides: Arc<OverrideHandle<B>>,
 	backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
 	block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+	pending_block: PendingBlockCache<B>,
 	fee_history_cache: FeeHistoryCache,
 	fee_history_cache_limit: FeeHistoryCacheLimit,
 	/// When using eth_call/eth_estimateGas, the maximum allowed gas limit will be
 	/// block.gas_limit * execute_gas_limit_multiplier
 	execute_gas_limit_multiplier: u64,
+	/// Minimum fee increase for a transaction replacing a pooled one with the same nonce,
+	/// submitted through this RPC. Other replacements only need a higher pool priority.
+	replacement_fee_bump: Percent,
 	forced_parent_hashes: Option<BTreeMap<H256, H256>>,
 	_marker: PhantomData<(B, BE, EC)>,
 }
@@ -99,29 +112,37 @@ impl<B: BlockT, C, P, CT, BE, A: ChainApi> Eth<B, C, P, CT, BE, A, ()> {
 		convert_transaction: Option<CT>,
 		sync: Arc<SyncingService<B>>,
 		signers: Vec<Box<dyn EthSigner>>,
+		unsafe_signing_rpc: bool,
 		overrides: Arc<OverrideHandle<B>>,
 		backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
 		is_authority: bool,
 		block_data_cache: Arc<EthBlockDataCacheTask<B>>,
+		pending_block: PendingBlockCache<B>,
 		fee_history_cache: FeeHistoryCache,
 		fee_history_cache_limit: FeeHistoryCacheLimit,
 		execute_gas_limit_multiplier: u64,
+		replacement_fee_bump: Percent,
 		forced_parent_hashes: Option<BTreeMap<H256, H256>>,
 	) -> Self {
+		let tx_pool = TxPool::new(client.clone(), graph.clone());
 		Self {
 			client,
 			pool,
 			graph,
+			tx_pool,
 			convert_transaction,
 			sync,
 			is_authority,
 			signers,
+			unsafe_signing_rpc,
 			overrides,
 			backend,
 			block_data_cache,
+			pending_block,
 			fee_history_cache,
 			fee_history_cache_limit,
 			execute_gas_limit_multiplier,
+			replacement_fee_bump,
 			forced_parent_hashes,
 			_marker: PhantomData,
 		}
@@ -134,16 +155,20 @@ impl<B: BlockT, C, P, CT, BE, A: ChainApi, EC: EthConfig<B, C>> Eth<B, C, P, CT,
 			client,
 			pool,
 			graph,
+			tx_pool,
 			convert_transaction,
 			sync,
 			is_authority,
 			signers,
+			unsafe_signing_rpc,
 			overrides,
 			backend,
 			block_data_cache,
+			pending_block,
 			fee_history_cache,
 			fee_history_cache_limit,
 			execute_gas_limit_multiplier,
+			replacement_fee_bump,
 			forced_parent_hashes,
 			_marker: _,
 		} = self;
@@ -152,16 +177,20 @@ impl<B: BlockT, C, P, CT, BE, A: ChainApi, EC: EthConfig<B, C>> Eth<B, C, P, CT,
 			client,
 			pool,
 			graph,
+			tx_pool,
 			convert_transaction,
 			sync,
 			is_authority,
 			signers,
+			unsafe_signing_rpc,
 			overrides,
 			backend,
 			block_data_cache,
+			pending_block,
 			fee_history_cache,
 			fee_history_cache_limit,
 			execute_gas_limit_multiplier,
+			replacement_fee_bump,
 			forced_parent_hashes,
 			_marker: PhantomData,
 		}
@@ -396,6 +425,22 @@ where
 	async fn send_raw_transaction(&self, bytes: Bytes) -> RpcResult<H256> {
 		self.send_raw_transaction(bytes).await
 	}
+
+	// ########################################################################
+	// Sign
+	// ########################################################################
+
+	fn sign(&self, address: H160, data: Bytes) -> RpcResult<Bytes> {
+		self.sign(address, data)
+	}
+
+	async fn sign_transaction(&self, request: TransactionRequest) -> RpcResult<Bytes> {
+		self.sign_transaction(request).await
+	}
+
+	fn sign_typed_data_v4(&self, address: H160, typed_data: TypedData) -> RpcResult<Bytes> {
+		self.sign_typed_data_v4(address, typed_data)
+	}
 }
 
 fn rich_block_build(
@@ -498,7 +543,7 @@ fn empty_block_from(number: U256) -> ethereum::BlockV2 {
 	ethereum::Block::new(partial_header, Default::default(), ommers)
 }
 
-fn transaction_build(
+pub(crate) fn transaction_build(
 	ethereum_transaction: EthereumTransaction,
 	block: Option<EthereumBlock>,
 	status: Option<TransactionStatus>,
@@ -577,6 +622,7 @@ fn transaction_build(
 fn pending_runtime_api<'a, B: BlockT, C, BE, A: ChainApi>(
 	client: &'a C,
 	graph: &'a Pool<A>,
+	pending_block: &PendingBlockCache<B>,
 ) -> RpcResult<ApiRef<'a, C::Api>>
 where
 	B: BlockT,
@@ -589,18 +635,21 @@ where
 	// In case of Pending, we need an overlayed state to query over.
 	let api = client.runtime_api();
 	let best_hash = client.info().best_hash;
-	// Get all transactions in the ready queue.
-	let xts: Vec<<B as BlockT>::Extrinsic> = graph
-		.validated_pool()
-		.ready()
-		.map(|in_pool_tx| in_pool_tx.data().clone())
-		.collect::<Vec<<B as BlockT>::Extrinsic>>();
+	// Apply the same transactions as the pending block, so that state queries agree with it.
+	let xts: Vec<<B as BlockT>::Extrinsic> = match pending_block.current(client, graph)? {
+		Some(pending) => pending.extrinsics.clone(),
+		None => graph
+			.validated_pool()
+			.ready()
+			.map(|in_pool_tx| in_pool_tx.data().clone())
+			.collect::<Vec<<B as BlockT>::Extrinsic>>(),
+	};
 	// Manually initialize the overlay.
 	if let Ok(Some(header)) = client.header(best_hash) {
 		let parent_hash = *header.parent_hash();
 		api.initialize_block(parent_hash, &header)
 			.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
-		// Apply the ready queue to the best block's state.
+		// Apply the pending transactions to the best block's state.
 		for xt in xts {
 			let _ = api.apply_extrinsic(best_hash, xt);
 		}
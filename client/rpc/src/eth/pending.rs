@@ -0,0 +1,148 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::sync::{Arc, RwLock};
+
+use ethereum::BlockV2 as EthereumBlock;
+use ethereum_types::U256;
+use jsonrpsee::core::RpcResult;
+// Substrate
+use sc_transaction_pool::{ChainApi, Pool};
+use sc_transaction_pool_api::InPoolTransaction;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+// Frontier
+use fp_rpc::{EthereumRuntimeRPCApi, TransactionStatus};
+
+use crate::internal_err;
+
+/// The block the ready queue of the pool would produce on top of the best block.
+pub struct PendingBlock<B: BlockT> {
+	/// Hash of the best block the pending block was built on.
+	pub parent_hash: B::Hash,
+	/// Ready extrinsics the pending block was built from.
+	pub extrinsics: Vec<B::Extrinsic>,
+	pub block: EthereumBlock,
+	pub receipts: Vec<ethereum::ReceiptV3>,
+	pub statuses: Vec<TransactionStatus>,
+	pub base_fee: Option<U256>,
+}
+
+impl<B: BlockT> PendingBlock<B> {
+	fn build<C>(
+		client: &C,
+		parent_hash: B::Hash,
+		extrinsics: Vec<B::Extrinsic>,
+	) -> RpcResult<Option<Self>>
+	where
+		C: ProvideRuntimeApi<B>,
+		C::Api: EthereumRuntimeRPCApi<B>,
+	{
+		let api = client.runtime_api();
+		let (block, statuses) = api
+			.pending_block(parent_hash, extrinsics.clone())
+			.map_err(|_| internal_err(format!("Runtime access error at {}", parent_hash)))?;
+		// The receipts are left in the overlay of `api` by `pending_block`.
+		let receipts = api.current_receipts(parent_hash).map_err(|err| {
+			internal_err(format!("fetch runtime current receipts failed: {:?}", err))
+		})?;
+		let base_fee = api.gas_price(parent_hash).ok();
+
+		Ok(match (block, receipts, statuses) {
+			(Some(block), Some(receipts), Some(statuses)) => Some(Self {
+				parent_hash,
+				extrinsics,
+				block,
+				receipts,
+				statuses,
+				base_fee,
+			}),
+			_ => None,
+		})
+	}
+}
+
+/// Latest pending block, shared by the Eth APIs and refreshed by
+/// [`EthTask::pending_block_task`](crate::EthTask::pending_block_task).
+pub struct PendingBlockCache<B: BlockT>(Arc<RwLock<Option<Arc<PendingBlock<B>>>>>);
+
+impl<B: BlockT> Clone for PendingBlockCache<B> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone())
+	}
+}
+
+impl<B: BlockT> Default for PendingBlockCache<B> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<B: BlockT> PendingBlockCache<B> {
+	pub fn new() -> Self {
+		Self(Arc::new(RwLock::new(None)))
+	}
+
+	/// The pending block on top of the current best block.
+	///
+	/// The cached block is served as is while neither the best block nor the ready queue
+	/// changed, so that all `pending` queries in between see the same block. It is rebuilt
+	/// otherwise.
+	pub fn current<C, A>(
+		&self,
+		client: &C,
+		graph: &Pool<A>,
+	) -> RpcResult<Option<Arc<PendingBlock<B>>>>
+	where
+		C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+		C::Api: EthereumRuntimeRPCApi<B>,
+		A: ChainApi<Block = B>,
+	{
+		let best_hash = client.info().best_hash;
+		let extrinsics = ready_extrinsics(graph);
+		if let Some(pending) = self.cached(best_hash) {
+			if pending.extrinsics == extrinsics {
+				return Ok(Some(pending));
+			}
+		}
+
+		let pending = PendingBlock::build(client, best_hash, extrinsics)?.map(Arc::new);
+		if let Ok(mut cached) = self.0.write() {
+			*cached = pending.clone();
+		}
+		Ok(pending)
+	}
+
+	fn cached(&self, best_hash: B::Hash) -> Option<Arc<PendingBlock<B>>> {
+		self.0
+			.read()
+			.ok()?
+			.as_ref()
+			.filter(|pending| pending.parent_hash == best_hash)
+			.cloned()
+	}
+}
+
+fn ready_extrinsics<A: ChainApi>(graph: &Pool<A>) -> Vec<<A::Block as BlockT>::Extrinsic> {
+	graph
+		.validated_pool()
+		.ready()
+		.map(|in_pool_tx| in_pool_tx.data().clone())
+		.collect()
+}
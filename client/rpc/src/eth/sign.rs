@@ -0,0 +1,344 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::collections::BTreeSet;
+
+use ethereum_types::{H160, H256, U256};
+use jsonrpsee::core::RpcResult;
+use serde_json::Value;
+// Substrate
+use sc_transaction_pool::ChainApi;
+use sp_core::hashing::keccak_256;
+use sp_runtime::traits::Block as BlockT;
+// Frontier
+use fc_rpc_core::types::*;
+
+use crate::{
+	eth::{Eth, EthConfig},
+	internal_err,
+	signer::message_hash,
+};
+
+impl<B: BlockT, C, P, CT, BE, A: ChainApi, EC: EthConfig<B, C>> Eth<B, C, P, CT, BE, A, EC> {
+	pub fn sign(&self, address: H160, data: Bytes) -> RpcResult<Bytes> {
+		self.ensure_signing_rpc()?;
+		self.sign_hash(&message_hash(&data.0), &address)
+	}
+
+	pub fn sign_typed_data_v4(&self, address: H160, typed_data: TypedData) -> RpcResult<Bytes> {
+		self.ensure_signing_rpc()?;
+		let hash = typed_data_hash(&typed_data.0).map_err(internal_err)?;
+		self.sign_hash(&hash, &address)
+	}
+
+	/// Signing RPCs hand out signatures of unlocked keys to any caller, so they are only served
+	/// when explicitly enabled.
+	pub(crate) fn ensure_signing_rpc(&self) -> RpcResult<()> {
+		if self.unsafe_signing_rpc {
+			Ok(())
+		} else {
+			Err(internal_err("signing RPCs are disabled"))
+		}
+	}
+
+	fn sign_hash(&self, hash: &H256, address: &H160) -> RpcResult<Bytes> {
+		let signer = self
+			.signers
+			.iter()
+			.find(|signer| signer.accounts().contains(address))
+			.ok_or_else(|| internal_err("no signer available"))?;
+		Ok(Bytes(signer.sign_hash(hash, address)?.to_vec()))
+	}
+}
+
+/// EIP-712 hash of `typed_data`, `keccak256("\x19\x01" || domainSeparator || hashStruct(message))`.
+pub(crate) fn typed_data_hash(typed_data: &TypedDataPayload) -> Result<H256, String> {
+	let mut message = vec![0x19, 0x01];
+	let domain_separator = hash_struct(typed_data, "EIP712Domain", &typed_data.domain)?;
+	message.extend_from_slice(domain_separator.as_bytes());
+	if typed_data.primary_type != "EIP712Domain" {
+		let message_hash = hash_struct(typed_data, &typed_data.primary_type, &typed_data.message)?;
+		message.extend_from_slice(message_hash.as_bytes());
+	}
+	Ok(H256::from(keccak_256(&message)))
+}
+
+fn hash_struct(typed_data: &TypedDataPayload, name: &str, value: &Value) -> Result<H256, String> {
+	let encoded = encode_data(typed_data, name, value)?;
+	Ok(H256::from(keccak_256(&encoded)))
+}
+
+/// `encodeType`: the struct signature followed by the signatures of the struct types it
+/// references, sorted by name.
+fn encode_type(typed_data: &TypedDataPayload, name: &str) -> Result<String, String> {
+	let mut dependencies = BTreeSet::new();
+	find_dependencies(typed_data, name, &mut dependencies)?;
+	dependencies.remove(name);
+
+	let mut encoded = String::new();
+	for name in std::iter::once(name).chain(dependencies.iter().map(String::as_str)) {
+		let fields = typed_data
+			.types
+			.get(name)
+			.ok_or_else(|| format!("unknown type {}", name))?;
+		let fields = fields
+			.iter()
+			.map(|field| format!("{} {}", field.type_, field.name))
+			.collect::<Vec<_>>();
+		encoded.push_str(&format!("{}({})", name, fields.join(",")));
+	}
+	Ok(encoded)
+}
+
+fn find_dependencies(
+	typed_data: &TypedDataPayload,
+	type_: &str,
+	found: &mut BTreeSet<String>,
+) -> Result<(), String> {
+	let name = base_type(type_);
+	if found.contains(name) || !typed_data.types.contains_key(name) {
+		return Ok(());
+	}
+	found.insert(name.to_string());
+	for field in &typed_data.types[name] {
+		find_dependencies(typed_data, &field.type_, found)?;
+	}
+	Ok(())
+}
+
+/// `encodeData`: the type hash followed by the 32 byte encoding of each member.
+fn encode_data(
+	typed_data: &TypedDataPayload,
+	name: &str,
+	value: &Value,
+) -> Result<Vec<u8>, String> {
+	let fields = typed_data
+		.types
+		.get(name)
+		.ok_or_else(|| format!("unknown type {}", name))?;
+	let mut encoded = keccak_256(encode_type(typed_data, name)?.as_bytes()).to_vec();
+	for field in fields {
+		let member = value.get(&field.name).unwrap_or(&Value::Null);
+		encoded.extend_from_slice(&encode_value(typed_data, &field.type_, member)?);
+	}
+	Ok(encoded)
+}
+
+fn encode_value(
+	typed_data: &TypedDataPayload,
+	type_: &str,
+	value: &Value,
+) -> Result<[u8; 32], String> {
+	if typed_data.types.contains_key(type_) {
+		// A missing struct member is encoded as zero, as other implementations do.
+		return Ok(match value {
+			Value::Null => [0u8; 32],
+			value => keccak_256(&encode_data(typed_data, type_, value)?),
+		});
+	}
+	if value.is_null() {
+		return Err(format!("missing value for a member of type {}", type_));
+	}
+
+	if type_.ends_with(']') {
+		let item_type = &type_[..type_.rfind('[').unwrap_or_default()];
+		let items = value
+			.as_array()
+			.ok_or_else(|| format!("expected an array for {}", type_))?;
+		let mut encoded = Vec::with_capacity(items.len() * 32);
+		for item in items {
+			encoded.extend_from_slice(&encode_value(typed_data, item_type, item)?);
+		}
+		return Ok(keccak_256(&encoded));
+	}
+
+	let mut encoded = [0u8; 32];
+	match type_ {
+		"string" => {
+			let value = value
+				.as_str()
+				.ok_or_else(|| format!("expected a string for {}", type_))?;
+			encoded = keccak_256(value.as_bytes());
+		}
+		"bytes" => encoded = keccak_256(&hex_value(value)?),
+		"bool" => {
+			let value = value
+				.as_bool()
+				.ok_or_else(|| format!("expected a boolean for {}", type_))?;
+			encoded[31] = value as u8;
+		}
+		"address" => {
+			let value = hex_value(value)?;
+			if value.len() != 20 {
+				return Err(format!("invalid address length {}", value.len()));
+			}
+			encoded[12..].copy_from_slice(&value);
+		}
+		_ if type_.starts_with("bytes") => {
+			let value = hex_value(value)?;
+			if value.len() > 32 {
+				return Err(format!("invalid {} length {}", type_, value.len()));
+			}
+			encoded[..value.len()].copy_from_slice(&value);
+		}
+		_ if type_.starts_with("uint") || type_.starts_with("int") => {
+			integer_value(value)?.to_big_endian(&mut encoded);
+		}
+		_ => return Err(format!("unknown type {}", type_)),
+	}
+	Ok(encoded)
+}
+
+/// Type name without array suffixes.
+fn base_type(type_: &str) -> &str {
+	type_.split('[').next().unwrap_or(type_)
+}
+
+fn hex_value(value: &Value) -> Result<Vec<u8>, String> {
+	let value = value
+		.as_str()
+		.ok_or_else(|| "expected a hex string".to_string())?;
+	hex::decode(value.trim_start_matches("0x")).map_err(|err| err.to_string())
+}
+
+/// Integer given as a JSON number or as a decimal or `0x` prefixed hex string, with negative
+/// values in two's complement.
+fn integer_value(value: &Value) -> Result<U256, String> {
+	let (negative, magnitude) = match value {
+		Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+			(Some(n), _) => (false, U256::from(n)),
+			(None, Some(n)) => (true, U256::from(n.unsigned_abs())),
+			_ => return Err(format!("invalid integer {}", number)),
+		},
+		Value::String(s) => {
+			let (negative, digits) = match s.strip_prefix('-') {
+				Some(digits) => (true, digits),
+				None => (false, s.as_str()),
+			};
+			let magnitude = match digits.strip_prefix("0x") {
+				Some(hex) => U256::from_str_radix(hex, 16).map_err(|err| format!("{:?}", err))?,
+				None => U256::from_dec_str(digits).map_err(|err| format!("{:?}", err))?,
+			};
+			(negative, magnitude)
+		}
+		_ => return Err("expected an integer".to_string()),
+	};
+	Ok(if negative {
+		magnitude.overflowing_neg().0
+	} else {
+		magnitude
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Example from EIP-712.
+	const MAIL: &str = r#"{
+		"types": {
+			"EIP712Domain": [
+				{ "name": "name", "type": "string" },
+				{ "name": "version", "type": "string" },
+				{ "name": "chainId", "type": "uint256" },
+				{ "name": "verifyingContract", "type": "address" }
+			],
+			"Person": [
+				{ "name": "name", "type": "string" },
+				{ "name": "wallet", "type": "address" }
+			],
+			"Mail": [
+				{ "name": "from", "type": "Person" },
+				{ "name": "to", "type": "Person" },
+				{ "name": "contents", "type": "string" }
+			]
+		},
+		"primaryType": "Mail",
+		"domain": {
+			"name": "Ether Mail",
+			"version": "1",
+			"chainId": 1,
+			"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+		},
+		"message": {
+			"from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+			"to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+			"contents": "Hello, Bob!"
+		}
+	}"#;
+
+	#[test]
+	fn typed_data_hash_matches_eip712_example() {
+		let typed_data: TypedData = serde_json::from_str(MAIL).unwrap();
+		let typed_data = typed_data.0;
+
+		assert_eq!(
+			encode_type(&typed_data, "Mail").unwrap(),
+			"Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+		);
+		assert_eq!(
+			hash_struct(&typed_data, "EIP712Domain", &typed_data.domain).unwrap(),
+			"0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+				.parse()
+				.unwrap()
+		);
+		assert_eq!(
+			hash_struct(&typed_data, "Mail", &typed_data.message).unwrap(),
+			"0xc52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+				.parse()
+				.unwrap()
+		);
+		assert_eq!(
+			typed_data_hash(&typed_data).unwrap(),
+			"0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+				.parse()
+				.unwrap()
+		);
+	}
+
+	#[test]
+	fn typed_data_encodes_integers_and_arrays() {
+		let typed_data = TypedDataPayload {
+			types: Default::default(),
+			primary_type: "EIP712Domain".into(),
+			domain: Value::Null,
+			message: Value::Null,
+		};
+
+		assert_eq!(
+			encode_value(&typed_data, "int8", &serde_json::json!(-1)).unwrap(),
+			[0xff; 32]
+		);
+		assert_eq!(
+			encode_value(&typed_data, "uint256", &serde_json::json!("0x10")).unwrap(),
+			encode_value(&typed_data, "uint256", &serde_json::json!("16")).unwrap()
+		);
+		assert_eq!(
+			encode_value(&typed_data, "uint8[]", &serde_json::json!([1, 2])).unwrap(),
+			keccak_256(
+				&[
+					encode_value(&typed_data, "uint8", &serde_json::json!(1)).unwrap(),
+					encode_value(&typed_data, "uint8", &serde_json::json!(2)).unwrap(),
+				]
+				.concat()
+			)
+		);
+		assert!(encode_value(&typed_data, "string", &Value::Null).is_err());
+		assert!(encode_value(&typed_data, "address", &serde_json::json!("0x01")).is_err());
+	}
+}
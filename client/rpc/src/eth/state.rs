@@ -18,11 +18,10 @@
 
 use ethereum_types::{H160, H256, U256};
 use jsonrpsee::core::RpcResult;
-use scale_codec::Encode;
 // Substrate
 use sc_client_api::backend::{Backend, StorageProvider};
 use sc_transaction_pool::ChainApi;
-use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::HeaderBackend;
@@ -49,7 +48,11 @@ where
 	pub async fn balance(&self, address: H160, number: Option<BlockNumber>) -> RpcResult<U256> {
 		let number = number.unwrap_or(BlockNumber::Latest);
 		if number == BlockNumber::Pending {
-			let api = pending_runtime_api(self.client.as_ref(), self.graph.as_ref())?;
+			let api = pending_runtime_api(
+				self.client.as_ref(),
+				self.graph.as_ref(),
+				&self.pending_block,
+			)?;
 			Ok(api
 				.account_basic(self.client.info().best_hash, address)
 				.map_err(|err| internal_err(format!("fetch runtime chain id failed: {:?}", err)))?
@@ -85,7 +88,11 @@ where
 	) -> RpcResult<H256> {
 		let number = number.unwrap_or(BlockNumber::Latest);
 		if number == BlockNumber::Pending {
-			let api = pending_runtime_api(self.client.as_ref(), self.graph.as_ref())?;
+			let api = pending_runtime_api(
+				self.client.as_ref(),
+				self.graph.as_ref(),
+				&self.pending_block,
+			)?;
 			Ok(api
 				.storage_at(self.client.info().best_hash, address, index)
 				.unwrap_or_default())
@@ -130,18 +137,8 @@ where
 				})?
 				.nonce;
 
-			let mut current_nonce = nonce;
-			let mut current_tag = (address, nonce).encode();
-			for tx in self.pool.ready() {
-				// since transactions in `ready()` need to be ordered by nonce
-				// it's fine to continue with current iterator.
-				if tx.provides().get(0) == Some(&current_tag) {
-					current_nonce = current_nonce.saturating_add(1.into());
-					current_tag = (address, current_nonce).encode();
-				}
-			}
-
-			return Ok(current_nonce);
+			// Skip over the nonces of the sender's transactions in the ready and future queues.
+			return self.tx_pool.pending_nonce(address, nonce);
 		}
 
 		let id = match frontier_backend_client::native_block_id::<B, C>(
@@ -171,7 +168,11 @@ where
 	pub async fn code_at(&self, address: H160, number: Option<BlockNumber>) -> RpcResult<Bytes> {
 		let number = number.unwrap_or(BlockNumber::Latest);
 		if number == BlockNumber::Pending {
-			let api = pending_runtime_api(self.client.as_ref(), self.graph.as_ref())?;
+			let api = pending_runtime_api(
+				self.client.as_ref(),
+				self.graph.as_ref(),
+				&self.pending_block,
+			)?;
 			Ok(api
 				.account_code_at(self.client.info().best_hash, address)
 				.unwrap_or_default()
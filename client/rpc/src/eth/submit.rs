@@ -16,6 +16,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use ethereum::TransactionV2 as EthereumTransaction;
 use ethereum_types::H256;
 use futures::future::TryFutureExt;
 use jsonrpsee::core::RpcResult;
@@ -49,7 +50,16 @@ where
 	CT: ConvertTransaction<<B as BlockT>::Extrinsic> + 'static,
 	A: ChainApi<Block = B> + 'static,
 {
-	pub async fn send_transaction(&self, request: TransactionRequest) -> RpcResult<H256> {
+	pub async fn sign_transaction(&self, request: TransactionRequest) -> RpcResult<Bytes> {
+		self.ensure_signing_rpc()?;
+		let transaction = self.sign_request(request).await?;
+		Ok(Bytes(
+			ethereum::EnvelopedEncodable::encode(&transaction).to_vec(),
+		))
+	}
+
+	/// Fill in the missing fields of `request` and sign it with the signer holding `from`.
+	async fn sign_request(&self, request: TransactionRequest) -> RpcResult<EthereumTransaction> {
 		let from = match request.from {
 			Some(from) => from,
 			None => {
@@ -138,11 +148,17 @@ where
 			}
 		}
 
-		let transaction = match transaction {
-			Some(transaction) => transaction,
-			None => return Err(internal_err("no signer available")),
-		};
+		match transaction {
+			Some(transaction) => Ok(transaction),
+			None => Err(internal_err("no signer available")),
+		}
+	}
+
+	pub async fn send_transaction(&self, request: TransactionRequest) -> RpcResult<H256> {
+		let transaction = self.sign_request(request).await?;
 		let transaction_hash = transaction.hash();
+		self.tx_pool
+			.ensure_replacement_fee_bump(&transaction, self.replacement_fee_bump)?;
 
 		let block_hash = self.client.info().best_hash;
 		let api_version = match self
@@ -218,6 +234,8 @@ where
 		};
 
 		let transaction_hash = transaction.hash();
+		self.tx_pool
+			.ensure_replacement_fee_bump(&transaction, self.replacement_fee_bump)?;
 
 		let block_hash = self.client.info().best_hash;
 		let api_version = match self
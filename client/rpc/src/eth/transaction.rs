@@ -18,7 +18,7 @@
 
 use std::sync::Arc;
 
-use ethereum::TransactionV2 as EthereumTransaction;
+use ethereum::{BlockV2 as EthereumBlock, TransactionV2 as EthereumTransaction};
 use ethereum_types::{H256, U256, U64};
 use jsonrpsee::core::RpcResult;
 // Substrate
@@ -31,7 +31,7 @@ use sp_core::hashing::keccak_256;
 use sp_runtime::traits::Block as BlockT;
 // Frontier
 use fc_rpc_core::types::*;
-use fp_rpc::EthereumRuntimeRPCApi;
+use fp_rpc::{EthereumRuntimeRPCApi, TransactionStatus};
 
 use crate::{
 	eth::{transaction_build, Eth, EthConfig},
@@ -283,7 +283,31 @@ where
 		.map_err(|err| internal_err(format!("{:?}", err)))?
 		{
 			Some((hash, index)) => (hash, index as usize),
-			None => return Ok(None),
+			None => {
+				// Not mapped in the db yet, the transaction may be in the pending block.
+				let pending = self
+					.pending_block
+					.current(client.as_ref(), self.graph.as_ref())?;
+				return match pending.and_then(|pending| {
+					let index = pending
+						.statuses
+						.iter()
+						.position(|status| status.transaction_hash == hash)?;
+					Some((pending, index))
+				}) {
+					Some((pending, index)) => receipt_build(
+						index,
+						pending.block.clone(),
+						pending.statuses.clone(),
+						pending.receipts.clone(),
+						true,
+						pending.base_fee.unwrap_or_default(),
+						None,
+					)
+					.map(Some),
+					None => Ok(None),
+				};
+			}
 		};
 
 		let substrate_hash = match frontier_backend_client::load_hash::<B, C>(
@@ -315,138 +339,159 @@ where
 		match (block, statuses, receipts) {
 			(Some(block), Some(statuses), Some(receipts)) => {
 				let block_hash = H256::from(keccak_256(&rlp::encode(&block.header)));
-				let receipt = receipts[index].clone();
+				let base_fee = client
+					.runtime_api()
+					.gas_price(substrate_hash)
+					.unwrap_or_default();
+				receipt_build(
+					index,
+					block,
+					statuses,
+					receipts,
+					is_eip1559,
+					base_fee,
+					Some(block_hash),
+				)
+				.map(Some)
+			}
+			_ => Ok(None),
+		}
+	}
+}
 
-				let (logs, logs_bloom, status_code, cumulative_gas_used, gas_used) = if !is_eip1559
-				{
-					// Pre-london frontier update stored receipts require cumulative gas calculation.
-					match receipt {
-						ethereum::ReceiptV3::Legacy(ref d) => {
-							let index = core::cmp::min(receipts.len(), index + 1);
-							let cumulative_gas: u32 = receipts[..index]
-								.iter()
-								.map(|r| match r {
-									ethereum::ReceiptV3::Legacy(d) => Ok(d.used_gas.as_u32()),
-									_ => Err(internal_err(format!(
-										"Unknown receipt for request {}",
-										hash
-									))),
-								})
-								.sum::<RpcResult<u32>>()?;
-							(
-								d.logs.clone(),
-								d.logs_bloom,
-								d.status_code,
-								U256::from(cumulative_gas),
-								d.used_gas,
-							)
-						}
-						_ => {
-							return Err(internal_err(format!(
-								"Unknown receipt for request {}",
-								hash
-							)))
-						}
-					}
+/// Receipt of the transaction at `index` of `block`. `block_hash` is `None` for the pending block.
+fn receipt_build(
+	index: usize,
+	block: EthereumBlock,
+	statuses: Vec<TransactionStatus>,
+	receipts: Vec<ethereum::ReceiptV3>,
+	is_eip1559: bool,
+	base_fee: U256,
+	block_hash: Option<H256>,
+) -> RpcResult<Receipt> {
+	let receipt = receipts[index].clone();
+	let transaction_hash = statuses[index].transaction_hash;
+
+	let (logs, logs_bloom, status_code, cumulative_gas_used, gas_used) = if !is_eip1559 {
+		// Pre-london frontier update stored receipts require cumulative gas calculation.
+		match receipt {
+			ethereum::ReceiptV3::Legacy(ref d) => {
+				let index = core::cmp::min(receipts.len(), index + 1);
+				let cumulative_gas: u32 = receipts[..index]
+					.iter()
+					.map(|r| match r {
+						ethereum::ReceiptV3::Legacy(d) => Ok(d.used_gas.as_u32()),
+						_ => Err(internal_err(format!(
+							"Unknown receipt for request {}",
+							transaction_hash
+						))),
+					})
+					.sum::<RpcResult<u32>>()?;
+				(
+					d.logs.clone(),
+					d.logs_bloom,
+					d.status_code,
+					U256::from(cumulative_gas),
+					d.used_gas,
+				)
+			}
+			_ => {
+				return Err(internal_err(format!(
+					"Unknown receipt for request {}",
+					transaction_hash
+				)))
+			}
+		}
+	} else {
+		match receipt {
+			ethereum::ReceiptV3::Legacy(ref d)
+			| ethereum::ReceiptV3::EIP2930(ref d)
+			| ethereum::ReceiptV3::EIP1559(ref d) => {
+				let cumulative_gas = d.used_gas;
+				let gas_used = if index > 0 {
+					let previous_receipt = receipts[index - 1].clone();
+					let previous_gas_used = match previous_receipt {
+						ethereum::ReceiptV3::Legacy(d)
+						| ethereum::ReceiptV3::EIP2930(d)
+						| ethereum::ReceiptV3::EIP1559(d) => d.used_gas,
+					};
+					cumulative_gas.saturating_sub(previous_gas_used)
 				} else {
-					match receipt {
-						ethereum::ReceiptV3::Legacy(ref d)
-						| ethereum::ReceiptV3::EIP2930(ref d)
-						| ethereum::ReceiptV3::EIP1559(ref d) => {
-							let cumulative_gas = d.used_gas;
-							let gas_used = if index > 0 {
-								let previous_receipt = receipts[index - 1].clone();
-								let previous_gas_used = match previous_receipt {
-									ethereum::ReceiptV3::Legacy(d)
-									| ethereum::ReceiptV3::EIP2930(d)
-									| ethereum::ReceiptV3::EIP1559(d) => d.used_gas,
-								};
-								cumulative_gas.saturating_sub(previous_gas_used)
-							} else {
-								cumulative_gas
-							};
-							(
-								d.logs.clone(),
-								d.logs_bloom,
-								d.status_code,
-								cumulative_gas,
-								gas_used,
-							)
-						}
-					}
-				};
-
-				let status = statuses[index].clone();
-				let mut cumulative_receipts = receipts;
-				cumulative_receipts.truncate((status.transaction_index + 1) as usize);
-				let transaction = block.transactions[index].clone();
-				let effective_gas_price = match transaction {
-					EthereumTransaction::Legacy(t) => t.gas_price,
-					EthereumTransaction::EIP2930(t) => t.gas_price,
-					EthereumTransaction::EIP1559(t) => client
-						.runtime_api()
-						.gas_price(substrate_hash)
-						.unwrap_or_default()
-						.checked_add(t.max_priority_fee_per_gas)
-						.unwrap_or_else(U256::max_value)
-						.min(t.max_fee_per_gas),
+					cumulative_gas
 				};
-
-				return Ok(Some(Receipt {
-					transaction_hash: Some(status.transaction_hash),
-					transaction_index: Some(status.transaction_index.into()),
-					block_hash: Some(block_hash),
-					from: Some(status.from),
-					to: status.to,
-					block_number: Some(block.header.number),
-					cumulative_gas_used,
-					gas_used: Some(gas_used),
-					contract_address: status.contract_address,
-					logs: {
-						let mut pre_receipts_log_index = None;
-						if cumulative_receipts.len() > 0 {
-							cumulative_receipts.truncate(cumulative_receipts.len() - 1);
-							pre_receipts_log_index = Some(
-								cumulative_receipts
-									.iter()
-									.map(|r| match r {
-										ethereum::ReceiptV3::Legacy(d)
-										| ethereum::ReceiptV3::EIP2930(d)
-										| ethereum::ReceiptV3::EIP1559(d) => d.logs.len() as u32,
-									})
-									.sum::<u32>(),
-							);
-						}
-						logs.iter()
-							.enumerate()
-							.map(|(i, log)| Log {
-								address: log.address,
-								topics: log.topics.clone(),
-								data: Bytes(log.data.clone()),
-								block_hash: Some(block_hash),
-								block_number: Some(block.header.number),
-								transaction_hash: Some(status.transaction_hash),
-								transaction_index: Some(status.transaction_index.into()),
-								log_index: Some(U256::from(
-									(pre_receipts_log_index.unwrap_or(0)) + i as u32,
-								)),
-								transaction_log_index: Some(U256::from(i)),
-								removed: false,
-							})
-							.collect()
-					},
-					status_code: Some(U64::from(status_code)),
-					logs_bloom,
-					state_root: None,
-					effective_gas_price,
-					transaction_type: match receipt {
-						ethereum::ReceiptV3::Legacy(_) => U256::from(0),
-						ethereum::ReceiptV3::EIP2930(_) => U256::from(1),
-						ethereum::ReceiptV3::EIP1559(_) => U256::from(2),
-					},
-				}));
+				(
+					d.logs.clone(),
+					d.logs_bloom,
+					d.status_code,
+					cumulative_gas,
+					gas_used,
+				)
 			}
-			_ => Ok(None),
 		}
-	}
+	};
+
+	let status = statuses[index].clone();
+	let mut cumulative_receipts = receipts;
+	cumulative_receipts.truncate((status.transaction_index + 1) as usize);
+	let transaction = block.transactions[index].clone();
+	let effective_gas_price = match transaction {
+		EthereumTransaction::Legacy(t) => t.gas_price,
+		EthereumTransaction::EIP2930(t) => t.gas_price,
+		EthereumTransaction::EIP1559(t) => base_fee
+			.checked_add(t.max_priority_fee_per_gas)
+			.unwrap_or_else(U256::max_value)
+			.min(t.max_fee_per_gas),
+	};
+
+	Ok(Receipt {
+		transaction_hash: Some(status.transaction_hash),
+		transaction_index: Some(status.transaction_index.into()),
+		block_hash,
+		from: Some(status.from),
+		to: status.to,
+		block_number: Some(block.header.number),
+		cumulative_gas_used,
+		gas_used: Some(gas_used),
+		contract_address: status.contract_address,
+		logs: {
+			let mut pre_receipts_log_index = None;
+			if cumulative_receipts.len() > 0 {
+				cumulative_receipts.truncate(cumulative_receipts.len() - 1);
+				pre_receipts_log_index = Some(
+					cumulative_receipts
+						.iter()
+						.map(|r| match r {
+							ethereum::ReceiptV3::Legacy(d)
+							| ethereum::ReceiptV3::EIP2930(d)
+							| ethereum::ReceiptV3::EIP1559(d) => d.logs.len() as u32,
+						})
+						.sum::<u32>(),
+				);
+			}
+			logs.iter()
+				.enumerate()
+				.map(|(i, log)| Log {
+					address: log.address,
+					topics: log.topics.clone(),
+					data: Bytes(log.data.clone()),
+					block_hash,
+					block_number: Some(block.header.number),
+					transaction_hash: Some(status.transaction_hash),
+					transaction_index: Some(status.transaction_index.into()),
+					log_index: Some(U256::from((pre_receipts_log_index.unwrap_or(0)) + i as u32)),
+					transaction_log_index: Some(U256::from(i)),
+					removed: false,
+				})
+				.collect()
+		},
+		status_code: Some(U64::from(status_code)),
+		logs_bloom,
+		state_root: None,
+		effective_gas_price,
+		transaction_type: match receipt {
+			ethereum::ReceiptV3::Legacy(_) => U256::from(0),
+			ethereum::ReceiptV3::EIP2930(_) => U256::from(1),
+			ethereum::ReceiptV3::EIP1559(_) => U256::from(2),
+		},
+	})
 }
@@ -39,7 +39,10 @@ use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
 use fc_mapping_sync::{EthereumBlockNotification, EthereumBlockNotificationSinks};
 use fc_rpc_core::{
 	types::{
-		pubsub::{Kind, Params, PubSubSyncStatus, Result as PubSubResult, SyncStatusMetadata},
+		pubsub::{
+			Kind, Params, PubSubSyncStatus, Result as PubSubResult, SyncStatusMetadata,
+			TransactionsParams,
+		},
 		Bytes, FilteredParams, Header, Log, Rich,
 	},
 	EthPubSubApiServer,
@@ -47,6 +50,8 @@ use fc_rpc_core::{
 use fc_storage::OverrideHandle;
 use fp_rpc::EthereumRuntimeRPCApi;
 
+use crate::eth::transaction_build;
+
 #[derive(Debug)]
 pub struct EthereumSubIdProvider;
 
@@ -165,6 +170,25 @@ impl EthSubscriptionResult {
 		}
 		logs
 	}
+	pub fn pending_transaction(
+		transaction: EthereumTransaction,
+		params: &TransactionsParams,
+	) -> Option<PubSubResult> {
+		// Only recover the sender when needed.
+		if !params.full_transactions && !params.has_address_filter() {
+			return Some(PubSubResult::TransactionHash(transaction.hash()));
+		}
+
+		let transaction = transaction_build(transaction, None, None, None);
+		if !params.matches(&transaction.from, transaction.to.as_ref()) {
+			return None;
+		}
+		Some(if params.full_transactions {
+			PubSubResult::Transaction(Box::new(transaction))
+		} else {
+			PubSubResult::TransactionHash(transaction.hash)
+		})
+	}
 	fn add_log(
 		block_hash: H256,
 		ethereum_log: &ethereum::Log,
@@ -216,9 +240,10 @@ where
 	) -> SubscriptionResult {
 		sink.accept()?;
 
-		let filtered_params = match params {
-			Some(Params::Logs(filter)) => FilteredParams::new(Some(filter)),
-			_ => FilteredParams::default(),
+		let (filtered_params, transactions_params) = match params {
+			Some(Params::Logs(filter)) => (FilteredParams::new(Some(filter)), Default::default()),
+			Some(Params::Transactions(params)) => (FilteredParams::default(), params),
+			_ => (FilteredParams::default(), Default::default()),
 		};
 
 		let client = self.client.clone();
@@ -341,7 +366,12 @@ where
 								futures::future::ready(None)
 							}
 						})
-						.map(|transaction| PubSubResult::TransactionHash(transaction.hash()));
+						.filter_map(move |transaction| {
+							futures::future::ready(EthSubscriptionResult::pending_transaction(
+								transaction,
+								&transactions_params,
+							))
+						});
 					sink.pipe_from_stream(stream).await;
 				}
 				Kind::Syncing => {
@@ -0,0 +1,260 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+	collections::BTreeMap,
+	fs, io,
+	path::{Path, PathBuf},
+	str::FromStr,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+use ethereum::TransactionV2 as EthereumTransaction;
+use ethereum_types::{H160, H256};
+use jsonrpsee::core::Error;
+// Frontier
+use fc_rpc_core::types::TransactionMessage;
+
+use crate::{
+	internal_err,
+	signer::{secret_key_address, sign_hash, sign_transaction, EthSigner},
+};
+
+struct UnlockedKey {
+	secret: libsecp256k1::SecretKey,
+	/// `None` keeps the key unlocked until it is locked explicitly.
+	expires: Option<Instant>,
+}
+
+/// Signer backed by a directory of Web3 Secret Storage (JSON keystore v3) files, with scrypt
+/// or pbkdf2 key derivation.
+///
+/// Keys stay encrypted on disk. They are decrypted in memory by [`Self::unlock`] until locked
+/// again or until their unlock duration runs out, and only unlocked keys can sign.
+#[derive(Clone)]
+pub struct EthKeystoreSigner {
+	keyfiles: Arc<BTreeMap<H160, PathBuf>>,
+	unlocked: Arc<Mutex<BTreeMap<H160, UnlockedKey>>>,
+}
+
+impl EthKeystoreSigner {
+	/// Load the keyfiles in `path`. Files without a valid `address` field are skipped.
+	pub fn open(path: &Path) -> io::Result<Self> {
+		let mut keyfiles = BTreeMap::new();
+		for entry in fs::read_dir(path)? {
+			let path = entry?.path();
+			if !path.is_file() {
+				continue;
+			}
+			match keyfile_address(&path) {
+				Some(address) => {
+					keyfiles.insert(address, path);
+				}
+				None => log::warn!(
+					target: "eth-keystore",
+					"Skipping {}, not a keystore file",
+					path.display(),
+				),
+			}
+		}
+
+		Ok(Self {
+			keyfiles: Arc::new(keyfiles),
+			unlocked: Arc::new(Mutex::new(BTreeMap::new())),
+		})
+	}
+
+	/// Decrypt the key of `address` and keep it for `duration`, or until [`Self::lock`] if
+	/// `None`.
+	pub fn unlock(
+		&self,
+		address: &H160,
+		password: &str,
+		duration: Option<Duration>,
+	) -> Result<(), Error> {
+		let secret = self.decrypt(address, password)?;
+		let expires = duration.map(|duration| Instant::now() + duration);
+		self.unlocked
+			.lock()
+			.map_err(|_| internal_err("keystore is not available"))?
+			.insert(*address, UnlockedKey { secret, expires });
+		Ok(())
+	}
+
+	/// Forget the decrypted key of `address`. Returns whether it was unlocked.
+	pub fn lock(&self, address: &H160) -> Result<bool, Error> {
+		Ok(self
+			.unlocked
+			.lock()
+			.map_err(|_| internal_err("keystore is not available"))?
+			.remove(address)
+			.is_some())
+	}
+
+	/// Sign `hash` with the key of `address`, decrypting it with `password` for this call only.
+	pub fn sign_hash_with_password(
+		&self,
+		hash: &H256,
+		address: &H160,
+		password: &str,
+	) -> Result<[u8; 65], Error> {
+		Ok(sign_hash(hash, &self.decrypt(address, password)?))
+	}
+
+	fn decrypt(&self, address: &H160, password: &str) -> Result<libsecp256k1::SecretKey, Error> {
+		let path = self
+			.keyfiles
+			.get(address)
+			.ok_or_else(|| internal_err("unknown account"))?;
+		// Do not tell a wrong password apart from a corrupted file.
+		let secret = eth_keystore::decrypt_key(path, password)
+			.ok()
+			.and_then(|secret| libsecp256k1::SecretKey::parse_slice(&secret).ok())
+			.ok_or_else(|| internal_err("could not decrypt key with given password"))?;
+		if &secret_key_address(&secret) != address {
+			return Err(internal_err("keyfile address does not match its key"));
+		}
+		Ok(secret)
+	}
+
+	fn unlocked_key(&self, address: &H160) -> Result<libsecp256k1::SecretKey, Error> {
+		let mut unlocked = self
+			.unlocked
+			.lock()
+			.map_err(|_| internal_err("keystore is not available"))?;
+		let now = Instant::now();
+		unlocked.retain(|_, key| key.expires.map_or(true, |expires| expires > now));
+		unlocked
+			.get(address)
+			.map(|key| key.secret)
+			.ok_or_else(|| internal_err("authentication needed: password or unlock"))
+	}
+}
+
+impl EthSigner for EthKeystoreSigner {
+	fn accounts(&self) -> Vec<H160> {
+		self.keyfiles.keys().cloned().collect()
+	}
+
+	fn sign(
+		&self,
+		message: TransactionMessage,
+		address: &H160,
+	) -> Result<EthereumTransaction, Error> {
+		sign_transaction(message, &self.unlocked_key(address)?)
+	}
+
+	fn sign_hash(&self, hash: &H256, address: &H160) -> Result<[u8; 65], Error> {
+		Ok(sign_hash(hash, &self.unlocked_key(address)?))
+	}
+}
+
+fn keyfile_address(path: &Path) -> Option<H160> {
+	let keyfile: serde_json::Value = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+	let address = keyfile.get("address")?.as_str()?;
+	H160::from_str(address.trim_start_matches("0x")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::signer::public_key_address;
+	use tempfile::tempdir;
+
+	// Keyfiles for the keys 0x2222... (pbkdf2) and 0x3333... (scrypt), encrypted with the
+	// password "frontier" and minimal key derivation costs.
+	const PBKDF2_KEYFILE: &str = r#"{"address": "1563915e194d8cfba1943570603f7606a3115508", "crypto": {"cipher": "aes-128-ctr", "cipherparams": {"iv": "101112131415161718191a1b1c1d1e1f"}, "ciphertext": "a7e75259c4058f7f6208a5186694895a140be76c5e21b2e0d737770b2f63cac6", "kdf": "pbkdf2", "kdfparams": {"c": 2, "dklen": 32, "prf": "hmac-sha256", "salt": "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"}, "mac": "03d0bddf985c3ba75cfbf87283ac7feb3e03676de400a533ffcd8e043ad21204"}, "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6", "version": 3}"#;
+	const SCRYPT_KEYFILE: &str = r#"{"address": "5cbdd86a2fa8dc4bddd8a8f69dba48572eec07fb", "crypto": {"cipher": "aes-128-ctr", "cipherparams": {"iv": "101112131415161718191a1b1c1d1e1f"}, "ciphertext": "4c288ac863320ea46564491de4f400d1b56db3aadf0a9d40e788f2f2120f9bdd", "kdf": "scrypt", "kdfparams": {"dklen": 32, "n": 2, "p": 1, "r": 8, "salt": "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f"}, "mac": "4973db5bfb7bfee2c8bcced77f01b22a4be91c52026882758655576544280605"}, "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6", "version": 3}"#;
+
+	fn open_keystore() -> (tempfile::TempDir, EthKeystoreSigner) {
+		let dir = tempdir().expect("create a temp dir");
+		fs::write(dir.path().join("pbkdf2.json"), PBKDF2_KEYFILE).unwrap();
+		fs::write(dir.path().join("scrypt.json"), SCRYPT_KEYFILE).unwrap();
+		fs::write(dir.path().join("README"), "not a keyfile").unwrap();
+		let signer = EthKeystoreSigner::open(dir.path()).expect("open keystore");
+		(dir, signer)
+	}
+
+	fn recover(hash: &H256, signature: &[u8; 65]) -> H160 {
+		let message = libsecp256k1::Message::parse(&hash.0);
+		let rs = libsecp256k1::Signature::parse_standard_slice(&signature[0..64]).unwrap();
+		let v = libsecp256k1::RecoveryId::parse(signature[64] - 27).unwrap();
+		public_key_address(&libsecp256k1::recover(&message, &rs, &v).unwrap())
+	}
+
+	#[test]
+	fn keystore_lists_keyfile_accounts() {
+		let (_dir, signer) = open_keystore();
+		assert_eq!(
+			signer.accounts(),
+			vec![
+				H160::from_str("1563915e194d8cfba1943570603f7606a3115508").unwrap(),
+				H160::from_str("5cbdd86a2fa8dc4bddd8a8f69dba48572eec07fb").unwrap(),
+			]
+		);
+	}
+
+	#[test]
+	fn keystore_signs_only_with_unlocked_keys() {
+		let (_dir, signer) = open_keystore();
+		let hash = H256::repeat_byte(0xaa);
+
+		for address in signer.accounts() {
+			assert!(EthSigner::sign_hash(&signer, &hash, &address).is_err());
+			assert!(signer.unlock(&address, "wrong", None).is_err());
+
+			signer.unlock(&address, "frontier", None).unwrap();
+			let signature = EthSigner::sign_hash(&signer, &hash, &address).unwrap();
+			assert_eq!(recover(&hash, &signature), address);
+
+			assert!(signer.lock(&address).unwrap());
+			assert!(EthSigner::sign_hash(&signer, &hash, &address).is_err());
+		}
+	}
+
+	#[test]
+	fn keystore_unlock_expires() {
+		let (_dir, signer) = open_keystore();
+		let hash = H256::repeat_byte(0xaa);
+		let address = signer.accounts()[0];
+
+		signer
+			.unlock(&address, "frontier", Some(Duration::ZERO))
+			.unwrap();
+		assert!(EthSigner::sign_hash(&signer, &hash, &address).is_err());
+		assert!(!signer.lock(&address).unwrap());
+	}
+
+	#[test]
+	fn keystore_signs_with_password() {
+		let (_dir, signer) = open_keystore();
+		let hash = H256::repeat_byte(0xaa);
+		let address = signer.accounts()[1];
+
+		assert!(signer
+			.sign_hash_with_password(&hash, &address, "wrong")
+			.is_err());
+		let signature = signer
+			.sign_hash_with_password(&hash, &address, "frontier")
+			.unwrap();
+		assert_eq!(recover(&hash, &signature), address);
+		// Signing with a password does not unlock the account.
+		assert!(EthSigner::sign_hash(&signer, &hash, &address).is_err());
+	}
+}
@@ -28,15 +28,22 @@
 
 mod eth;
 mod eth_pubsub;
+mod keystore;
 mod net;
+mod personal;
 mod signer;
 mod txpool;
 mod web3;
 
 pub use self::{
-	eth::{format, EstimateGasAdapter, Eth, EthBlockDataCacheTask, EthConfig, EthFilter, EthTask},
+	eth::{
+		format, EstimateGasAdapter, Eth, EthBlockDataCacheTask, EthConfig, EthFilter, EthTask,
+		PendingBlock, PendingBlockCache,
+	},
 	eth_pubsub::{EthPubSub, EthereumSubIdProvider},
+	keystore::EthKeystoreSigner,
 	net::Net,
+	personal::Personal,
 	signer::{EthDevSigner, EthSigner},
 	txpool::TxPool,
 	web3::Web3,
@@ -44,8 +51,8 @@ pub use self::{
 
 pub use ethereum::TransactionV2 as EthereumTransaction;
 pub use fc_rpc_core::{
-	EthApiServer, EthFilterApiServer, EthPubSubApiServer, NetApiServer, TxPoolApiServer,
-	Web3ApiServer,
+	EthApiServer, EthFilterApiServer, EthPubSubApiServer, NetApiServer, PersonalApiServer,
+	TxPoolApiServer, Web3ApiServer,
 };
 pub use fc_storage::{
 	OverrideHandle, RuntimeApiStorageOverride, SchemaV1Override, SchemaV2Override,
@@ -0,0 +1,75 @@
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+use ethereum_types::H160;
+use jsonrpsee::core::RpcResult;
+// Frontier
+use fc_rpc_core::{types::Bytes, PersonalApiServer};
+
+use crate::{
+	keystore::EthKeystoreSigner,
+	signer::{message_hash, EthSigner},
+};
+
+/// Unlock duration when `personal_unlockAccount` is called without one.
+const DEFAULT_UNLOCK_DURATION: u64 = 300;
+
+/// Personal API implementation.
+pub struct Personal {
+	keystore: EthKeystoreSigner,
+}
+
+impl Personal {
+	pub fn new(keystore: EthKeystoreSigner) -> Self {
+		Self { keystore }
+	}
+}
+
+impl PersonalApiServer for Personal {
+	fn list_accounts(&self) -> RpcResult<Vec<H160>> {
+		Ok(self.keystore.accounts())
+	}
+
+	fn unlock_account(
+		&self,
+		address: H160,
+		password: String,
+		duration: Option<u64>,
+	) -> RpcResult<bool> {
+		let duration = match duration.unwrap_or(DEFAULT_UNLOCK_DURATION) {
+			0 => None,
+			seconds => Some(Duration::from_secs(seconds)),
+		};
+		self.keystore.unlock(&address, &password, duration)?;
+		Ok(true)
+	}
+
+	fn lock_account(&self, address: H160) -> RpcResult<bool> {
+		self.keystore.lock(&address)
+	}
+
+	fn sign(&self, data: Bytes, address: H160, password: String) -> RpcResult<Bytes> {
+		let hash = message_hash(&data.0);
+		let signature = self
+			.keystore
+			.sign_hash_with_password(&hash, &address, &password)?;
+		Ok(Bytes(signature.to_vec()))
+	}
+}
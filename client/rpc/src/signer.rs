@@ -36,6 +36,11 @@ pub trait EthSigner: Send + Sync {
 		message: TransactionMessage,
 		address: &H160,
 	) -> Result<EthereumTransaction, Error>;
+	/// Sign a 32 byte hash using the given account, returning the signature as `r || s || v`
+	/// with `v` being 27 or 28.
+	fn sign_hash(&self, _hash: &H256, _address: &H160) -> Result<[u8; 65], Error> {
+		Err(internal_err("signer does not support signing messages"))
+	}
 }
 
 pub struct EthDevSigner {
@@ -53,19 +58,114 @@ impl EthDevSigner {
 			.expect("Test key is valid; qed")],
 		}
 	}
+
+	fn key(&self, address: &H160) -> Result<&libsecp256k1::SecretKey, Error> {
+		self.keys
+			.iter()
+			.find(|secret| &secret_key_address(secret) == address)
+			.ok_or_else(|| internal_err("signer not available"))
+	}
 }
 
-fn secret_key_address(secret: &libsecp256k1::SecretKey) -> H160 {
+pub(crate) fn secret_key_address(secret: &libsecp256k1::SecretKey) -> H160 {
 	let public = libsecp256k1::PublicKey::from_secret_key(secret);
 	public_key_address(&public)
 }
 
-fn public_key_address(public: &libsecp256k1::PublicKey) -> H160 {
+pub(crate) fn public_key_address(public: &libsecp256k1::PublicKey) -> H160 {
 	let mut res = [0u8; 64];
 	res.copy_from_slice(&public.serialize()[1..65]);
 	H160::from(H256::from(keccak_256(&res)))
 }
 
+/// Hash of `data` as signed by `eth_sign` and `personal_sign`.
+pub(crate) fn message_hash(data: &[u8]) -> H256 {
+	let mut message = format!("\x19Ethereum Signed Message:\n{}", data.len()).into_bytes();
+	message.extend_from_slice(data);
+	H256::from(keccak_256(&message))
+}
+
+pub(crate) fn sign_hash(hash: &H256, secret: &libsecp256k1::SecretKey) -> [u8; 65] {
+	let (signature, recid) = libsecp256k1::sign(&libsecp256k1::Message::parse(&hash.0), secret);
+	let mut res = [0u8; 65];
+	res[0..64].copy_from_slice(&signature.serialize());
+	res[64] = 27 + recid.serialize();
+	res
+}
+
+pub(crate) fn sign_transaction(
+	message: TransactionMessage,
+	secret: &libsecp256k1::SecretKey,
+) -> Result<EthereumTransaction, Error> {
+	match message {
+		TransactionMessage::Legacy(m) => {
+			let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
+				.map_err(|_| internal_err("invalid signing message"))?;
+			let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+			let v = match m.chain_id {
+				None => 27 + recid.serialize() as u64,
+				Some(chain_id) => 2 * chain_id + 35 + recid.serialize() as u64,
+			};
+			let rs = signature.serialize();
+			let r = H256::from_slice(&rs[0..32]);
+			let s = H256::from_slice(&rs[32..64]);
+			Ok(EthereumTransaction::Legacy(ethereum::LegacyTransaction {
+				nonce: m.nonce,
+				gas_price: m.gas_price,
+				gas_limit: m.gas_limit,
+				action: m.action,
+				value: m.value,
+				input: m.input,
+				signature: ethereum::TransactionSignature::new(v, r, s)
+					.ok_or_else(|| internal_err("signer generated invalid signature"))?,
+			}))
+		}
+		TransactionMessage::EIP2930(m) => {
+			let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
+				.map_err(|_| internal_err("invalid signing message"))?;
+			let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+			let rs = signature.serialize();
+			let r = H256::from_slice(&rs[0..32]);
+			let s = H256::from_slice(&rs[32..64]);
+			Ok(EthereumTransaction::EIP2930(ethereum::EIP2930Transaction {
+				chain_id: m.chain_id,
+				nonce: m.nonce,
+				gas_price: m.gas_price,
+				gas_limit: m.gas_limit,
+				action: m.action,
+				value: m.value,
+				input: m.input.clone(),
+				access_list: m.access_list,
+				odd_y_parity: recid.serialize() != 0,
+				r,
+				s,
+			}))
+		}
+		TransactionMessage::EIP1559(m) => {
+			let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
+				.map_err(|_| internal_err("invalid signing message"))?;
+			let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
+			let rs = signature.serialize();
+			let r = H256::from_slice(&rs[0..32]);
+			let s = H256::from_slice(&rs[32..64]);
+			Ok(EthereumTransaction::EIP1559(ethereum::EIP1559Transaction {
+				chain_id: m.chain_id,
+				nonce: m.nonce,
+				max_priority_fee_per_gas: m.max_priority_fee_per_gas,
+				max_fee_per_gas: m.max_fee_per_gas,
+				gas_limit: m.gas_limit,
+				action: m.action,
+				value: m.value,
+				input: m.input.clone(),
+				access_list: m.access_list,
+				odd_y_parity: recid.serialize() != 0,
+				r,
+				s,
+			}))
+		}
+	}
+}
+
 impl EthSigner for EthDevSigner {
 	fn accounts(&self) -> Vec<H160> {
 		self.keys.iter().map(secret_key_address).collect()
@@ -76,88 +176,10 @@ impl EthSigner for EthDevSigner {
 		message: TransactionMessage,
 		address: &H160,
 	) -> Result<EthereumTransaction, Error> {
-		let mut transaction = None;
-
-		for secret in &self.keys {
-			let key_address = secret_key_address(secret);
-
-			if &key_address == address {
-				match message {
-					TransactionMessage::Legacy(m) => {
-						let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
-							.map_err(|_| internal_err("invalid signing message"))?;
-						let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
-						let v = match m.chain_id {
-							None => 27 + recid.serialize() as u64,
-							Some(chain_id) => 2 * chain_id + 35 + recid.serialize() as u64,
-						};
-						let rs = signature.serialize();
-						let r = H256::from_slice(&rs[0..32]);
-						let s = H256::from_slice(&rs[32..64]);
-						transaction =
-							Some(EthereumTransaction::Legacy(ethereum::LegacyTransaction {
-								nonce: m.nonce,
-								gas_price: m.gas_price,
-								gas_limit: m.gas_limit,
-								action: m.action,
-								value: m.value,
-								input: m.input,
-								signature: ethereum::TransactionSignature::new(v, r, s)
-									.ok_or_else(|| {
-										internal_err("signer generated invalid signature")
-									})?,
-							}));
-					}
-					TransactionMessage::EIP2930(m) => {
-						let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
-							.map_err(|_| internal_err("invalid signing message"))?;
-						let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
-						let rs = signature.serialize();
-						let r = H256::from_slice(&rs[0..32]);
-						let s = H256::from_slice(&rs[32..64]);
-						transaction =
-							Some(EthereumTransaction::EIP2930(ethereum::EIP2930Transaction {
-								chain_id: m.chain_id,
-								nonce: m.nonce,
-								gas_price: m.gas_price,
-								gas_limit: m.gas_limit,
-								action: m.action,
-								value: m.value,
-								input: m.input.clone(),
-								access_list: m.access_list,
-								odd_y_parity: recid.serialize() != 0,
-								r,
-								s,
-							}));
-					}
-					TransactionMessage::EIP1559(m) => {
-						let signing_message = libsecp256k1::Message::parse_slice(&m.hash()[..])
-							.map_err(|_| internal_err("invalid signing message"))?;
-						let (signature, recid) = libsecp256k1::sign(&signing_message, secret);
-						let rs = signature.serialize();
-						let r = H256::from_slice(&rs[0..32]);
-						let s = H256::from_slice(&rs[32..64]);
-						transaction =
-							Some(EthereumTransaction::EIP1559(ethereum::EIP1559Transaction {
-								chain_id: m.chain_id,
-								nonce: m.nonce,
-								max_priority_fee_per_gas: m.max_priority_fee_per_gas,
-								max_fee_per_gas: m.max_fee_per_gas,
-								gas_limit: m.gas_limit,
-								action: m.action,
-								value: m.value,
-								input: m.input.clone(),
-								access_list: m.access_list,
-								odd_y_parity: recid.serialize() != 0,
-								r,
-								s,
-							}));
-					}
-				}
-				break;
-			}
-		}
+		sign_transaction(message, self.key(address)?)
+	}
 
-		transaction.ok_or_else(|| internal_err("signer not available"))
+	fn sign_hash(&self, hash: &H256, address: &H160) -> Result<[u8; 65], Error> {
+		Ok(sign_hash(hash, self.key(address)?))
 	}
 }
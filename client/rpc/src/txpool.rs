@@ -16,7 +16,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+use std::{
+	collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
+	hash::Hash,
+	marker::PhantomData,
+	sync::{Arc, Mutex, MutexGuard},
+};
 
 use ethereum::TransactionV2;
 use ethereum_types::{H160, H256, U256};
@@ -28,7 +33,7 @@ use sc_transaction_pool_api::InPoolTransaction;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
 use sp_core::hashing::keccak_256;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::{traits::Block as BlockT, Percent};
 // Frontier
 use fc_rpc_core::{
 	types::{Get, Summary, TransactionMap, TxPoolResult, TxPoolTransaction},
@@ -38,17 +43,114 @@ use fp_rpc::{EthereumRuntimeRPCApi, TxPoolResponse};
 
 use crate::{internal_err, public_key};
 
-pub struct TxPool<B, C, A: ChainApi> {
+/// Ethereum transaction of the pool, with its recovered sender.
+struct PoolTransaction {
+	sender: H160,
+	nonce: U256,
+	transaction: TransactionV2,
+}
+
+/// Ethereum transactions of the pool indexed by sender.
+///
+/// Extrinsics are matched against Ethereum transactions, and their sender recovered, only once
+/// when they are first seen in the pool. Afterwards the index is only updated for the extrinsics
+/// that entered or left the pool, and for the ready queue.
+struct SenderIndex<H> {
+	/// Ethereum transactions by extrinsic hash, `None` for other extrinsics.
+	transactions: HashMap<H, Option<PoolTransaction>>,
+	/// Extrinsic hashes of the transactions of each sender, by nonce.
+	senders: HashMap<H160, BTreeMap<U256, H>>,
+	/// Extrinsic hashes of the ready queue.
+	ready: HashSet<H>,
+}
+
+impl<H> Default for SenderIndex<H> {
+	fn default() -> Self {
+		Self {
+			transactions: HashMap::new(),
+			senders: HashMap::new(),
+			ready: HashSet::new(),
+		}
+	}
+}
+
+impl<H: Copy + Eq + Hash> SenderIndex<H> {
+	fn insert(&mut self, hash: H, transaction: Option<PoolTransaction>) {
+		if let Some(transaction) = &transaction {
+			self.senders
+				.entry(transaction.sender)
+				.or_default()
+				.insert(transaction.nonce, hash);
+		}
+		self.transactions.insert(hash, transaction);
+	}
+
+	/// Remove the extrinsics that are not in `in_pool` anymore.
+	fn retain(&mut self, in_pool: &HashSet<H>) {
+		let senders = &mut self.senders;
+		self.transactions.retain(|hash, transaction| {
+			if in_pool.contains(hash) {
+				return true;
+			}
+			if let Some(transaction) = transaction {
+				if let Entry::Occupied(mut nonces) = senders.entry(transaction.sender) {
+					if nonces.get().get(&transaction.nonce) == Some(hash) {
+						nonces.get_mut().remove(&transaction.nonce);
+					}
+					if nonces.get().is_empty() {
+						nonces.remove();
+					}
+				}
+			}
+			false
+		});
+	}
+
+	/// The transaction of `sender` with `nonce` in the ready or future queue.
+	fn transaction(&self, sender: &H160, nonce: &U256) -> Option<&TransactionV2> {
+		let hash = self.senders.get(sender)?.get(nonce)?;
+		self.transactions
+			.get(hash)?
+			.as_ref()
+			.map(|pooled| &pooled.transaction)
+	}
+
+	/// Transactions of `sender` in the ready and future queues, by nonce.
+	fn sender_queues(
+		&self,
+		sender: &H160,
+	) -> (
+		BTreeMap<U256, &TransactionV2>,
+		BTreeMap<U256, &TransactionV2>,
+	) {
+		let mut ready = BTreeMap::new();
+		let mut future = BTreeMap::new();
+		for (nonce, hash) in self.senders.get(sender).into_iter().flatten() {
+			if let Some(Some(pooled)) = self.transactions.get(hash) {
+				if self.ready.contains(hash) {
+					ready.insert(*nonce, &pooled.transaction);
+				} else {
+					future.insert(*nonce, &pooled.transaction);
+				}
+			}
+		}
+		(ready, future)
+	}
+}
+
+pub struct TxPool<B: BlockT, C, A: ChainApi> {
 	client: Arc<C>,
 	graph: Arc<Pool<A>>,
+	index: Arc<Mutex<SenderIndex<B::Hash>>>,
 	_marker: PhantomData<B>,
 }
 
-impl<B, C, A: ChainApi> Clone for TxPool<B, C, A> {
+impl<B: BlockT, C, A: ChainApi> Clone for TxPool<B, C, A> {
 	fn clone(&self) -> Self {
 		Self {
 			client: self.client.clone(),
 			graph: self.graph.clone(),
+			index: self.index.clone(),
 			_marker: PhantomData,
 		}
 	}
@@ -68,67 +170,187 @@ where
 	where
 		T: Get + Serialize,
 	{
-		// Get the pending and queued ethereum transactions.
-		let ethereum_txns = self.tx_pool_response()?;
+		let index = self.index()?;
 
 		// Build the T response.
 		let mut pending = TransactionMap::<T>::new();
-		for txn in ethereum_txns.ready.iter() {
-			let hash = txn.hash();
-			let nonce = match txn {
-				TransactionV2::Legacy(t) => t.nonce,
-				TransactionV2::EIP2930(t) => t.nonce,
-				TransactionV2::EIP1559(t) => t.nonce,
-			};
-			let from_address = match public_key(txn) {
-				Ok(pk) => H160::from(H256::from(keccak_256(&pk))),
-				Err(_e) => H160::default(),
-			};
-			pending
-				.entry(from_address)
-				.or_insert_with(HashMap::new)
-				.insert(nonce, T::get(hash, from_address, txn));
-		}
 		let mut queued = TransactionMap::<T>::new();
-		for txn in ethereum_txns.future.iter() {
-			let hash = txn.hash();
-			let nonce = match txn {
-				TransactionV2::Legacy(t) => t.nonce,
-				TransactionV2::EIP2930(t) => t.nonce,
-				TransactionV2::EIP1559(t) => t.nonce,
-			};
-			let from_address = match public_key(txn) {
-				Ok(pk) => H160::from(H256::from(keccak_256(&pk))),
-				Err(_e) => H160::default(),
-			};
-			queued
-				.entry(from_address)
-				.or_insert_with(HashMap::new)
-				.insert(nonce, T::get(hash, from_address, txn));
+		for from_address in index.senders.keys() {
+			let (ready, future) = index.sender_queues(from_address);
+			if !ready.is_empty() {
+				pending.insert(*from_address, nonce_map(*from_address, &ready));
+			}
+			if !future.is_empty() {
+				queued.insert(*from_address, nonce_map(*from_address, &future));
+			}
 		}
 		Ok(TxPoolResult { pending, queued })
 	}
 
+	/// Same as `map_build`, for the transactions of a single sender.
+	fn map_build_from<T>(&self, address: H160) -> RpcResult<TxPoolResult<HashMap<U256, T>>>
+	where
+		T: Get + Serialize,
+	{
+		let index = self.index()?;
+
+		let (ready, future) = index.sender_queues(&address);
+		Ok(TxPoolResult {
+			pending: nonce_map(address, &ready),
+			queued: nonce_map(address, &future),
+		})
+	}
+
+	/// The next nonce of `address` given its `account_nonce`, skipping over the nonces of its
+	/// transactions in the ready and future queues.
+	pub fn pending_nonce(&self, address: H160, account_nonce: U256) -> RpcResult<U256> {
+		let index = self.index()?;
+
+		let mut nonce = account_nonce;
+		if let Some(nonces) = index.senders.get(&address) {
+			while nonces.contains_key(&nonce) {
+				nonce = nonce.saturating_add(U256::one());
+			}
+		}
+		Ok(nonce)
+	}
+
+	/// Reject `transaction` if it replaces a pooled transaction with the same sender and nonce
+	/// without raising both its fee cap and its tip cap by at least `fee_bump`, as Geth does.
+	///
+	/// This is a local policy of the RPC: the runtime validation doesn't know the pooled
+	/// transaction, so the pool replaces it with any transaction of higher priority received from
+	/// peers or through `author_submitExtrinsic`.
+	pub(crate) fn ensure_replacement_fee_bump(
+		&self,
+		transaction: &TransactionV2,
+		fee_bump: Percent,
+	) -> RpcResult<()> {
+		let sender = sender(transaction);
+		let index = self.index()?;
+		match index.transaction(&sender, &nonce(transaction)) {
+			Some(pooled)
+				if pooled.hash() != transaction.hash()
+					&& is_underpriced_replacement(transaction, pooled, fee_bump) =>
+			{
+				Err(internal_err("replacement transaction underpriced"))
+			}
+			_ => Ok(()),
+		}
+	}
+
+	/// The sender index, updated with the extrinsics that entered or left the pool since the
+	/// last call.
+	fn index(&self) -> RpcResult<MutexGuard<'_, SenderIndex<B::Hash>>> {
+		let validated_pool = self.graph.validated_pool();
+		// The future queue is only exposed along with its extrinsics.
+		let futures = validated_pool.futures();
+
+		let mut ready = HashSet::new();
+		let mut entered = Vec::new();
+		{
+			let index = self.lock_index()?;
+			for in_pool_tx in validated_pool.ready() {
+				let hash = *in_pool_tx.hash();
+				if !index.transactions.contains_key(&hash) {
+					entered.push((hash, in_pool_tx.data().clone()));
+				}
+				ready.insert(hash);
+			}
+			for (hash, xt) in &futures {
+				if !index.transactions.contains_key(hash) {
+					entered.push((*hash, xt.clone()));
+				}
+			}
+		}
+
+		// The runtime is called without holding the lock.
+		let entered = self.match_extrinsics(entered)?;
+
+		let in_pool = ready
+			.iter()
+			.copied()
+			.chain(futures.into_iter().map(|(hash, _)| hash))
+			.collect::<HashSet<_>>();
+		let mut index = self.lock_index()?;
+		index.retain(&in_pool);
+		for (hash, transaction) in entered {
+			if in_pool.contains(&hash) {
+				index.insert(hash, transaction);
+			}
+		}
+		index.ready = ready;
+		Ok(index)
+	}
+
+	fn lock_index(&self) -> RpcResult<MutexGuard<'_, SenderIndex<B::Hash>>> {
+		self.index
+			.lock()
+			.map_err(|err| internal_err(format!("txpool index lock poisoned: {:?}", err)))
+	}
+
+	/// Use the runtime to match each extrinsic against an Ethereum transaction, and recover its
+	/// sender.
+	#[allow(clippy::type_complexity)]
+	fn match_extrinsics(
+		&self,
+		extrinsics: Vec<(B::Hash, <B as BlockT>::Extrinsic)>,
+	) -> RpcResult<Vec<(B::Hash, Option<PoolTransaction>)>> {
+		if extrinsics.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let best_hash = self.client.info().best_hash;
+		let api = self.client.runtime_api();
+		extrinsics
+			.into_iter()
+			.map(|(hash, xt)| {
+				let transaction = api
+					.extrinsic_filter(best_hash, vec![xt])
+					.map_err(|err| {
+						internal_err(format!("fetch pool transactions failed: {:?}", err))
+					})?
+					.pop()
+					.map(|transaction| PoolTransaction {
+						sender: sender(&transaction),
+						nonce: nonce(&transaction),
+						transaction,
+					});
+				Ok((hash, transaction))
+			})
+			.collect()
+	}
+
 	pub(crate) fn tx_pool_response(&self) -> RpcResult<TxPoolResponse> {
-		// Collect transactions in the ready validated pool.
+		let (txs_ready, txs_future) = self.pool_extrinsics();
+		self.filter_extrinsics(self.client.info().best_hash, txs_ready, txs_future)
+	}
+
+	/// Collect the extrinsics currently in the ready and future validated pool.
+	fn pool_extrinsics(&self) -> (Vec<<B as BlockT>::Extrinsic>, Vec<<B as BlockT>::Extrinsic>) {
 		let txs_ready = self
 			.graph
 			.validated_pool()
 			.ready()
 			.map(|in_pool_tx| in_pool_tx.data().clone())
 			.collect();
-
-		// Collect transactions in the future validated pool.
 		let txs_future = self
 			.graph
 			.validated_pool()
 			.futures()
-			.iter()
-			.map(|(_hash, extrinsic)| extrinsic.clone())
+			.into_iter()
+			.map(|(_hash, extrinsic)| extrinsic)
 			.collect();
+		(txs_ready, txs_future)
+	}
 
-		// Use the runtime to match the (here) opaque extrinsics against ethereum transactions.
-		let best_block = self.client.info().best_hash;
+	/// Use the runtime to match the (here) opaque extrinsics against ethereum transactions.
+	fn filter_extrinsics(
+		&self,
+		best_block: B::Hash,
+		txs_ready: Vec<<B as BlockT>::Extrinsic>,
+		txs_future: Vec<<B as BlockT>::Extrinsic>,
+	) -> RpcResult<TxPoolResponse> {
 		let api = self.client.runtime_api();
 		let ready = api
 			.extrinsic_filter(best_block, txs_ready)
@@ -141,14 +363,65 @@ where
 	}
 }
 
-impl<B, C, A: ChainApi> TxPool<B, C, A> {
+impl<B: BlockT, C, A: ChainApi> TxPool<B, C, A> {
 	pub fn new(client: Arc<C>, graph: Arc<Pool<A>>) -> Self {
 		Self {
 			client,
 			graph,
+			index: Arc::new(Mutex::new(SenderIndex::default())),
 			_marker: PhantomData,
 		}
 	}
+
+	pub(crate) fn graph(&self) -> &Pool<A> {
+		&self.graph
+	}
+}
+
+fn nonce(txn: &TransactionV2) -> U256 {
+	match txn {
+		TransactionV2::Legacy(t) => t.nonce,
+		TransactionV2::EIP2930(t) => t.nonce,
+		TransactionV2::EIP1559(t) => t.nonce,
+	}
+}
+
+/// Whether `replacement` raises the fee cap or the tip cap of `pooled` by less than `fee_bump`.
+fn is_underpriced_replacement(
+	replacement: &TransactionV2,
+	pooled: &TransactionV2,
+	fee_bump: Percent,
+) -> bool {
+	let bumped =
+		|fee: U256| fee.saturating_mul(U256::from(100 + u64::from(fee_bump.deconstruct()))) / 100;
+	let (fee_cap, tip_cap) = fee_caps(replacement);
+	let (pooled_fee_cap, pooled_tip_cap) = fee_caps(pooled);
+	fee_cap < bumped(pooled_fee_cap) || tip_cap < bumped(pooled_tip_cap)
+}
+
+/// Maximum fee and maximum tip per gas of `txn`.
+fn fee_caps(txn: &TransactionV2) -> (U256, U256) {
+	match txn {
+		TransactionV2::Legacy(t) => (t.gas_price, t.gas_price),
+		TransactionV2::EIP2930(t) => (t.gas_price, t.gas_price),
+		TransactionV2::EIP1559(t) => (t.max_fee_per_gas, t.max_priority_fee_per_gas),
+	}
+}
+
+fn sender(txn: &TransactionV2) -> H160 {
+	match public_key(txn) {
+		Ok(pk) => H160::from(H256::from(keccak_256(&pk))),
+		Err(_e) => H160::default(),
+	}
+}
+
+fn nonce_map<T: Get>(
+	from_address: H160,
+	txns: &BTreeMap<U256, &TransactionV2>,
+) -> HashMap<U256, T> {
+	txns.iter()
+		.map(|(nonce, txn)| (*nonce, T::get(txn.hash(), from_address, txn)))
+		.collect()
 }
 
 impl<B, C, A> TxPoolApiServer for TxPool<B, C, A>
@@ -167,6 +440,17 @@ where
 		self.map_build::<Summary>()
 	}
 
+	fn content_from(
+		&self,
+		address: H160,
+	) -> RpcResult<TxPoolResult<HashMap<U256, TxPoolTransaction>>> {
+		self.map_build_from::<TxPoolTransaction>(address)
+	}
+
+	fn inspect_from(&self, address: H160) -> RpcResult<TxPoolResult<HashMap<U256, Summary>>> {
+		self.map_build_from::<Summary>(address)
+	}
+
 	fn status(&self) -> RpcResult<TxPoolResult<U256>> {
 		let status = self.graph.validated_pool().status();
 		Ok(TxPoolResult {
@@ -175,3 +459,121 @@ where
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum::{
+		EIP1559Transaction, LegacyTransaction, TransactionAction, TransactionSignature,
+	};
+
+	fn legacy(nonce: u64, gas_price: u64) -> TransactionV2 {
+		TransactionV2::Legacy(LegacyTransaction {
+			nonce: nonce.into(),
+			gas_price: gas_price.into(),
+			gas_limit: 21_000.into(),
+			action: TransactionAction::Create,
+			value: U256::zero(),
+			input: Vec::new(),
+			signature: TransactionSignature::new(27, H256::repeat_byte(1), H256::repeat_byte(1))
+				.expect("valid signature"),
+		})
+	}
+
+	fn eip1559(max_fee_per_gas: u64, max_priority_fee_per_gas: u64) -> TransactionV2 {
+		TransactionV2::EIP1559(EIP1559Transaction {
+			chain_id: 42,
+			nonce: U256::zero(),
+			max_priority_fee_per_gas: max_priority_fee_per_gas.into(),
+			max_fee_per_gas: max_fee_per_gas.into(),
+			gas_limit: 21_000.into(),
+			action: TransactionAction::Create,
+			value: U256::zero(),
+			input: Vec::new(),
+			access_list: Vec::new(),
+			odd_y_parity: false,
+			r: H256::repeat_byte(1),
+			s: H256::repeat_byte(1),
+		})
+	}
+
+	#[test]
+	fn replacement_requires_fee_bump() {
+		let bump = Percent::from_percent(10);
+
+		let pooled = legacy(0, 1_000);
+		assert!(is_underpriced_replacement(&legacy(0, 1_000), &pooled, bump));
+		assert!(is_underpriced_replacement(&legacy(0, 1_099), &pooled, bump));
+		assert!(!is_underpriced_replacement(
+			&legacy(0, 1_100),
+			&pooled,
+			bump
+		));
+
+		// Both the fee cap and the tip cap must be raised.
+		let pooled = eip1559(2_000, 1_000);
+		assert!(is_underpriced_replacement(
+			&eip1559(2_200, 1_000),
+			&pooled,
+			bump
+		));
+		assert!(is_underpriced_replacement(
+			&eip1559(2_000, 1_100),
+			&pooled,
+			bump
+		));
+		assert!(!is_underpriced_replacement(
+			&eip1559(2_200, 1_100),
+			&pooled,
+			bump
+		));
+		assert!(!is_underpriced_replacement(
+			&legacy(0, 2_200),
+			&pooled,
+			bump
+		));
+	}
+
+	fn pooled(sender: u64, nonce: u64) -> PoolTransaction {
+		let transaction = legacy(nonce, 1);
+		PoolTransaction {
+			sender: H160::from_low_u64_be(sender),
+			nonce: nonce.into(),
+			transaction,
+		}
+	}
+
+	fn nonces(queue: &BTreeMap<U256, &TransactionV2>) -> Vec<u64> {
+		queue.keys().map(|nonce| nonce.as_u64()).collect()
+	}
+
+	#[test]
+	fn sender_index_tracks_pool_changes() {
+		let alice = H160::from_low_u64_be(1);
+		let mut index = SenderIndex::<u64>::default();
+		index.insert(10, Some(pooled(1, 0)));
+		index.insert(11, Some(pooled(1, 2)));
+		index.insert(12, None);
+		index.insert(20, Some(pooled(2, 0)));
+		index.ready = [10, 12, 20].into_iter().collect();
+
+		let (ready, future) = index.sender_queues(&alice);
+		assert_eq!((nonces(&ready), nonces(&future)), (vec![0], vec![2]));
+
+		// Nonce 0 is included, nonce 1 fills the gap and nonce 2 is promoted.
+		index.retain(&[11, 12, 20].into_iter().collect());
+		index.insert(13, Some(pooled(1, 1)));
+		index.ready = [11, 12, 13, 20].into_iter().collect();
+		let (ready, future) = index.sender_queues(&alice);
+		assert_eq!((nonces(&ready), nonces(&future)), (vec![1, 2], vec![]));
+
+		// Replacing a transaction keeps the sender indexed under the new extrinsic.
+		index.retain(&[11, 12, 20].into_iter().collect());
+		index.insert(14, Some(pooled(1, 1)));
+		assert_eq!(index.senders[&alice].get(&1.into()), Some(&14));
+
+		index.retain(&HashSet::new());
+		assert!(index.senders.is_empty());
+		assert!(index.transactions.is_empty());
+	}
+}
@@ -50,7 +50,7 @@ use frame_support::{
 	weights::Weight,
 };
 use frame_system::{pallet_prelude::OriginFor, CheckWeight, WeightInfo};
-use pallet_evm::{BlockHashMapping, FeeCalculator, GasWeightMapping, Runner};
+use pallet_evm::{BlockHashMapping, ExecutionDetails, FeeCalculator, GasWeightMapping, Runner};
 use sp_runtime::{
 	generic::DigestItem,
 	traits::{DispatchInfoOf, Dispatchable, One, Saturating, UniqueSaturatedInto, Zero},
@@ -199,6 +199,11 @@ pub mod pallet {
 		type PostLogContent: Get<PostLogContent>;
 		/// The maximum length of the extra data in the Executed event.
 		type ExtraDataLength: Get<u32>;
+		/// Number of block hashes kept in [`HistoricalBlockHashes`], `0` disabling it.
+		type HistoricalBlockHashCount: Get<u32>;
+		/// Maximum number of transactions a sender can have in the pool, counted from its
+		/// current nonce, `0` disabling the limit.
+		type MaxQueuedTransactionsPerSender: Get<u32>;
 	}
 
 	#[pallet::hooks]
@@ -254,7 +259,13 @@ pub mod pallet {
 			//	- read: frame_system::Pallet::<T>::block_number()
 			//	- write: <Pallet<T>>::store_block()
 			//	- write: <BlockHash<T>>::remove()
-			weight.saturating_add(T::DbWeight::get().reads_writes(2, 2))
+			//	- write: <HistoricalBlockHashes<T>>::insert(), if enabled
+			let writes = if T::HistoricalBlockHashCount::get() > 0 {
+				3
+			} else {
+				2
+			};
+			weight.saturating_add(T::DbWeight::get().reads_writes(2, writes))
 		}
 
 		fn on_runtime_upgrade() -> Weight {
@@ -306,6 +317,7 @@ pub mod pallet {
 			transaction_hash: H256,
 			exit_reason: ExitReason,
 			extra_data: Vec<u8>,
+			details: Option<ExecutionDetails>,
 		},
 	}
 
@@ -338,6 +350,12 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type BlockHash<T: Config> = StorageMap<_, Twox64Concat, U256, H256, ValueQuery>;
 
+	/// Ring buffer of the last `HistoricalBlockHashCount` block numbers and hashes, the block
+	/// `n` being stored at slot `n % HistoricalBlockHashCount`.
+	#[pallet::storage]
+	pub type HistoricalBlockHashes<T: Config> =
+		StorageMap<_, Twox64Concat, u32, (U256, H256), OptionQuery>;
+
 	#[pallet::genesis_config]
 	#[derive(Default)]
 	pub struct GenesisConfig {}
@@ -447,6 +465,11 @@ impl<T: Config> Pallet<T> {
 		CurrentReceipts::<T>::put(receipts.clone());
 		CurrentTransactionStatuses::<T>::put(statuses.clone());
 		BlockHash::<T>::insert(block_number, block.header.hash());
+		let history_len = T::HistoricalBlockHashCount::get();
+		if history_len > 0 {
+			let slot = (block_number % history_len).low_u32();
+			HistoricalBlockHashes::<T>::insert(slot, (block_number, block.header.hash()));
+		}
 
 		match post_log {
 			Some(PostLogContent::BlockAndTxnHashes) => {
@@ -519,6 +542,11 @@ impl<T: Config> Pallet<T> {
 		.and_then(|v| v.with_balance_for(&who))
 		.map_err(|e| e.0)?;
 
+		let max_queued = T::MaxQueuedTransactionsPerSender::get();
+		if max_queued > 0 && transaction_nonce >= who.nonce.saturating_add(max_queued.into()) {
+			return Err(InvalidTransaction::Future.into());
+		}
+
 		let priority = match (
 			transaction_data.gas_price,
 			transaction_data.max_fee_per_gas,
@@ -542,12 +570,18 @@ impl<T: Config> Pallet<T> {
 		};
 
 		// The tag provides and requires must be filled correctly according to the nonce.
+		// The pool replaces a transaction providing the same tag with any transaction of
+		// higher priority. The pooled transaction is not known here, so a minimum fee bump
+		// cannot be enforced by this validation: the Ethereum RPC of a node enforces it on
+		// the transactions it receives, a replacement gossiped by peers or submitted as an
+		// extrinsic only needs a higher tip.
 		let mut builder = ValidTransactionBuilder::default()
 			.and_provides((origin, transaction_nonce))
 			.priority(priority);
 
 		// In the context of the pool, a transaction with
-		// too high a nonce is still considered valid
+		// too high a nonce is still considered valid, and is promoted
+		// once the transaction with the previous nonce is ready.
 		if transaction_nonce > who.nonce {
 			if let Some(prev_nonce) = transaction_nonce.checked_sub(1.into()) {
 				builder = builder.and_requires((origin, prev_nonce))
@@ -563,6 +597,19 @@ impl<T: Config> Pallet<T> {
 	) -> Result<(PostDispatchInfo, CallOrCreateInfo), DispatchErrorWithPostInfo> {
 		let (to, _, info) = Self::execute(source, &transaction, None)?;
 
+		let details = match &info {
+			CallOrCreateInfo::Call(info) => pallet_evm::Pallet::<T>::execution_details(
+				&info.exit_reason,
+				info.used_gas.effective,
+				&info.value,
+			),
+			CallOrCreateInfo::Create(info) => pallet_evm::Pallet::<T>::execution_details(
+				&info.exit_reason,
+				info.used_gas.effective,
+				&[],
+			),
+		};
+
 		let pending = Pending::<T>::get();
 		let transaction_hash = transaction.hash();
 		let transaction_index = pending.len() as u32;
@@ -680,6 +727,7 @@ impl<T: Config> Pallet<T> {
 			transaction_hash,
 			exit_reason: reason,
 			extra_data,
+			details,
 		});
 
 		Ok((
@@ -707,6 +755,19 @@ impl<T: Config> Pallet<T> {
 		<CurrentBlock<T>>::get().map(|block| block.header.hash())
 	}
 
+	/// Get the hash of block `number` if it's still in the historical block hash ring buffer.
+	pub fn historical_block_hash(number: U256) -> Option<H256> {
+		let history_len = T::HistoricalBlockHashCount::get();
+		if history_len == 0 {
+			return None;
+		}
+
+		let slot = (number % history_len).low_u32();
+		HistoricalBlockHashes::<T>::get(slot)
+			.filter(|(stored_number, _)| *stored_number == number)
+			.map(|(_, hash)| hash)
+	}
+
 	/// Execute an Ethereum transaction.
 	pub fn execute(
 		from: H160,
@@ -996,6 +1057,15 @@ impl<T: Config> BlockHashMapping for EthereumBlockHashMapping<T> {
 	}
 }
 
+/// Returns the Ethereum block hash by number from the [`HistoricalBlockHashes`] ring buffer,
+/// or zero if it's no longer or not yet stored.
+pub struct HistoricalBlockHashMapping<T>(PhantomData<T>);
+impl<T: Config> BlockHashMapping for HistoricalBlockHashMapping<T> {
+	fn block_hash(number: u32) -> H256 {
+		Pallet::<T>::historical_block_hash(U256::from(number)).unwrap_or_default()
+	}
+}
+
 pub struct InvalidTransactionWrapper(InvalidTransaction);
 
 impl From<InvalidEvmTransactionError> for InvalidTransactionWrapper {
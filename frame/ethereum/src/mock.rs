@@ -124,7 +124,7 @@ impl pallet_timestamp::Config for Test {
 pub struct FixedGasPrice;
 impl FeeCalculator for FixedGasPrice {
 	fn min_gas_price() -> (U256, Weight) {
-		(1.into(), Weight::zero())
+		(BaseFee::get(), Weight::zero())
 	}
 }
 
@@ -148,6 +148,8 @@ parameter_types! {
 	pub BlockGasLimit: U256 = U256::from(BLOCK_GAS_LIMIT);
 	pub const GasLimitPovSizeRatio: u64 = BLOCK_GAS_LIMIT.saturating_div(MAX_POV_SIZE);
 	pub const WeightPerGas: Weight = Weight::from_parts(20_000, 0);
+	pub static EventRevertDataLimit: Option<u32> = None;
+	pub static BaseFee: U256 = U256::one();
 }
 
 pub struct HashedAddressMapping;
@@ -176,6 +178,8 @@ impl pallet_evm::Config for Test {
 	type Runner = pallet_evm::runner::stack::Runner<Self>;
 	type OnChargeTransaction = ();
 	type OnCreate = ();
+	type StorageDeposit = ();
+	type EventRevertDataLimit = EventRevertDataLimit;
 	type FindAuthor = FindAuthorTruncated;
 	type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
 	type Timestamp = Timestamp;
@@ -191,6 +195,8 @@ impl Config for Test {
 	type StateRoot = IntermediateStateRoot<Self>;
 	type PostLogContent = PostBlockAndTxnHashes;
 	type ExtraDataLength = ConstU32<30>;
+	type HistoricalBlockHashCount = ConstU32<4>;
+	type MaxQueuedTransactionsPerSender = ConstU32<4>;
 }
 
 impl fp_self_contained::SelfContainedCall for RuntimeCall {
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::{HistoricalBlockHashMapping, HistoricalBlockHashes};
+use pallet_evm::BlockHashMapping;
+
+#[test]
+fn historical_block_hashes_are_kept_in_ring_buffer() {
+	let (_, mut ext) = new_test_ext(1);
+	ext.execute_with(|| {
+		let mut hashes = Vec::new();
+		for number in 1..=6u32 {
+			Ethereum::store_block(None, U256::from(number));
+			hashes.push(Ethereum::current_block_hash().unwrap());
+		}
+
+		// Only the last 4 blocks are kept, block 5 having replaced block 1.
+		assert_eq!(HistoricalBlockHashes::<Test>::iter().count(), 4);
+		assert_eq!(Ethereum::historical_block_hash(U256::from(1)), None);
+		assert_eq!(Ethereum::historical_block_hash(U256::from(2)), None);
+		for number in 3..=6u32 {
+			assert_eq!(
+				Ethereum::historical_block_hash(U256::from(number)),
+				Some(hashes[number as usize - 1])
+			);
+		}
+		assert_eq!(Ethereum::historical_block_hash(U256::from(7)), None);
+
+		assert_eq!(HistoricalBlockHashMapping::<Test>::block_hash(6), hashes[5]);
+		assert_eq!(
+			HistoricalBlockHashMapping::<Test>::block_hash(2),
+			H256::zero()
+		);
+	});
+}
@@ -450,6 +450,7 @@ fn event_extra_data_should_be_handle_properly() {
 			.unwrap(),
 			exit_reason: ExitReason::Succeed(ExitSucceed::Returned),
 			extra_data: vec![],
+			details: None,
 		}));
 
 		let t3 = EIP1559UnsignedTransaction {
@@ -474,6 +475,7 @@ fn event_extra_data_should_be_handle_properly() {
 			.unwrap(),
 			exit_reason: ExitReason::Revert(ExitRevert::Reverted),
 			extra_data: b"very_long_error_msg_that_we_ex".to_vec(),
+			details: None,
 		}));
 	});
 }
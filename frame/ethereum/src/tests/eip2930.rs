@@ -377,6 +377,7 @@ fn event_extra_data_should_be_handle_properly() {
 			.unwrap(),
 			exit_reason: ExitReason::Succeed(ExitSucceed::Returned),
 			extra_data: vec![],
+			details: None,
 		}));
 
 		let t3 = EIP2930UnsignedTransaction {
@@ -400,6 +401,7 @@ fn event_extra_data_should_be_handle_properly() {
 			.unwrap(),
 			exit_reason: ExitReason::Revert(ExitRevert::Reverted),
 			extra_data: b"very_long_error_msg_that_we_ex".to_vec(),
+			details: None,
 		}));
 	});
 }
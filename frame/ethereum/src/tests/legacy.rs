@@ -377,6 +377,7 @@ fn event_extra_data_should_be_handle_properly() {
 			.unwrap(),
 			exit_reason: ExitReason::Succeed(ExitSucceed::Returned),
 			extra_data: vec![],
+			details: None,
 		}));
 
 		let t3 = LegacyUnsignedTransaction {
@@ -400,6 +401,61 @@ fn event_extra_data_should_be_handle_properly() {
 			.unwrap(),
 			exit_reason: ExitReason::Revert(ExitRevert::Reverted),
 			extra_data: b"very_long_error_msg_that_we_ex".to_vec(),
+			details: None,
+		}));
+	});
+}
+
+#[test]
+fn event_details_should_carry_bounded_revert_data() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		EventRevertDataLimit::set(Some(4));
+
+		let t = LegacyUnsignedTransaction {
+			nonce: U256::zero(),
+			gas_price: U256::from(1),
+			gas_limit: U256::from(0x100000),
+			action: ethereum::TransactionAction::Create,
+			value: U256::zero(),
+			input: hex::decode(TEST_CONTRACT_CODE).unwrap(),
+		}
+		.sign(&alice.private_key);
+		assert_ok!(Ethereum::execute(alice.address, &t, None,));
+
+		let contract_address = hex::decode("32dcab0ef3fb2de2fce1d2e0799d36239671f04a").unwrap();
+		let bar = hex::decode("febb0f7e").unwrap();
+
+		let t2 = LegacyUnsignedTransaction {
+			nonce: U256::from(1),
+			gas_price: U256::from(1),
+			gas_limit: U256::from(0x100000),
+			action: TransactionAction::Call(H160::from_slice(&contract_address)),
+			value: U256::zero(),
+			input: bar,
+		}
+		.sign(&alice.private_key);
+
+		// calling bar revert, the revert data is cut down to the `Error(string)` selector
+		let transaction_hash = t2.hash();
+		let (_, info) = Ethereum::apply_validated_transaction(alice.address, t2).unwrap();
+		let used_gas = match info {
+			CallOrCreateInfo::Call(info) => info.used_gas.effective,
+			CallOrCreateInfo::Create(_) => panic!("expected call info"),
+		};
+		System::assert_last_event(RuntimeEvent::Ethereum(Event::Executed {
+			from: alice.address,
+			to: H160::from_slice(&contract_address),
+			transaction_hash,
+			exit_reason: ExitReason::Revert(ExitRevert::Reverted),
+			extra_data: b"very_long_error_msg_that_we_ex".to_vec(),
+			details: Some(pallet_evm::ExecutionDetails {
+				used_gas,
+				revert_data: vec![0x08, 0xc3, 0x79, 0xa0],
+			}),
 		}));
 	});
 }
@@ -29,9 +29,11 @@ use crate::{
 };
 use fp_self_contained::CheckedExtrinsic;
 
+mod block_hash;
 mod eip1559;
 mod eip2930;
 mod legacy;
+mod pool;
 
 // This ERC-20 contract mints the maximum amount of tokens to the contract creator.
 // pragma solidity ^0.5.0;`
@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Transaction pool ordering and replacement, as driven by `validate_transaction_in_pool`.
+
+use super::*;
+use sp_runtime::transaction_validity::ValidTransaction;
+
+fn transfer(
+	from: &AccountInfo,
+	to: H160,
+	nonce: u64,
+	max_priority_fee_per_gas: u64,
+	max_fee_per_gas: u64,
+) -> Transaction {
+	EIP1559UnsignedTransaction {
+		nonce: U256::from(nonce),
+		max_priority_fee_per_gas: U256::from(max_priority_fee_per_gas),
+		max_fee_per_gas: U256::from(max_fee_per_gas),
+		gas_limit: U256::from(21_000),
+		action: TransactionAction::Call(to),
+		value: U256::from(1_000),
+		input: Vec::new(),
+	}
+	.sign(&from.private_key, None)
+}
+
+fn validate(from: &AccountInfo, transaction: &Transaction) -> ValidTransaction {
+	Ethereum::validate_transaction_in_pool(from.address, transaction).unwrap()
+}
+
+#[test]
+fn speed_up_replaces_pooled_transaction() {
+	let (pairs, mut ext) = new_test_ext_with_initial_balance(2, 10_000_000_000_000);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let original = validate(alice, &transfer(alice, bob.address, 0, 1_000, 2_000));
+		// MetaMask "speed up" resends the transaction with fees raised by 10%.
+		let speed_up = validate(alice, &transfer(alice, bob.address, 0, 1_100, 2_200));
+
+		assert_eq!(speed_up.provides, original.provides);
+		assert!(speed_up.priority > original.priority);
+	});
+}
+
+#[test]
+fn cancel_replaces_pooled_transaction() {
+	let (pairs, mut ext) = new_test_ext_with_initial_balance(2, 10_000_000_000_000);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let original = validate(alice, &transfer(alice, bob.address, 0, 1_000, 2_000));
+		// MetaMask "cancel" sends a transfer to self with the same nonce and fees raised by 10%.
+		let mut cancel = EIP1559UnsignedTransaction {
+			nonce: U256::zero(),
+			max_priority_fee_per_gas: U256::from(1_100),
+			max_fee_per_gas: U256::from(2_200),
+			gas_limit: U256::from(21_000),
+			action: TransactionAction::Call(alice.address),
+			value: U256::zero(),
+			input: Vec::new(),
+		};
+		let cancel_priority = validate(alice, &cancel.sign(&alice.private_key, None));
+
+		assert_eq!(cancel_priority.provides, original.provides);
+		assert!(cancel_priority.priority > original.priority);
+
+		// A cancel that does not raise the fees cannot replace the original.
+		cancel.max_priority_fee_per_gas = U256::from(1_000);
+		cancel.max_fee_per_gas = U256::from(2_000);
+		let cancel_priority = validate(alice, &cancel.sign(&alice.private_key, None));
+		assert_eq!(cancel_priority.priority, original.priority);
+	});
+}
+
+#[test]
+fn priority_is_the_effective_tip() {
+	let (pairs, mut ext) = new_test_ext_with_initial_balance(2, 10_000_000_000_000);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		// The base fee of the mock is 1.
+		let capped_by_tip = validate(alice, &transfer(alice, bob.address, 0, 1_000, 2_000));
+		let capped_by_fee = validate(alice, &transfer(alice, bob.address, 0, 1_000, 501));
+
+		assert_eq!(capped_by_tip.priority, 1_000);
+		assert_eq!(capped_by_fee.priority, 500);
+		// The minimum fee bump of a replacement is enforced by the RPC against the pooled
+		// transaction, the priority keeps the ordering of all senders by tip.
+		let bumped = validate(alice, &transfer(alice, bob.address, 0, 1_001, 2_002));
+		assert_eq!(bumped.priority, capped_by_tip.priority + 1);
+	});
+}
+
+#[test]
+fn future_transactions_are_promoted_when_nonce_gap_fills() {
+	let (pairs, mut ext) = new_test_ext_with_initial_balance(2, 10_000_000_000_000);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let ready = validate(alice, &transfer(alice, bob.address, 0, 1_000, 2_000));
+		let future = validate(alice, &transfer(alice, bob.address, 2, 1_000, 2_000));
+		let gap = validate(alice, &transfer(alice, bob.address, 1, 1_000, 2_000));
+
+		assert!(ready.requires.is_empty());
+		// The nonce 2 transaction waits for the nonce 1 one, itself waiting for nonce 0.
+		assert_eq!(future.requires, gap.provides);
+		assert_eq!(gap.requires, ready.provides);
+	});
+}
+
+#[test]
+fn transactions_beyond_sender_queue_limit_are_rejected() {
+	let (pairs, mut ext) = new_test_ext_with_initial_balance(2, 10_000_000_000_000);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		// The mock allows 4 queued transactions per sender.
+		assert_ok!(Ethereum::validate_transaction_in_pool(
+			alice.address,
+			&transfer(alice, bob.address, 3, 1_000, 2_000)
+		));
+		assert_err!(
+			Ethereum::validate_transaction_in_pool(
+				alice.address,
+				&transfer(alice, bob.address, 4, 1_000, 2_000)
+			),
+			InvalidTransaction::Future
+		);
+	});
+}
+
+#[test]
+fn underpriced_transactions_are_invalidated_when_base_fee_rises() {
+	let (pairs, mut ext) = new_test_ext_with_initial_balance(2, 10_000_000_000_000);
+	let alice = &pairs[0];
+	let bob = &pairs[1];
+
+	ext.execute_with(|| {
+		let transaction = transfer(alice, bob.address, 0, 1_000, 2_000);
+		assert_ok!(Ethereum::validate_transaction_in_pool(
+			alice.address,
+			&transaction
+		));
+
+		// Revalidation after the base fee rose above the max fee evicts the transaction.
+		BaseFee::set(U256::from(2_001));
+		assert_err!(
+			Ethereum::validate_transaction_in_pool(alice.address, &transaction),
+			InvalidTransaction::Payment
+		);
+	});
+}
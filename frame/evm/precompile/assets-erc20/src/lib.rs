@@ -0,0 +1,457 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ERC-20 interface to the assets of `pallet-assets`.
+//!
+//! Every asset is reachable at the address made of `Prefix`, zero padding and the asset id as a
+//! big-endian `u128` in the last 16 bytes. `Prefix` must therefore be at most 4 bytes long, no
+//! address belongs to the set otherwise.
+//!
+//! The proof size of the `pallet_assets` storage read by the views is recorded from the maximum
+//! encoded length of the items, and the one of the calls from the benchmarked weights.
+//!
+//! ```ignore
+//! pub struct AssetPrefix;
+//! impl Get<&'static [u8]> for AssetPrefix {
+//!     fn get() -> &'static [u8] {
+//!         &[0xff; 4]
+//!     }
+//! }
+//!
+//! PrecompileSetStartingWith<AssetPrefix, Erc20AssetsPrecompileSet<R, AssetPrefix>, CallableByContract>
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+extern crate alloc;
+
+use alloc::{string::String, vec};
+use core::marker::PhantomData;
+
+use frame_support::{
+	dispatch::DispatchResult,
+	traits::{
+		fungibles::{approvals, metadata},
+		Get, StorageInfoTrait,
+	},
+	weights::Weight,
+};
+use frame_system::RawOrigin;
+use pallet_assets::WeightInfo;
+use sp_runtime::traits::{StaticLookup, UniqueSaturatedInto};
+
+// Frontier
+use pallet_evm::{AddressMapping, GasWeightMapping, IsPrecompileResult, Precompile, PrecompileSet};
+use precompile_utils::prelude::*;
+
+/// `keccak256("Transfer(address,address,uint256)")`
+pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+
+/// `keccak256("Approval(address,address,uint256)")`
+pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
+
+type AssetIdOf<Runtime, Instance> = <Runtime as pallet_assets::Config<Instance>>::AssetId;
+type BalanceOf<Runtime, Instance> = <Runtime as pallet_assets::Config<Instance>>::Balance;
+
+/// Precompile set exposing every existing asset of `pallet_assets::Pallet<Runtime, Instance>`
+/// as an ERC-20.
+pub struct Erc20AssetsPrecompileSet<Runtime, Prefix, Instance: 'static = ()>(
+	PhantomData<(Runtime, Prefix, Instance)>,
+);
+
+impl<Runtime, Prefix, Instance> Default for Erc20AssetsPrecompileSet<Runtime, Prefix, Instance> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<Runtime, Prefix, Instance> Erc20AssetsPrecompileSet<Runtime, Prefix, Instance>
+where
+	Runtime: pallet_assets::Config<Instance>,
+	Prefix: Get<&'static [u8]>,
+	Instance: 'static,
+	AssetIdOf<Runtime, Instance>: TryFrom<u128> + Into<u128>,
+{
+	/// Address of the ERC-20 precompile of `asset_id`, `None` if `Prefix` is longer than 4 bytes.
+	pub fn asset_id_to_address(asset_id: AssetIdOf<Runtime, Instance>) -> Option<H160> {
+		let prefix = Prefix::get();
+		if prefix.len() > 4 {
+			return None;
+		}
+
+		let mut address = H160::zero();
+		address.as_bytes_mut()[..prefix.len()].copy_from_slice(prefix);
+		address.as_bytes_mut()[4..].copy_from_slice(&asset_id.into().to_be_bytes());
+		Some(address)
+	}
+
+	/// Asset id encoded in `address`, if it has the expected layout.
+	pub fn address_to_asset_id(address: H160) -> Option<AssetIdOf<Runtime, Instance>> {
+		let prefix = Prefix::get();
+		let bytes = address.as_bytes();
+		if prefix.len() > 4
+			|| !bytes.starts_with(prefix)
+			|| bytes[prefix.len()..4].iter().any(|byte| *byte != 0)
+		{
+			return None;
+		}
+
+		let mut asset_id = [0u8; 16];
+		asset_id.copy_from_slice(&bytes[4..]);
+		u128::from_be_bytes(asset_id).try_into().ok()
+	}
+}
+
+impl<Runtime, Prefix, Instance> PrecompileSet
+	for Erc20AssetsPrecompileSet<Runtime, Prefix, Instance>
+where
+	Runtime: pallet_evm::Config + pallet_assets::Config<Instance>,
+	Prefix: Get<&'static [u8]>,
+	Instance: 'static,
+	AssetIdOf<Runtime, Instance>: TryFrom<u128> + Into<u128>,
+{
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		// The gas of the asset lookup is charged by `is_precompile`, which cannot record its
+		// proof size.
+		let asset_id = Self::address_to_asset_id(handle.code_address())?;
+		if pallet_assets::Pallet::<Runtime, Instance>::maybe_total_supply(asset_id).is_none() {
+			return None;
+		}
+		if let Err(error) = handle.record_external_cost(
+			None,
+			Some(storage_proof_size::<Runtime, Instance>(b"Asset")),
+		) {
+			return Some(Err(error.into()));
+		}
+
+		Some(Erc20AssetsPrecompile::<Runtime, Prefix, Instance>::execute(
+			handle,
+		))
+	}
+
+	fn is_precompile(&self, address: H160, _remaining_gas: u64) -> IsPrecompileResult {
+		let is_precompile = Self::address_to_asset_id(address)
+			.map(|asset_id| {
+				pallet_assets::Pallet::<Runtime, Instance>::maybe_total_supply(asset_id).is_some()
+			})
+			.unwrap_or(false);
+
+		IsPrecompileResult::Answer {
+			is_precompile,
+			extra_cost: db_access_gas::<Runtime>(1, 0),
+		}
+	}
+}
+
+/// ERC-20 precompile of the asset whose id is encoded in the code address.
+pub struct Erc20AssetsPrecompile<Runtime, Prefix, Instance: 'static = ()>(
+	PhantomData<(Runtime, Prefix, Instance)>,
+);
+
+#[precompile]
+impl<Runtime, Prefix, Instance> Erc20AssetsPrecompile<Runtime, Prefix, Instance>
+where
+	Runtime: pallet_evm::Config + pallet_assets::Config<Instance>,
+	Prefix: Get<&'static [u8]>,
+	Instance: 'static,
+	AssetIdOf<Runtime, Instance>: TryFrom<u128> + Into<u128>,
+{
+	#[precompile::public("name()")]
+	#[precompile::view]
+	fn name(handle: &mut impl PrecompileHandle) -> EvmResult<String> {
+		let asset_id = Self::asset_id(handle)?;
+		record_storage_read::<Runtime, Instance>(handle, b"Metadata")?;
+
+		let name = <pallet_assets::Pallet<Runtime, Instance> as metadata::Inspect<
+			Runtime::AccountId,
+		>>::name(asset_id);
+		Ok(String::from_utf8_lossy(&name).into_owned())
+	}
+
+	#[precompile::public("symbol()")]
+	#[precompile::view]
+	fn symbol(handle: &mut impl PrecompileHandle) -> EvmResult<String> {
+		let asset_id = Self::asset_id(handle)?;
+		record_storage_read::<Runtime, Instance>(handle, b"Metadata")?;
+
+		let symbol = <pallet_assets::Pallet<Runtime, Instance> as metadata::Inspect<
+			Runtime::AccountId,
+		>>::symbol(asset_id);
+		Ok(String::from_utf8_lossy(&symbol).into_owned())
+	}
+
+	#[precompile::public("decimals()")]
+	#[precompile::view]
+	fn decimals(handle: &mut impl PrecompileHandle) -> EvmResult<u8> {
+		let asset_id = Self::asset_id(handle)?;
+		record_storage_read::<Runtime, Instance>(handle, b"Metadata")?;
+
+		Ok(<pallet_assets::Pallet<Runtime, Instance> as metadata::Inspect<
+			Runtime::AccountId,
+		>>::decimals(asset_id))
+	}
+
+	#[precompile::public("totalSupply()")]
+	#[precompile::view]
+	fn total_supply(handle: &mut impl PrecompileHandle) -> EvmResult<U256> {
+		let asset_id = Self::asset_id(handle)?;
+		record_storage_read::<Runtime, Instance>(handle, b"Asset")?;
+
+		Ok(Self::balance_to_u256(pallet_assets::Pallet::<
+			Runtime,
+			Instance,
+		>::total_supply(asset_id)))
+	}
+
+	#[precompile::public("balanceOf(address)")]
+	#[precompile::view]
+	fn balance_of(handle: &mut impl PrecompileHandle, owner: H160) -> EvmResult<U256> {
+		let asset_id = Self::asset_id(handle)?;
+		record_storage_read::<Runtime, Instance>(handle, b"Account")?;
+
+		let owner = Runtime::AddressMapping::into_account_id(owner);
+		Ok(Self::balance_to_u256(pallet_assets::Pallet::<
+			Runtime,
+			Instance,
+		>::balance(asset_id, owner)))
+	}
+
+	#[precompile::public("allowance(address,address)")]
+	#[precompile::view]
+	fn allowance(
+		handle: &mut impl PrecompileHandle,
+		owner: H160,
+		spender: H160,
+	) -> EvmResult<U256> {
+		let asset_id = Self::asset_id(handle)?;
+		record_storage_read::<Runtime, Instance>(handle, b"Approvals")?;
+
+		let owner = Runtime::AddressMapping::into_account_id(owner);
+		let spender = Runtime::AddressMapping::into_account_id(spender);
+		Ok(Self::balance_to_u256(<pallet_assets::Pallet<
+			Runtime,
+			Instance,
+		> as approvals::Inspect<Runtime::AccountId>>::allowance(
+			asset_id, &owner, &spender,
+		)))
+	}
+
+	#[precompile::public("approve(address,uint256)")]
+	fn approve(handle: &mut impl PrecompileHandle, spender: H160, value: U256) -> EvmResult<bool> {
+		let asset_id = Self::asset_id(handle)?;
+		let owner = handle.context().caller;
+		let amount = Self::u256_to_balance(value)?;
+
+		let origin = Self::origin(owner);
+		let delegate = Self::lookup(spender);
+
+		// Approvals of `pallet-assets` are cumulative, so the previous one is cancelled first to
+		// replace it like ERC-20 does.
+		record_weight::<Runtime>(
+			handle,
+			<Runtime as pallet_assets::Config<Instance>>::WeightInfo::cancel_approval(),
+		)?;
+		let _ = pallet_assets::Pallet::<Runtime, Instance>::cancel_approval(
+			origin.clone(),
+			asset_id.clone().into(),
+			delegate.clone(),
+		);
+
+		if !value.is_zero() {
+			record_weight::<Runtime>(
+				handle,
+				<Runtime as pallet_assets::Config<Instance>>::WeightInfo::approve_transfer(),
+			)?;
+			dispatch_result(
+				pallet_assets::Pallet::<Runtime, Instance>::approve_transfer(
+					origin,
+					asset_id.into(),
+					delegate,
+					amount,
+				),
+			)?;
+		}
+
+		deposit_log(handle, SELECTOR_LOG_APPROVAL, owner, spender, value)?;
+		Ok(true)
+	}
+
+	#[precompile::public("transfer(address,uint256)")]
+	fn transfer(handle: &mut impl PrecompileHandle, to: H160, value: U256) -> EvmResult<bool> {
+		let asset_id = Self::asset_id(handle)?;
+		let from = handle.context().caller;
+		let amount = Self::u256_to_balance(value)?;
+
+		record_weight::<Runtime>(
+			handle,
+			<Runtime as pallet_assets::Config<Instance>>::WeightInfo::transfer(),
+		)?;
+		dispatch_result(pallet_assets::Pallet::<Runtime, Instance>::transfer(
+			Self::origin(from),
+			asset_id.into(),
+			Self::lookup(to),
+			amount,
+		))?;
+
+		deposit_log(handle, SELECTOR_LOG_TRANSFER, from, to, value)?;
+		Ok(true)
+	}
+
+	#[precompile::public("transferFrom(address,address,uint256)")]
+	fn transfer_from(
+		handle: &mut impl PrecompileHandle,
+		from: H160,
+		to: H160,
+		value: U256,
+	) -> EvmResult<bool> {
+		let asset_id = Self::asset_id(handle)?;
+		let caller = handle.context().caller;
+		let amount = Self::u256_to_balance(value)?;
+
+		if caller == from {
+			record_weight::<Runtime>(
+				handle,
+				<Runtime as pallet_assets::Config<Instance>>::WeightInfo::transfer(),
+			)?;
+			dispatch_result(pallet_assets::Pallet::<Runtime, Instance>::transfer(
+				Self::origin(from),
+				asset_id.into(),
+				Self::lookup(to),
+				amount,
+			))?;
+		} else {
+			record_weight::<Runtime>(
+				handle,
+				<Runtime as pallet_assets::Config<Instance>>::WeightInfo::transfer_approved(),
+			)?;
+			dispatch_result(
+				pallet_assets::Pallet::<Runtime, Instance>::transfer_approved(
+					Self::origin(caller),
+					asset_id.into(),
+					Self::lookup(from),
+					Self::lookup(to),
+					amount,
+				),
+			)?;
+		}
+
+		deposit_log(handle, SELECTOR_LOG_TRANSFER, from, to, value)?;
+		Ok(true)
+	}
+}
+
+impl<Runtime, Prefix, Instance> Erc20AssetsPrecompile<Runtime, Prefix, Instance>
+where
+	Runtime: pallet_evm::Config + pallet_assets::Config<Instance>,
+	Prefix: Get<&'static [u8]>,
+	Instance: 'static,
+	AssetIdOf<Runtime, Instance>: TryFrom<u128> + Into<u128>,
+{
+	fn asset_id(handle: &impl PrecompileHandle) -> EvmResult<AssetIdOf<Runtime, Instance>> {
+		Erc20AssetsPrecompileSet::<Runtime, Prefix, Instance>::address_to_asset_id(
+			handle.code_address(),
+		)
+		.ok_or_else(|| revert("Not an asset address"))
+	}
+
+	fn origin(address: H160) -> Runtime::RuntimeOrigin {
+		RawOrigin::Signed(Runtime::AddressMapping::into_account_id(address)).into()
+	}
+
+	fn lookup(address: H160) -> <Runtime::Lookup as StaticLookup>::Source {
+		Runtime::Lookup::unlookup(Runtime::AddressMapping::into_account_id(address))
+	}
+
+	fn balance_to_u256(balance: BalanceOf<Runtime, Instance>) -> U256 {
+		U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(balance))
+	}
+
+	fn u256_to_balance(value: U256) -> EvmResult<BalanceOf<Runtime, Instance>> {
+		u128::try_from(value)
+			.ok()
+			.and_then(|value| value.try_into().ok())
+			.ok_or_else(|| revert("Value is too large for the balance type"))
+	}
+}
+
+fn db_access_gas<Runtime: pallet_evm::Config>(reads: u64, writes: u64) -> u64 {
+	let weight = <Runtime as frame_system::Config>::DbWeight::get().reads_writes(reads, writes);
+	Runtime::GasWeightMapping::weight_to_gas(weight)
+}
+
+/// Proof size of reading the `pallet_assets` storage item `name`, the maximum encoded length of
+/// its key and value.
+fn storage_proof_size<Runtime, Instance>(name: &[u8]) -> u64
+where
+	Runtime: pallet_assets::Config<Instance>,
+	Instance: 'static,
+{
+	<pallet_assets::Pallet<Runtime, Instance> as StorageInfoTrait>::storage_info()
+		.into_iter()
+		.find(|info| info.storage_name == name)
+		.and_then(|info| info.max_size)
+		.map(u64::from)
+		.unwrap_or_default()
+}
+
+fn record_storage_read<Runtime, Instance>(
+	handle: &mut impl PrecompileHandle,
+	name: &[u8],
+) -> EvmResult
+where
+	Runtime: pallet_evm::Config + pallet_assets::Config<Instance>,
+	Instance: 'static,
+{
+	handle.record_cost(db_access_gas::<Runtime>(1, 0))?;
+	handle.record_external_cost(None, Some(storage_proof_size::<Runtime, Instance>(name)))?;
+	Ok(())
+}
+
+fn record_weight<Runtime: pallet_evm::Config>(
+	handle: &mut impl PrecompileHandle,
+	weight: Weight,
+) -> EvmResult {
+	handle.record_cost(Runtime::GasWeightMapping::weight_to_gas(weight))?;
+	handle.record_external_cost(None, Some(weight.proof_size()))?;
+	Ok(())
+}
+
+fn dispatch_result(result: DispatchResult) -> EvmResult {
+	result.map_err(|error| revert(<&'static str>::from(error)))
+}
+
+fn deposit_log(
+	handle: &mut impl PrecompileHandle,
+	selector: [u8; 32],
+	from: H160,
+	to: H160,
+	value: U256,
+) -> EvmResult {
+	let address = handle.context().address;
+	emit_log(
+		handle,
+		address,
+		vec![selector.into(), from.into(), to.into()],
+		encode_arguments(value),
+	)
+}
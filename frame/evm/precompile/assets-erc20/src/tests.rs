@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::assert_ok;
+use pallet_evm::{Context, Log, PrecompileFailure};
+use pallet_evm_test_vector_support::MockHandle;
+
+use super::*;
+use crate::mock::*;
+
+const ALICE: H160 = H160::repeat_byte(0xaa);
+const BOB: H160 = H160::repeat_byte(0xbb);
+const CHARLIE: H160 = H160::repeat_byte(0xcc);
+const ASSET_ID: u32 = 5;
+
+fn asset_address() -> H160 {
+	Erc20Assets::asset_id_to_address(ASSET_ID).unwrap()
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into();
+	ext.execute_with(|| {
+		assert_ok!(Assets::force_create(
+			RuntimeOrigin::root(),
+			ASSET_ID,
+			ALICE,
+			true,
+			1
+		));
+		assert_ok!(Assets::force_set_metadata(
+			RuntimeOrigin::root(),
+			ASSET_ID,
+			b"Test asset".to_vec(),
+			b"TST".to_vec(),
+			12,
+			false
+		));
+		assert_ok!(Assets::mint(
+			RuntimeOrigin::signed(ALICE),
+			ASSET_ID,
+			ALICE,
+			1000
+		));
+	});
+	ext
+}
+
+fn call(
+	address: H160,
+	caller: H160,
+	input: Vec<u8>,
+	is_static: bool,
+) -> (Option<PrecompileResult>, MockHandle) {
+	let context = Context {
+		address,
+		caller,
+		apparent_value: U256::zero(),
+	};
+	let mut handle = MockHandle::new(input, None, context);
+	handle.is_static = is_static;
+	(Erc20Assets::default().execute(&mut handle), handle)
+}
+
+fn output(result: Option<PrecompileResult>) -> Vec<u8> {
+	result
+		.expect("is a precompile")
+		.expect("call succeeds")
+		.output
+}
+
+fn erc20_log(selector: [u8; 32], from: H160, to: H160, value: u64) -> Log {
+	Log {
+		address: asset_address(),
+		topics: vec![selector.into(), from.into(), to.into()],
+		data: encode_arguments(U256::from(value)),
+	}
+}
+
+fn selector(signature: [u8; 32]) -> u32 {
+	u32::from_be_bytes(signature[..4].try_into().unwrap())
+}
+
+fn balance_of(owner: H160) -> U256 {
+	let input = encode_with_selector(selector(keccak256!("balanceOf(address)")), owner);
+	decode_arguments(&output(call(asset_address(), owner, input, true).0)).unwrap()
+}
+
+#[test]
+fn addresses_are_derived_from_asset_ids() {
+	let address = Erc20Assets::asset_id_to_address(0x01020304).unwrap();
+	assert_eq!(
+		address,
+		H160::from_slice(&[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4])
+	);
+	assert_eq!(Erc20Assets::address_to_asset_id(address), Some(0x01020304));
+
+	// Wrong prefix.
+	assert_eq!(
+		Erc20Assets::address_to_asset_id(H160::from_low_u64_be(5)),
+		None
+	);
+	// Doesn't fit in the asset id type.
+	let mut address = Erc20Assets::asset_id_to_address(0).unwrap();
+	address.as_bytes_mut()[15] = 1;
+	assert_eq!(Erc20Assets::address_to_asset_id(address), None);
+}
+
+#[test]
+fn prefix_longer_than_four_bytes_has_no_addresses() {
+	struct LongPrefix;
+	impl Get<&'static [u8]> for LongPrefix {
+		fn get() -> &'static [u8] {
+			&[0xff; 5]
+		}
+	}
+	type LongPrefixAssets = Erc20AssetsPrecompileSet<Test, LongPrefix>;
+
+	assert_eq!(LongPrefixAssets::asset_id_to_address(ASSET_ID), None);
+	let mut address = H160::repeat_byte(0xff);
+	address.as_bytes_mut()[4..].fill(0);
+	assert_eq!(LongPrefixAssets::address_to_asset_id(address), None);
+}
+
+#[test]
+fn only_existing_assets_are_precompiles() {
+	new_test_ext().execute_with(|| {
+		let is_precompile = |address| match Erc20Assets::default().is_precompile(address, 0) {
+			IsPrecompileResult::Answer { is_precompile, .. } => is_precompile,
+			_ => panic!("unexpected result"),
+		};
+
+		assert!(is_precompile(asset_address()));
+		assert!(!is_precompile(
+			Erc20Assets::asset_id_to_address(ASSET_ID + 1).unwrap()
+		));
+		assert!(!is_precompile(H160::from_low_u64_be(ASSET_ID as u64)));
+
+		let input = selector(keccak256!("totalSupply()")).to_be_bytes().to_vec();
+		let unknown = Erc20Assets::asset_id_to_address(ASSET_ID + 1).unwrap();
+		assert!(call(unknown, ALICE, input, true).0.is_none());
+	});
+}
+
+#[test]
+fn metadata_and_total_supply() {
+	new_test_ext().execute_with(|| {
+		let view = |signature: [u8; 32]| {
+			output(
+				call(
+					asset_address(),
+					ALICE,
+					selector(signature).to_be_bytes().to_vec(),
+					true,
+				)
+				.0,
+			)
+		};
+
+		assert_eq!(
+			view(keccak256!("name()")),
+			encode_arguments(String::from("Test asset"))
+		);
+		assert_eq!(
+			view(keccak256!("symbol()")),
+			encode_arguments(String::from("TST"))
+		);
+		assert_eq!(view(keccak256!("decimals()")), encode_arguments(12u8));
+		assert_eq!(
+			view(keccak256!("totalSupply()")),
+			encode_arguments(U256::from(1000))
+		);
+		assert_eq!(balance_of(ALICE), U256::from(1000));
+	});
+}
+
+#[test]
+fn transfer_moves_balance_and_logs() {
+	new_test_ext().execute_with(|| {
+		let input = encode_with_selector(
+			selector(keccak256!("transfer(address,uint256)")),
+			(BOB, U256::from(400)),
+		);
+		let (result, handle) = call(asset_address(), ALICE, input, false);
+
+		assert_eq!(output(result), encode_arguments(true));
+		assert_eq!(
+			handle.logs,
+			vec![erc20_log(SELECTOR_LOG_TRANSFER, ALICE, BOB, 400)]
+		);
+		assert_eq!(balance_of(ALICE), U256::from(600));
+		assert_eq!(balance_of(BOB), U256::from(400));
+
+		// Not enough balance.
+		let input = encode_with_selector(
+			selector(keccak256!("transfer(address,uint256)")),
+			(BOB, U256::from(601)),
+		);
+		assert!(matches!(
+			call(asset_address(), ALICE, input, false).0,
+			Some(Err(PrecompileFailure::Revert { .. }))
+		));
+	});
+}
+
+#[test]
+fn approve_replaces_allowance() {
+	new_test_ext().execute_with(|| {
+		let approve = selector(keccak256!("approve(address,uint256)"));
+		let allowance = || {
+			let input = encode_with_selector(
+				selector(keccak256!("allowance(address,address)")),
+				(ALICE, BOB),
+			);
+			decode_arguments::<U256>(&output(call(asset_address(), BOB, input, true).0)).unwrap()
+		};
+
+		let input = encode_with_selector(approve, (BOB, U256::from(500)));
+		let (result, handle) = call(asset_address(), ALICE, input, false);
+		assert_eq!(output(result), encode_arguments(true));
+		assert_eq!(
+			handle.logs,
+			vec![erc20_log(SELECTOR_LOG_APPROVAL, ALICE, BOB, 500)]
+		);
+		assert_eq!(allowance(), U256::from(500));
+
+		let input = encode_with_selector(approve, (BOB, U256::from(200)));
+		assert_eq!(
+			output(call(asset_address(), ALICE, input, false).0),
+			encode_arguments(true)
+		);
+		assert_eq!(allowance(), U256::from(200));
+
+		let input = encode_with_selector(approve, (BOB, U256::zero()));
+		assert_eq!(
+			output(call(asset_address(), ALICE, input, false).0),
+			encode_arguments(true)
+		);
+		assert_eq!(allowance(), U256::zero());
+	});
+}
+
+#[test]
+fn transfer_from_spends_allowance() {
+	new_test_ext().execute_with(|| {
+		let input = encode_with_selector(
+			selector(keccak256!("approve(address,uint256)")),
+			(BOB, U256::from(500)),
+		);
+		assert_eq!(
+			output(call(asset_address(), ALICE, input, false).0),
+			encode_arguments(true)
+		);
+
+		let transfer_from = selector(keccak256!("transferFrom(address,address,uint256)"));
+		let input = encode_with_selector(transfer_from, (ALICE, CHARLIE, U256::from(300)));
+		let (result, handle) = call(asset_address(), BOB, input, false);
+		assert_eq!(output(result), encode_arguments(true));
+		assert_eq!(
+			handle.logs,
+			vec![erc20_log(SELECTOR_LOG_TRANSFER, ALICE, CHARLIE, 300)]
+		);
+		assert_eq!(balance_of(ALICE), U256::from(700));
+		assert_eq!(balance_of(CHARLIE), U256::from(300));
+
+		// Only 200 left to spend.
+		let input = encode_with_selector(transfer_from, (ALICE, CHARLIE, U256::from(201)));
+		assert!(matches!(
+			call(asset_address(), BOB, input, false).0,
+			Some(Err(PrecompileFailure::Revert { .. }))
+		));
+		assert_eq!(balance_of(CHARLIE), U256::from(300));
+	});
+}
+
+#[test]
+fn views_record_the_proof_size_of_the_storage_read() {
+	new_test_ext().execute_with(|| {
+		// `Asset`: 16 (hash) + 4 (asset id) + 134 (`AssetDetails`).
+		let asset = 154;
+		// `Metadata`: 16 (hash) + 4 (asset id) + 112 (`AssetMetadata` with 50 bytes strings).
+		let metadata = 132;
+		assert_eq!(storage_proof_size::<Test, ()>(b"Asset"), asset);
+		assert_eq!(storage_proof_size::<Test, ()>(b"Metadata"), metadata);
+
+		let input = selector(keccak256!("decimals()")).to_be_bytes().to_vec();
+		let (result, handle) = call(asset_address(), ALICE, input, true);
+		output(result);
+		assert_eq!(handle.proof_size_used, asset + metadata);
+	});
+}
+
+#[test]
+fn calls_record_the_proof_size_of_their_weight() {
+	new_test_ext().execute_with(|| {
+		let input = encode_with_selector(
+			selector(keccak256!("transfer(address,uint256)")),
+			(BOB, U256::from(400)),
+		);
+		let (result, handle) = call(asset_address(), ALICE, input, false);
+		output(result);
+		assert_eq!(
+			handle.proof_size_used,
+			storage_proof_size::<Test, ()>(b"Asset")
+				+ <() as pallet_assets::WeightInfo>::transfer().proof_size()
+		);
+	});
+}
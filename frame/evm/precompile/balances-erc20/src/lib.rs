@@ -0,0 +1,273 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ERC-20 interface to the native currency of the EVM pallet.
+//!
+//! Balances are read from and transferred with `pallet_evm::Config::Currency`, the Ethereum
+//! addresses being converted with `pallet_evm::Config::AddressMapping`. Allowances are kept in
+//! the [`Allowances`] storage map, under the prefix of the precompile [`InstancePrefix`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+extern crate alloc;
+
+use alloc::{string::String, vec};
+use core::marker::PhantomData;
+
+use frame_support::{
+	storage::types::{StorageDoubleMap, ValueQuery},
+	traits::{Currency, ExistenceRequirement, Get, StorageInstance},
+	Blake2_128Concat,
+};
+use sp_runtime::traits::UniqueSaturatedInto;
+
+// Frontier
+use fp_evm::{ACCOUNT_BASIC_PROOF_SIZE, WRITE_PROOF_SIZE};
+use pallet_evm::{AddressMapping, BalanceOf, GasWeightMapping};
+use precompile_utils::prelude::*;
+
+/// `keccak256("Transfer(address,address,uint256)")`
+pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+
+/// `keccak256("Approval(address,address,uint256)")`
+pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
+
+/// `TotalIssuance` read, 16 (u128 balance).
+pub const TOTAL_ISSUANCE_PROOF_SIZE: u64 = 16;
+
+/// `Allowances` read, 16 (hash1) + 20 (key1) + 16 (hash2) + 20 (key2) + 32 (value).
+pub const ALLOWANCE_PROOF_SIZE: u64 = 104;
+
+/// Pallet prefix of the storage of an instance of the precompile, several instances in a
+/// runtime needing distinct prefixes to keep separate allowances.
+pub trait InstancePrefix {
+	const PREFIX: &'static str;
+}
+
+impl InstancePrefix for () {
+	const PREFIX: &'static str = "Erc20Balances";
+}
+
+/// Storage prefix of [`Allowances`].
+pub struct AllowancesPrefix<Instance>(PhantomData<Instance>);
+
+impl<Instance: InstancePrefix> StorageInstance for AllowancesPrefix<Instance> {
+	const STORAGE_PREFIX: &'static str = "Allowances";
+
+	fn pallet_prefix() -> &'static str {
+		Instance::PREFIX
+	}
+}
+
+/// Amount `spender` is allowed to transfer from `owner`, keyed by `(owner, spender)`.
+pub type Allowances<Instance = ()> = StorageDoubleMap<
+	AllowancesPrefix<Instance>,
+	Blake2_128Concat,
+	H160,
+	Blake2_128Concat,
+	H160,
+	U256,
+	ValueQuery,
+>;
+
+/// Metadata of the native currency returned by the optional ERC-20 getters.
+pub trait Erc20Metadata {
+	fn name() -> &'static str;
+	fn symbol() -> &'static str;
+	fn decimals() -> u8;
+}
+
+/// ERC-20 precompile over `pallet_evm::Config::Currency`.
+pub struct Erc20BalancesPrecompile<Runtime, Metadata, Instance = ()>(
+	PhantomData<(Runtime, Metadata, Instance)>,
+);
+
+#[precompile]
+impl<Runtime, Metadata, Instance> Erc20BalancesPrecompile<Runtime, Metadata, Instance>
+where
+	Runtime: pallet_evm::Config,
+	Metadata: Erc20Metadata,
+	Instance: InstancePrefix,
+{
+	#[precompile::public("name()")]
+	#[precompile::view]
+	fn name(_handle: &mut impl PrecompileHandle) -> EvmResult<String> {
+		Ok(String::from(Metadata::name()))
+	}
+
+	#[precompile::public("symbol()")]
+	#[precompile::view]
+	fn symbol(_handle: &mut impl PrecompileHandle) -> EvmResult<String> {
+		Ok(String::from(Metadata::symbol()))
+	}
+
+	#[precompile::public("decimals()")]
+	#[precompile::view]
+	fn decimals(_handle: &mut impl PrecompileHandle) -> EvmResult<u8> {
+		Ok(Metadata::decimals())
+	}
+
+	#[precompile::public("totalSupply()")]
+	#[precompile::view]
+	fn total_supply(handle: &mut impl PrecompileHandle) -> EvmResult<U256> {
+		Self::record_db_read(handle, TOTAL_ISSUANCE_PROOF_SIZE)?;
+
+		Ok(Self::balance_to_u256(Runtime::Currency::total_issuance()))
+	}
+
+	#[precompile::public("balanceOf(address)")]
+	#[precompile::view]
+	fn balance_of(handle: &mut impl PrecompileHandle, owner: H160) -> EvmResult<U256> {
+		Self::record_db_read(handle, ACCOUNT_BASIC_PROOF_SIZE)?;
+
+		let owner = Runtime::AddressMapping::into_account_id(owner);
+		Ok(Self::balance_to_u256(Runtime::Currency::free_balance(
+			&owner,
+		)))
+	}
+
+	#[precompile::public("allowance(address,address)")]
+	#[precompile::view]
+	fn allowance(
+		handle: &mut impl PrecompileHandle,
+		owner: H160,
+		spender: H160,
+	) -> EvmResult<U256> {
+		Self::record_db_read(handle, ALLOWANCE_PROOF_SIZE)?;
+
+		Ok(Allowances::<Instance>::get(owner, spender))
+	}
+
+	#[precompile::public("approve(address,uint256)")]
+	fn approve(handle: &mut impl PrecompileHandle, spender: H160, value: U256) -> EvmResult<bool> {
+		Self::record_db_writes(handle, 1)?;
+
+		let owner = handle.context().caller;
+		Allowances::<Instance>::insert(owner, spender, value);
+
+		Self::deposit_log(handle, SELECTOR_LOG_APPROVAL, owner, spender, value)?;
+		Ok(true)
+	}
+
+	#[precompile::public("transfer(address,uint256)")]
+	fn transfer(handle: &mut impl PrecompileHandle, to: H160, value: U256) -> EvmResult<bool> {
+		let from = handle.context().caller;
+		Self::transfer_balance(handle, from, to, value)?;
+
+		Self::deposit_log(handle, SELECTOR_LOG_TRANSFER, from, to, value)?;
+		Ok(true)
+	}
+
+	#[precompile::public("transferFrom(address,address,uint256)")]
+	fn transfer_from(
+		handle: &mut impl PrecompileHandle,
+		from: H160,
+		to: H160,
+		value: U256,
+	) -> EvmResult<bool> {
+		let caller = handle.context().caller;
+		if caller != from {
+			Self::record_db_read(handle, ALLOWANCE_PROOF_SIZE)?;
+			let allowed = Allowances::<Instance>::get(from, caller);
+			let remaining = allowed
+				.checked_sub(value)
+				.ok_or_else(|| revert("Trying to spend more than allowed"))?;
+
+			// Like most ERC-20 implementations, a maximum allowance is never decreased.
+			if allowed != U256::MAX {
+				Self::record_db_writes(handle, 1)?;
+				Allowances::<Instance>::insert(from, caller, remaining);
+			}
+		}
+
+		Self::transfer_balance(handle, from, to, value)?;
+
+		Self::deposit_log(handle, SELECTOR_LOG_TRANSFER, from, to, value)?;
+		Ok(true)
+	}
+}
+
+impl<Runtime, Metadata, Instance> Erc20BalancesPrecompile<Runtime, Metadata, Instance>
+where
+	Runtime: pallet_evm::Config,
+{
+	/// Records reading a storage item whose key and value encode in at most `proof_size` bytes.
+	fn record_db_read(handle: &mut impl PrecompileHandle, proof_size: u64) -> EvmResult {
+		let weight = <Runtime as frame_system::Config>::DbWeight::get().reads(1);
+		handle.record_cost(Runtime::GasWeightMapping::weight_to_gas(weight))?;
+		handle.record_external_cost(None, Some(proof_size))?;
+		Ok(())
+	}
+
+	fn record_db_writes(handle: &mut impl PrecompileHandle, writes: u64) -> EvmResult {
+		let weight = <Runtime as frame_system::Config>::DbWeight::get().writes(writes);
+		handle.record_cost(Runtime::GasWeightMapping::weight_to_gas(weight))?;
+		handle.record_external_cost(None, Some(writes.saturating_mul(WRITE_PROOF_SIZE)))?;
+		Ok(())
+	}
+
+	fn balance_to_u256(balance: BalanceOf<Runtime>) -> U256 {
+		U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(balance))
+	}
+
+	fn transfer_balance(
+		handle: &mut impl PrecompileHandle,
+		from: H160,
+		to: H160,
+		value: U256,
+	) -> EvmResult {
+		// Reads and writes the account of both parties.
+		Self::record_db_read(handle, ACCOUNT_BASIC_PROOF_SIZE)?;
+		Self::record_db_read(handle, ACCOUNT_BASIC_PROOF_SIZE)?;
+		Self::record_db_writes(handle, 2)?;
+
+		let value: BalanceOf<Runtime> = u128::try_from(value)
+			.ok()
+			.and_then(|value| value.try_into().ok())
+			.ok_or_else(|| revert("Value is too large for the balance type"))?;
+
+		Runtime::Currency::transfer(
+			&Runtime::AddressMapping::into_account_id(from),
+			&Runtime::AddressMapping::into_account_id(to),
+			value,
+			ExistenceRequirement::AllowDeath,
+		)
+		.map_err(|error| revert(<&'static str>::from(error)))
+	}
+
+	fn deposit_log(
+		handle: &mut impl PrecompileHandle,
+		selector: [u8; 32],
+		from: H160,
+		to: H160,
+		value: U256,
+	) -> EvmResult {
+		let address = handle.context().address;
+		emit_log(
+			handle,
+			address,
+			vec![selector.into(), from.into(), to.into()],
+			encode_arguments(value),
+		)
+	}
+}
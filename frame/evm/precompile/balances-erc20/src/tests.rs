@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pallet_evm::{Context, Log, Precompile, PrecompileFailure};
+use pallet_evm_test_vector_support::MockHandle;
+
+use super::*;
+use crate::mock::*;
+
+const ALICE: H160 = H160::repeat_byte(0xaa);
+const BOB: H160 = H160::repeat_byte(0xbb);
+const CHARLIE: H160 = H160::repeat_byte(0xcc);
+
+fn precompile_address() -> H160 {
+	H160::from_low_u64_be(2050)
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, 1000)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	t.into()
+}
+
+fn call(caller: H160, input: Vec<u8>, is_static: bool) -> (PrecompileResult, MockHandle) {
+	let context = Context {
+		address: precompile_address(),
+		caller,
+		apparent_value: U256::zero(),
+	};
+	let mut handle = MockHandle::new(input, None, context);
+	handle.is_static = is_static;
+	(Erc20::execute(&mut handle), handle)
+}
+
+fn output(result: PrecompileResult) -> Vec<u8> {
+	result.expect("call succeeds").output
+}
+
+fn erc20_log(selector: [u8; 32], from: H160, to: H160, value: u64) -> Log {
+	Log {
+		address: precompile_address(),
+		topics: vec![selector.into(), from.into(), to.into()],
+		data: encode_arguments(U256::from(value)),
+	}
+}
+
+fn selector(signature: [u8; 32]) -> u32 {
+	u32::from_be_bytes(signature[..4].try_into().unwrap())
+}
+
+fn balance_of(owner: H160) -> U256 {
+	let input = encode_with_selector(selector(keccak256!("balanceOf(address)")), owner);
+	decode_arguments(&output(call(owner, input, true).0)).unwrap()
+}
+
+#[test]
+fn metadata_and_total_supply() {
+	new_test_ext().execute_with(|| {
+		let (result, _) = call(
+			ALICE,
+			selector(keccak256!("name()")).to_be_bytes().to_vec(),
+			true,
+		);
+		assert_eq!(output(result), encode_arguments(String::from("Unit")));
+
+		let (result, _) = call(
+			ALICE,
+			selector(keccak256!("decimals()")).to_be_bytes().to_vec(),
+			true,
+		);
+		assert_eq!(output(result), encode_arguments(18u8));
+
+		let (result, handle) = call(
+			ALICE,
+			selector(keccak256!("totalSupply()")).to_be_bytes().to_vec(),
+			true,
+		);
+		assert_eq!(output(result), encode_arguments(U256::from(1000)));
+		assert!(handle.gas_used > 0);
+
+		assert_eq!(balance_of(ALICE), U256::from(1000));
+		assert_eq!(balance_of(BOB), U256::zero());
+	});
+}
+
+#[test]
+fn transfer_moves_balance_and_logs() {
+	new_test_ext().execute_with(|| {
+		let input = encode_with_selector(
+			selector(keccak256!("transfer(address,uint256)")),
+			(BOB, U256::from(400)),
+		);
+		let (result, handle) = call(ALICE, input, false);
+
+		assert_eq!(output(result), encode_arguments(true));
+		assert_eq!(
+			handle.logs,
+			vec![erc20_log(SELECTOR_LOG_TRANSFER, ALICE, BOB, 400)]
+		);
+		assert_eq!(balance_of(ALICE), U256::from(600));
+		assert_eq!(balance_of(BOB), U256::from(400));
+	});
+}
+
+#[test]
+fn transfer_reverts() {
+	new_test_ext().execute_with(|| {
+		let transfer = selector(keccak256!("transfer(address,uint256)"));
+
+		// Not enough balance.
+		let input = encode_with_selector(transfer, (BOB, U256::from(1001)));
+		assert!(matches!(
+			call(ALICE, input, false).0,
+			Err(PrecompileFailure::Revert { .. })
+		));
+
+		// Doesn't fit in the balance type.
+		let input = encode_with_selector(transfer, (BOB, U256::MAX));
+		assert!(matches!(
+			call(ALICE, input, false).0,
+			Err(PrecompileFailure::Revert { .. })
+		));
+
+		// Static context.
+		let input = encode_with_selector(transfer, (BOB, U256::from(1)));
+		assert!(matches!(
+			call(ALICE, input, true).0,
+			Err(PrecompileFailure::Revert { .. })
+		));
+
+		assert_eq!(balance_of(ALICE), U256::from(1000));
+	});
+}
+
+#[test]
+fn approve_and_transfer_from() {
+	new_test_ext().execute_with(|| {
+		let input = encode_with_selector(
+			selector(keccak256!("approve(address,uint256)")),
+			(BOB, U256::from(500)),
+		);
+		let (result, handle) = call(ALICE, input, false);
+		assert_eq!(output(result), encode_arguments(true));
+		assert_eq!(
+			handle.logs,
+			vec![erc20_log(SELECTOR_LOG_APPROVAL, ALICE, BOB, 500)]
+		);
+
+		let transfer_from = selector(keccak256!("transferFrom(address,address,uint256)"));
+		let input = encode_with_selector(transfer_from, (ALICE, CHARLIE, U256::from(300)));
+		let (result, handle) = call(BOB, input, false);
+		assert_eq!(output(result), encode_arguments(true));
+		assert_eq!(
+			handle.logs,
+			vec![erc20_log(SELECTOR_LOG_TRANSFER, ALICE, CHARLIE, 300)]
+		);
+		assert_eq!(balance_of(ALICE), U256::from(700));
+		assert_eq!(balance_of(CHARLIE), U256::from(300));
+
+		let input = encode_with_selector(
+			selector(keccak256!("allowance(address,address)")),
+			(ALICE, BOB),
+		);
+		assert_eq!(
+			output(call(BOB, input, true).0),
+			encode_arguments(U256::from(200))
+		);
+
+		// Spending more than the remaining allowance reverts.
+		let input = encode_with_selector(transfer_from, (ALICE, CHARLIE, U256::from(201)));
+		assert_eq!(
+			call(BOB, input, false).0,
+			Err(revert("Trying to spend more than allowed"))
+		);
+		assert_eq!(balance_of(CHARLIE), U256::from(300));
+	});
+}
+
+#[test]
+fn max_allowance_is_not_decreased() {
+	new_test_ext().execute_with(|| {
+		Allowances::<()>::insert(ALICE, BOB, U256::MAX);
+
+		let input = encode_with_selector(
+			selector(keccak256!("transferFrom(address,address,uint256)")),
+			(ALICE, CHARLIE, U256::from(100)),
+		);
+		assert_eq!(output(call(BOB, input, false).0), encode_arguments(true));
+		assert_eq!(Allowances::<()>::get(ALICE, BOB), U256::MAX);
+	});
+}
+
+#[test]
+fn storage_accesses_record_their_proof_size() {
+	new_test_ext().execute_with(|| {
+		let input = encode_with_selector(selector(keccak256!("balanceOf(address)")), ALICE);
+		let (result, handle) = call(ALICE, input, true);
+		output(result);
+		assert_eq!(handle.proof_size_used, ACCOUNT_BASIC_PROOF_SIZE);
+
+		let input = encode_with_selector(
+			selector(keccak256!("approve(address,uint256)")),
+			(BOB, U256::from(400)),
+		);
+		let (result, handle) = call(ALICE, input, false);
+		output(result);
+		assert_eq!(handle.proof_size_used, WRITE_PROOF_SIZE);
+
+		let input = encode_with_selector(
+			selector(keccak256!("transferFrom(address,address,uint256)")),
+			(ALICE, CHARLIE, U256::from(400)),
+		);
+		let (result, handle) = call(BOB, input, false);
+		output(result);
+		assert_eq!(
+			handle.proof_size_used,
+			ALLOWANCE_PROOF_SIZE + 2 * ACCOUNT_BASIC_PROOF_SIZE + 3 * WRITE_PROOF_SIZE
+		);
+	});
+}
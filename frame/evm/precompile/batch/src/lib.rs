@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Precompile running several subcalls on behalf of its caller.
+//!
+//! Subcalls are made with the caller of the precompile as `msg.sender` and `value` source, so
+//! it must not accept delegate calls.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+// Substrate
+use sp_core::ConstU32;
+// Frontier
+use fp_evm::{Context, ExitError, ExitReason, PrecompileFailure, Transfer};
+use precompile_utils::{logs::log_cost, prelude::*};
+
+/// Maximum number of subcalls of a batch.
+pub const ARRAY_LIMIT: u32 = 2u32.pow(9);
+
+/// Maximum length of the call data of a subcall.
+pub const CALL_DATA_LIMIT: u32 = 2u32.pow(16);
+
+// The arguments are bounded, as their pointers can alias the same data and make decoding them
+// allocate much more memory than the input holds.
+type BoundedArray<T> = BoundedVec<T, ConstU32<ARRAY_LIMIT>>;
+type CallData = BoundedBytes<ConstU32<CALL_DATA_LIMIT>>;
+
+/// `keccak256("SubcallSucceeded(uint256)")`
+pub const LOG_SUBCALL_SUCCEEDED: [u8; 32] = keccak256!("SubcallSucceeded(uint256)");
+
+/// `keccak256("SubcallFailed(uint256)")`
+pub const LOG_SUBCALL_FAILED: [u8; 32] = keccak256!("SubcallFailed(uint256)");
+
+/// What to do when a subcall fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+	/// Continue with the next subcalls.
+	BatchSome,
+	/// Don't make the next subcalls.
+	BatchSomeUntilFailure,
+	/// Revert all subcalls.
+	BatchAll,
+}
+
+/// Batch precompile.
+///
+/// Every function takes the addresses, values, call data and gas limits of the subcalls. Only
+/// the addresses are mandatory: missing values and call data are empty, and a missing or zero
+/// gas limit forwards all the gas allowed by EIP-150, once the cost of the call is paid.
+pub struct BatchPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile]
+impl<Runtime> BatchPrecompile<Runtime>
+where
+	Runtime: pallet_evm::Config,
+{
+	#[precompile::public("batchSome(address[],uint256[],bytes[],uint64[])")]
+	fn batch_some(
+		handle: &mut impl PrecompileHandle,
+		to: BoundedArray<H160>,
+		value: BoundedArray<U256>,
+		call_data: BoundedArray<CallData>,
+		gas_limit: BoundedArray<u64>,
+	) -> EvmResult {
+		Self::batch(
+			handle,
+			Mode::BatchSome,
+			to.into(),
+			value.into(),
+			call_data.into(),
+			gas_limit.into(),
+		)
+	}
+
+	#[precompile::public("batchSomeUntilFailure(address[],uint256[],bytes[],uint64[])")]
+	fn batch_some_until_failure(
+		handle: &mut impl PrecompileHandle,
+		to: BoundedArray<H160>,
+		value: BoundedArray<U256>,
+		call_data: BoundedArray<CallData>,
+		gas_limit: BoundedArray<u64>,
+	) -> EvmResult {
+		Self::batch(
+			handle,
+			Mode::BatchSomeUntilFailure,
+			to.into(),
+			value.into(),
+			call_data.into(),
+			gas_limit.into(),
+		)
+	}
+
+	#[precompile::public("batchAll(address[],uint256[],bytes[],uint64[])")]
+	fn batch_all(
+		handle: &mut impl PrecompileHandle,
+		to: BoundedArray<H160>,
+		value: BoundedArray<U256>,
+		call_data: BoundedArray<CallData>,
+		gas_limit: BoundedArray<u64>,
+	) -> EvmResult {
+		Self::batch(
+			handle,
+			Mode::BatchAll,
+			to.into(),
+			value.into(),
+			call_data.into(),
+			gas_limit.into(),
+		)
+	}
+}
+
+impl<Runtime> BatchPrecompile<Runtime>
+where
+	Runtime: pallet_evm::Config,
+{
+	pub fn batch(
+		handle: &mut impl PrecompileHandle,
+		mode: Mode,
+		to: Vec<H160>,
+		value: Vec<U256>,
+		call_data: Vec<CallData>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		if value.len() > to.len() || call_data.len() > to.len() || gas_limit.len() > to.len() {
+			return Err(revert(
+				"More values, call data or gas limits than addresses",
+			));
+		}
+
+		let caller = handle.context().caller;
+		let mut call_data = call_data.into_iter();
+		for (index, address) in to.into_iter().enumerate() {
+			let value = value.get(index).copied().unwrap_or_default();
+			let input = call_data.next().map(Vec::from).unwrap_or_default();
+
+			// The log is paid upfront so the subcall can't consume the gas it needs.
+			handle.record_cost(log_cost(1, 32))?;
+
+			let forwarded_gas = forwardable_gas(handle.remaining_gas(), value, Runtime::config());
+			let gas_limit = match gas_limit.get(index).copied() {
+				None | Some(0) => forwarded_gas,
+				Some(gas_limit) if gas_limit <= forwarded_gas => gas_limit,
+				Some(_) => match mode {
+					Mode::BatchAll => return Err(ExitError::OutOfGas.into()),
+					Mode::BatchSome => {
+						Self::log_subcall(handle, LOG_SUBCALL_FAILED, index)?;
+						continue;
+					}
+					Mode::BatchSomeUntilFailure => {
+						Self::log_subcall(handle, LOG_SUBCALL_FAILED, index)?;
+						break;
+					}
+				},
+			};
+
+			let transfer = (!value.is_zero()).then_some(Transfer {
+				source: caller,
+				target: address,
+				value,
+			});
+			let context = Context {
+				address,
+				caller,
+				apparent_value: value,
+			};
+			let (reason, output) =
+				handle.call(address, transfer, input, Some(gas_limit), false, &context);
+
+			let failure = match reason {
+				ExitReason::Succeed(_) => {
+					Self::log_subcall(handle, LOG_SUBCALL_SUCCEEDED, index)?;
+					continue;
+				}
+				ExitReason::Revert(exit_status) => PrecompileFailure::Revert {
+					exit_status,
+					output,
+				},
+				ExitReason::Error(exit_status) => PrecompileFailure::Error { exit_status },
+				ExitReason::Fatal(exit_status) => {
+					return Err(PrecompileFailure::Fatal { exit_status })
+				}
+			};
+
+			match mode {
+				Mode::BatchAll => return Err(failure),
+				Mode::BatchSome => Self::log_subcall(handle, LOG_SUBCALL_FAILED, index)?,
+				Mode::BatchSomeUntilFailure => {
+					Self::log_subcall(handle, LOG_SUBCALL_FAILED, index)?;
+					break;
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Emit a log whose cost was already recorded.
+	fn log_subcall(handle: &mut impl PrecompileHandle, topic: [u8; 32], index: usize) -> EvmResult {
+		let address = handle.context().address;
+		handle.log(
+			address,
+			alloc::vec![topic.into()],
+			encode_arguments(U256::from(index)),
+		)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,265 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use fp_evm::{ExitRevert, Log, Precompile};
+use pallet_evm_test_vector_support::MockHandle;
+
+use super::*;
+use crate::mock::Batch;
+
+const BATCH: H160 = H160::repeat_byte(0xba);
+const ALICE: H160 = H160::repeat_byte(0xaa);
+
+fn new_handle(input: Vec<u8>) -> MockHandle {
+	let context = Context {
+		address: BATCH,
+		caller: ALICE,
+		apparent_value: U256::zero(),
+	};
+	let mut handle = MockHandle::new(input, Some(100_000), context);
+	handle.enforce_gas_limit = true;
+	handle
+}
+
+fn selector(signature: [u8; 32]) -> u32 {
+	u32::from_be_bytes(signature[..4].try_into().unwrap())
+}
+
+fn batch_input(signature: [u8; 32], call_data: &[&[u8]], gas_limit: Vec<u64>) -> Vec<u8> {
+	let to: Vec<H160> = (1..=call_data.len() as u64)
+		.map(H160::from_low_u64_be)
+		.collect();
+	let call_data = call_data
+		.iter()
+		.map(|input| Bytes::from(*input))
+		.collect::<Vec<_>>();
+	encode_with_selector(
+		selector(signature),
+		(to, vec![U256::from(7)], call_data, gas_limit),
+	)
+}
+
+fn subcall_log(topic: [u8; 32], index: u64) -> Log {
+	Log {
+		address: BATCH,
+		topics: vec![topic.into()],
+		data: encode_arguments(U256::from(index)),
+	}
+}
+
+#[test]
+fn subcalls_are_made_on_behalf_of_the_caller() {
+	let input = batch_input(
+		keccak256!("batchAll(address[],uint256[],bytes[],uint64[])"),
+		&[b"first", b"second"],
+		vec![0, 5000],
+	);
+	let mut handle = new_handle(input);
+
+	assert!(Batch::execute(&mut handle).is_ok());
+
+	let subcalls = &handle.subcalls;
+	assert_eq!(subcalls.len(), 2);
+	assert_eq!(subcalls[0].address, H160::from_low_u64_be(1));
+	assert_eq!(subcalls[0].input, b"first");
+	assert_eq!(subcalls[0].context.caller, ALICE);
+	assert_eq!(
+		subcalls[0].transfer,
+		Some(Transfer {
+			source: ALICE,
+			target: H160::from_low_u64_be(1),
+			value: U256::from(7),
+		})
+	);
+	// All the gas allowed by EIP-150 is forwarded by default, once a cold call transferring
+	// value is paid.
+	let available = 100_000 - log_cost(1, 32) - 2_600 - 34_000;
+	assert_eq!(subcalls[0].gas_limit, Some(available - available / 64));
+
+	assert_eq!(subcalls[1].transfer, None);
+	assert_eq!(subcalls[1].context.apparent_value, U256::zero());
+	assert_eq!(subcalls[1].gas_limit, Some(5000));
+
+	assert_eq!(
+		handle.logs,
+		vec![
+			subcall_log(LOG_SUBCALL_SUCCEEDED, 0),
+			subcall_log(LOG_SUBCALL_SUCCEEDED, 1),
+		]
+	);
+}
+
+#[test]
+fn batch_some_continues_after_failure() {
+	let input = batch_input(
+		keccak256!("batchSome(address[],uint256[],bytes[],uint64[])"),
+		&[b"revert", b"ok"],
+		vec![],
+	);
+	let mut handle = new_handle(input);
+
+	assert!(Batch::execute(&mut handle).is_ok());
+	assert_eq!(handle.subcalls.len(), 2);
+	assert_eq!(
+		handle.logs,
+		vec![
+			subcall_log(LOG_SUBCALL_FAILED, 0),
+			subcall_log(LOG_SUBCALL_SUCCEEDED, 1),
+		]
+	);
+}
+
+#[test]
+fn batch_some_until_failure_stops_at_failure() {
+	let input = batch_input(
+		keccak256!("batchSomeUntilFailure(address[],uint256[],bytes[],uint64[])"),
+		&[b"ok", b"revert", b"ok"],
+		vec![],
+	);
+	let mut handle = new_handle(input);
+
+	assert!(Batch::execute(&mut handle).is_ok());
+	assert_eq!(handle.subcalls.len(), 2);
+	assert_eq!(
+		handle.logs,
+		vec![
+			subcall_log(LOG_SUBCALL_SUCCEEDED, 0),
+			subcall_log(LOG_SUBCALL_FAILED, 1),
+		]
+	);
+}
+
+#[test]
+fn batch_all_reverts_on_failure() {
+	let input = batch_input(
+		keccak256!("batchAll(address[],uint256[],bytes[],uint64[])"),
+		&[b"ok", b"revert", b"ok"],
+		vec![],
+	);
+	let mut handle = new_handle(input);
+
+	assert_eq!(
+		Batch::execute(&mut handle),
+		Err(PrecompileFailure::Revert {
+			exit_status: ExitRevert::Reverted,
+			output: b"reason".to_vec(),
+		})
+	);
+	assert_eq!(handle.subcalls.len(), 2);
+}
+
+#[test]
+fn gas_limit_above_remaining_gas() {
+	let batch_some = batch_input(
+		keccak256!("batchSome(address[],uint256[],bytes[],uint64[])"),
+		&[b"ok", b"ok"],
+		vec![1_000_000],
+	);
+	let mut handle = new_handle(batch_some);
+	assert!(Batch::execute(&mut handle).is_ok());
+	assert_eq!(handle.subcalls.len(), 1);
+	assert_eq!(
+		handle.logs,
+		vec![
+			subcall_log(LOG_SUBCALL_FAILED, 0),
+			subcall_log(LOG_SUBCALL_SUCCEEDED, 1),
+		]
+	);
+
+	let batch_all = batch_input(
+		keccak256!("batchAll(address[],uint256[],bytes[],uint64[])"),
+		&[b"ok"],
+		vec![1_000_000],
+	);
+	let mut handle = new_handle(batch_all);
+	assert_eq!(Batch::execute(&mut handle), Err(ExitError::OutOfGas.into()));
+	assert!(handle.subcalls.is_empty());
+}
+
+#[test]
+fn gas_limit_leaves_the_call_cost_and_a_64th() {
+	// 98_994 gas remain once the log is paid: a cold call transferring value costs 36_600, and
+	// 1/64 of the rest is kept, leaving 61_420 gas to forward.
+	let input = batch_input(
+		keccak256!("batchAll(address[],uint256[],bytes[],uint64[])"),
+		&[b"ok"],
+		vec![61_421],
+	);
+	let mut handle = new_handle(input);
+	assert_eq!(Batch::execute(&mut handle), Err(ExitError::OutOfGas.into()));
+	assert!(handle.subcalls.is_empty());
+
+	let input = batch_input(
+		keccak256!("batchAll(address[],uint256[],bytes[],uint64[])"),
+		&[b"ok"],
+		vec![61_420],
+	);
+	let mut handle = new_handle(input);
+	assert!(Batch::execute(&mut handle).is_ok());
+	assert_eq!(handle.subcalls[0].gas_limit, Some(61_420));
+}
+
+#[test]
+fn more_values_than_addresses_reverts() {
+	let input = encode_with_selector(
+		selector(keccak256!("batchAll(address[],uint256[],bytes[],uint64[])")),
+		(
+			vec![H160::from_low_u64_be(1)],
+			vec![U256::zero(), U256::zero()],
+			Vec::<Bytes>::new(),
+			Vec::<u64>::new(),
+		),
+	);
+	let mut handle = new_handle(input);
+	assert_eq!(
+		Batch::execute(&mut handle),
+		Err(revert(
+			"More values, call data or gas limits than addresses"
+		))
+	);
+}
+
+#[test]
+fn arguments_above_their_limit_revert() {
+	let call_data = [0u8; CALL_DATA_LIMIT as usize + 1];
+	let input = batch_input(
+		keccak256!("batchAll(address[],uint256[],bytes[],uint64[])"),
+		&[&call_data[..]],
+		vec![],
+	);
+	let mut handle = new_handle(input);
+	assert_eq!(
+		Batch::execute(&mut handle),
+		Err(revert("Bytes length is larger than the limit"))
+	);
+
+	let input = encode_with_selector(
+		selector(keccak256!("batchAll(address[],uint256[],bytes[],uint64[])")),
+		(
+			vec![H160::from_low_u64_be(1); ARRAY_LIMIT as usize + 1],
+			Vec::<U256>::new(),
+			Vec::<Bytes>::new(),
+			Vec::<u64>::new(),
+		),
+	);
+	let mut handle = new_handle(input);
+	assert_eq!(
+		Batch::execute(&mut handle),
+		Err(revert("Array length is larger than the limit"))
+	);
+	assert!(handle.subcalls.is_empty());
+}
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Precompile reading block hashes older than the 256 blocks reachable with `BLOCKHASH`, in
+//! the spirit of EIP-2935.
+//!
+//! Hashes are read from a `pallet_evm::BlockHashMapping` backed by a bounded history, such as
+//! `pallet_ethereum::HistoricalBlockHashMapping` whose length is configured with
+//! `pallet_ethereum::Config::HistoricalBlockHashCount`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use core::marker::PhantomData;
+
+use frame_support::traits::Get;
+
+// Frontier
+use pallet_evm::{BlockHashMapping, GasWeightMapping};
+use precompile_utils::prelude::*;
+
+/// Proof size of a `pallet_ethereum::HistoricalBlockHashes` entry: the storage prefix, the
+/// `Twox64Concat` hashed slot and the block number and hash.
+pub const ENTRY_PROOF_SIZE: u64 = 32 + 12 + 64;
+
+/// Block hash history precompile.
+pub struct BlockHashHistoryPrecompile<Runtime, History>(PhantomData<(Runtime, History)>);
+
+#[precompile]
+impl<Runtime, History> BlockHashHistoryPrecompile<Runtime, History>
+where
+	Runtime: pallet_evm::Config,
+	History: BlockHashMapping,
+{
+	/// Hash of block `number`, or zero if it isn't in the history.
+	#[precompile::public("blockHash(uint256)")]
+	#[precompile::view]
+	fn block_hash(handle: &mut impl PrecompileHandle, number: U256) -> EvmResult<H256> {
+		let weight = <Runtime as frame_system::Config>::DbWeight::get().reads(1);
+		handle.record_cost(Runtime::GasWeightMapping::weight_to_gas(weight))?;
+		handle.record_external_cost(None, Some(ENTRY_PROOF_SIZE))?;
+
+		Ok(u32::try_from(number)
+			.map(History::block_hash)
+			.unwrap_or_default())
+	}
+}
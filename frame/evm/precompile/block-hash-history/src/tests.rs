@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pallet_evm::{Context, Precompile};
+use pallet_evm_test_vector_support::MockHandle;
+
+use super::*;
+use crate::mock::*;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into()
+}
+
+fn block_hash(number: U256) -> (H256, MockHandle) {
+	let context = Context {
+		address: H160::from_low_u64_be(2062),
+		caller: H160::repeat_byte(0xaa),
+		apparent_value: U256::zero(),
+	};
+	let input = encode_with_selector(
+		u32::from_be_bytes(keccak256!("blockHash(uint256)")[..4].try_into().unwrap()),
+		number,
+	);
+	let mut handle = MockHandle::new(input, None, context);
+	handle.is_static = true;
+
+	let output = BlockHashHistory::execute(&mut handle)
+		.expect("call succeeds")
+		.output;
+	(decode_arguments(&output).unwrap(), handle)
+}
+
+#[test]
+fn returns_stored_hashes() {
+	new_test_ext().execute_with(|| {
+		let (hash, handle) = block_hash(U256::from(7));
+		assert_eq!(hash, H256::from_low_u64_be(7));
+		assert!(handle.gas_used > 0);
+		assert_eq!(handle.proof_size_used, ENTRY_PROOF_SIZE);
+	});
+}
+
+#[test]
+fn returns_zero_outside_history() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(block_hash(U256::from(11)).0, H256::zero());
+		assert_eq!(block_hash(U256::from(u64::MAX)).0, H256::zero());
+		assert_eq!(block_hash(U256::MAX).0, H256::zero());
+	});
+}
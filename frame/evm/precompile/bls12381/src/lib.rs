@@ -0,0 +1,551 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! BLS12-381 precompiles, as specified by [EIP-2537](https://eips.ethereum.org/EIPS/eip-2537).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+// Arkworks
+use ark_bls12_381::{
+	g1, g2, Bls12_381, Fq, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective,
+};
+use ark_ec::{
+	hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurve},
+	pairing::Pairing,
+	AffineRepr, CurveGroup, VariableBaseMSM,
+};
+use ark_ff::{BigInteger384, PrimeField, Zero};
+
+// Frontier
+use fp_evm::{
+	ExitError, ExitSucceed, Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput,
+	PrecompileResult,
+};
+
+/// Gas discount table for BLS12-381 G1 multi exponentiation operations.
+const BLS12381_G1_MULTIEXP_DISCOUNT_TABLE: [u16; 128] = [
+	1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682, 677, 673, 669, 665,
+	661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632, 630, 627, 625, 623, 621, 619, 617, 615,
+	613, 611, 609, 608, 606, 604, 603, 601, 599, 598, 596, 595, 593, 592, 591, 589, 588, 586, 585,
+	584, 582, 581, 580, 579, 577, 576, 575, 574, 573, 572, 570, 569, 568, 567, 566, 565, 564, 563,
+	562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 551, 550, 549, 548, 547, 547, 546, 545,
+	544, 543, 542, 541, 540, 540, 539, 538, 537, 536, 536, 535, 534, 533, 532, 532, 531, 530, 529,
+	528, 528, 527, 526, 525, 525, 524, 523, 522, 522, 521, 520, 520, 519,
+];
+
+/// Gas discount table for BLS12-381 G2 multi exponentiation operations.
+const BLS12381_G2_MULTIEXP_DISCOUNT_TABLE: [u16; 128] = [
+	1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732, 724, 717, 711, 704,
+	699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655, 652, 649, 646, 643, 640, 637, 634, 632,
+	629, 627, 624, 622, 620, 618, 615, 613, 611, 609, 607, 606, 604, 602, 600, 598, 597, 595, 593,
+	592, 590, 589, 587, 586, 584, 583, 582, 580, 579, 578, 576, 575, 574, 573, 571, 570, 569, 568,
+	567, 566, 565, 563, 562, 561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 552, 551, 550, 549,
+	548, 547, 546, 545, 545, 544, 543, 542, 541, 541, 540, 539, 538, 537, 537, 536, 535, 535, 534,
+	533, 532, 532, 531, 530, 530, 529, 528, 528, 527, 526, 526, 525, 524, 524,
+];
+
+/// Encode Fq as `64` bytes by performing Big-Endian encoding of the corresponding (unsigned) integer (top 16 bytes are always zeroes).
+fn encode_fq(field: Fq) -> [u8; 64] {
+	let mut result = [0u8; 64];
+	let rep = field.into_bigint().0;
+
+	result[16..24].copy_from_slice(&rep[5].to_be_bytes());
+	result[24..32].copy_from_slice(&rep[4].to_be_bytes());
+	result[32..40].copy_from_slice(&rep[3].to_be_bytes());
+	result[40..48].copy_from_slice(&rep[2].to_be_bytes());
+	result[48..56].copy_from_slice(&rep[1].to_be_bytes());
+	result[56..64].copy_from_slice(&rep[0].to_be_bytes());
+
+	result
+}
+
+/// Encode point G1 as byte concatenation of encodings of the `x` and `y` affine coordinates.
+fn encode_g1(g1: G1Affine) -> [u8; 128] {
+	let mut result = [0u8; 128];
+	if !g1.is_zero() {
+		result[0..64].copy_from_slice(&encode_fq(g1.x));
+		result[64..128].copy_from_slice(&encode_fq(g1.y));
+	}
+	result
+}
+
+/// Encode point G2 as byte concatenation of encodings of the `x` and `y` affine coordinates.
+fn encode_g2(g2: G2Affine) -> [u8; 256] {
+	let mut result = [0u8; 256];
+	if !g2.is_zero() {
+		result[0..64].copy_from_slice(&encode_fq(g2.x.c0));
+		result[64..128].copy_from_slice(&encode_fq(g2.x.c1));
+		result[128..192].copy_from_slice(&encode_fq(g2.y.c0));
+		result[192..256].copy_from_slice(&encode_fq(g2.y.c1));
+	}
+	result
+}
+
+/// Copy bytes from source.offset to target.
+fn read_input(source: &[u8], target: &mut [u8], offset: usize) {
+	let len = target.len();
+	target[..len].copy_from_slice(&source[offset..][..len]);
+}
+
+/// Decode Fr expects 32 byte input, returns fr in scalar field.
+fn decode_fr(input: &[u8], offset: usize) -> Fr {
+	let mut bytes = [0u8; 32];
+	read_input(input, &mut bytes, offset);
+	Fr::from_be_bytes_mod_order(&bytes)
+}
+
+/// Decode Fq expects 64 byte input with zero top 16 bytes,
+/// returns Fq in base field.
+///
+/// Errors use the messages of the EIP-2537 test vectors.
+fn decode_fq(bytes: [u8; 64]) -> Result<Fq, PrecompileFailure> {
+	// check top bytes
+	if bytes.iter().take(16).any(|b| b.ne(&0u8)) {
+		return Err(PrecompileFailure::Error {
+			exit_status: ExitError::Other("invalid field element top bytes".into()),
+		});
+	}
+
+	let mut tmp = BigInteger384::new([0, 0, 0, 0, 0, 0]);
+	// Note: The following unwraps are if the compiler cannot convert
+	// the byte slice into [u8;8], we know this is infallible since we
+	// are providing the indices at compile time and bytes has a fixed size
+	tmp.0[5] = u64::from_be_bytes(<[u8; 8]>::try_from(&bytes[16..24]).unwrap());
+	tmp.0[4] = u64::from_be_bytes(<[u8; 8]>::try_from(&bytes[24..32]).unwrap());
+	tmp.0[3] = u64::from_be_bytes(<[u8; 8]>::try_from(&bytes[32..40]).unwrap());
+	tmp.0[2] = u64::from_be_bytes(<[u8; 8]>::try_from(&bytes[40..48]).unwrap());
+	tmp.0[1] = u64::from_be_bytes(<[u8; 8]>::try_from(&bytes[48..56]).unwrap());
+	tmp.0[0] = u64::from_be_bytes(<[u8; 8]>::try_from(&bytes[56..64]).unwrap());
+
+	Fq::from_bigint(tmp).ok_or_else(|| PrecompileFailure::Error {
+		exit_status: ExitError::Other("invalid fp.Element encoding".into()),
+	})
+}
+
+/// Decode Fq given encoded 64 bytes at offset.
+fn decode_fq_at(input: &[u8], offset: usize) -> Result<Fq, PrecompileFailure> {
+	let mut buf = [0u8; 64];
+	read_input(input, &mut buf, offset);
+	decode_fq(buf)
+}
+
+/// Decode G1 given encoded (x, y) coordinates in 128 bytes returns a valid G1 Point.
+fn decode_g1(input: &[u8], offset: usize) -> Result<G1Projective, PrecompileFailure> {
+	// Decode x
+	let px = decode_fq_at(input, offset)?;
+	// Decode y
+	let py = decode_fq_at(input, offset + 64)?;
+
+	// Check if given input points to infinity
+	if px.is_zero() && py.is_zero() {
+		Ok(G1Projective::zero())
+	} else {
+		let g1 = G1Affine::new_unchecked(px, py);
+		if !g1.is_on_curve() {
+			Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("invalid point: not on curve".into()),
+			})
+		} else {
+			Ok(g1.into())
+		}
+	}
+}
+
+/// Decode G2 given encoded (x, y) coordinates in 256 bytes returns a valid G2 Point.
+fn decode_g2(input: &[u8], offset: usize) -> Result<G2Projective, PrecompileFailure> {
+	let px0 = decode_fq_at(input, offset)?;
+	let px1 = decode_fq_at(input, offset + 64)?;
+	let py0 = decode_fq_at(input, offset + 128)?;
+	let py1 = decode_fq_at(input, offset + 192)?;
+
+	let px = Fq2::new(px0, px1);
+	let py = Fq2::new(py0, py1);
+
+	if px.is_zero() && py.is_zero() {
+		Ok(G2Projective::zero())
+	} else {
+		let g2 = G2Affine::new_unchecked(px, py);
+		if !g2.is_on_curve() {
+			Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("invalid point: not on curve".into()),
+			})
+		} else {
+			Ok(g2.into())
+		}
+	}
+}
+
+/// Decode a G1 point which must also be in the correct subgroup.
+fn decode_g1_in_subgroup(input: &[u8], offset: usize) -> Result<G1Affine, PrecompileFailure> {
+	let g1 = decode_g1(input, offset)?.into_affine();
+	if !g1.is_in_correct_subgroup_assuming_on_curve() {
+		return Err(PrecompileFailure::Error {
+			exit_status: ExitError::Other("g1 point is not on correct subgroup".into()),
+		});
+	}
+	Ok(g1)
+}
+
+/// Decode a G2 point which must also be in the correct subgroup.
+fn decode_g2_in_subgroup(input: &[u8], offset: usize) -> Result<G2Affine, PrecompileFailure> {
+	let g2 = decode_g2(input, offset)?.into_affine();
+	if !g2.is_in_correct_subgroup_assuming_on_curve() {
+		return Err(PrecompileFailure::Error {
+			exit_status: ExitError::Other("g2 point is not on correct subgroup".into()),
+		});
+	}
+	Ok(g2)
+}
+
+/// Returns the gas of a multi exponentiation of `k` pairs.
+fn multiexp_gas_cost(k: usize, mul_gas: u64, discount_table: &[u16; 128]) -> u64 {
+	if k == 0 {
+		return 0;
+	}
+	// Lookup discount value for the pair length
+	let discount = discount_table[k.min(discount_table.len()) - 1];
+	// Calculate gas and return the result
+	k as u64 * mul_gas * discount as u64 / 1_000
+}
+
+/// Bls12381G1Add implements EIP-2537 G1Add precompile.
+pub struct Bls12381G1Add;
+
+impl Bls12381G1Add {
+	/// https://eips.ethereum.org/EIPS/eip-2537#g1-addition
+	const GAS_COST: u64 = 375;
+}
+
+impl Precompile for Bls12381G1Add {
+	/// Implements EIP-2537 G1Add precompile.
+	/// > G1 addition call expects `256` bytes as an input that is interpreted as byte concatenation of two G1 points (`128` bytes each).
+	/// > Output is an encoding of addition operation result - single G1 point (`128` bytes).
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		handle.record_cost(Bls12381G1Add::GAS_COST)?;
+
+		let input = handle.input();
+		if input.len() != 256 {
+			return Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("invalid input length".into()),
+			});
+		}
+
+		// Decode G1 point p_0
+		let p0 = decode_g1(input, 0)?;
+		// Decode G1 point p_1
+		let p1 = decode_g1(input, 128)?;
+		// Compute r = p_0 + p_1
+		let r = p0 + p1;
+		// Encode the G1 point into 128 bytes output
+		let output = encode_g1(r.into_affine());
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: output.to_vec(),
+		})
+	}
+}
+
+/// Bls12381G1MultiExp implements EIP-2537 G1MSM precompile.
+pub struct Bls12381G1MultiExp;
+
+impl Bls12381G1MultiExp {
+	/// https://eips.ethereum.org/EIPS/eip-2537#g1g2-msm
+	const MUL_GAS_COST: u64 = 12_000;
+}
+
+impl Precompile for Bls12381G1MultiExp {
+	/// Implements EIP-2537 G1MSM precompile.
+	/// > G1 MSM call expects `160*k` bytes as an input that is interpreted as byte concatenation of `k` slices each of them being a byte concatenation of encoding of G1 point (`128` bytes) and encoding of a scalar value (`32` bytes).
+	/// > Output is an encoding of MSM operation result - single G1 point (`128` bytes).
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		let k = handle.input().len() / 160;
+		let gas_cost = multiexp_gas_cost(
+			k,
+			Bls12381G1MultiExp::MUL_GAS_COST,
+			&BLS12381_G1_MULTIEXP_DISCOUNT_TABLE,
+		);
+		handle.record_cost(gas_cost)?;
+
+		if handle.input().is_empty() || handle.input().len() % 160 != 0 {
+			return Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("invalid input length".into()),
+			});
+		}
+
+		let input = handle.input();
+
+		let mut points = Vec::new();
+		let mut scalars = Vec::new();
+		// Decode point scalar pairs
+		for idx in 0..k {
+			let offset = idx * 160;
+			// Decode G1 point, which must be in the subgroup
+			let p = decode_g1_in_subgroup(input, offset)?;
+			// Decode scalar value
+			let scalar = decode_fr(input, offset + 128);
+			points.push(p);
+			scalars.push(scalar);
+		}
+
+		// Compute r = e_0 * p_0 + e_1 * p_1 + ... + e_(k-1) * p_(k-1)
+		let r = G1Projective::msm(&points, &scalars).map_err(|_| PrecompileFailure::Error {
+			exit_status: ExitError::Other("MSM failed".into()),
+		})?;
+
+		// Encode the G1 point into 128 bytes output
+		let output = encode_g1(r.into_affine());
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: output.to_vec(),
+		})
+	}
+}
+
+/// Bls12381G2Add implements EIP-2537 G2Add precompile.
+pub struct Bls12381G2Add;
+
+impl Bls12381G2Add {
+	/// https://eips.ethereum.org/EIPS/eip-2537#g2-addition
+	const GAS_COST: u64 = 600;
+}
+
+impl Precompile for Bls12381G2Add {
+	/// Implements EIP-2537 G2Add precompile.
+	/// > G2 addition call expects `512` bytes as an input that is interpreted as byte concatenation of two G2 points (`256` bytes each).
+	/// > Output is an encoding of addition operation result - single G2 point (`256` bytes).
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		handle.record_cost(Bls12381G2Add::GAS_COST)?;
+
+		let input = handle.input();
+		if input.len() != 512 {
+			return Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("invalid input length".into()),
+			});
+		}
+
+		// Decode G2 point p_0
+		let p0 = decode_g2(input, 0)?;
+		// Decode G2 point p_1
+		let p1 = decode_g2(input, 256)?;
+		// Compute r = p_0 + p_1
+		let r = p0 + p1;
+		// Encode the G2 point into 256 bytes output
+		let output = encode_g2(r.into_affine());
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: output.to_vec(),
+		})
+	}
+}
+
+/// Bls12381G2MultiExp implements EIP-2537 G2MSM precompile.
+pub struct Bls12381G2MultiExp;
+
+impl Bls12381G2MultiExp {
+	/// https://eips.ethereum.org/EIPS/eip-2537#g1g2-msm
+	const MUL_GAS_COST: u64 = 22_500;
+}
+
+impl Precompile for Bls12381G2MultiExp {
+	/// Implements EIP-2537 G2MSM precompile.
+	/// > G2 MSM call expects `288*k` bytes as an input that is interpreted as byte concatenation of `k` slices each of them being a byte concatenation of encoding of G2 point (`256` bytes) and encoding of a scalar value (`32` bytes).
+	/// > Output is an encoding of MSM operation result - single G2 point (`256` bytes).
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		let k = handle.input().len() / 288;
+		let gas_cost = multiexp_gas_cost(
+			k,
+			Bls12381G2MultiExp::MUL_GAS_COST,
+			&BLS12381_G2_MULTIEXP_DISCOUNT_TABLE,
+		);
+		handle.record_cost(gas_cost)?;
+
+		if handle.input().is_empty() || handle.input().len() % 288 != 0 {
+			return Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("invalid input length".into()),
+			});
+		}
+
+		let input = handle.input();
+
+		let mut points = Vec::new();
+		let mut scalars = Vec::new();
+		// Decode point scalar pairs
+		for idx in 0..k {
+			let offset = idx * 288;
+			// Decode G2 point, which must be in the subgroup
+			let p = decode_g2_in_subgroup(input, offset)?;
+			// Decode scalar value
+			let scalar = decode_fr(input, offset + 256);
+			points.push(p);
+			scalars.push(scalar);
+		}
+
+		// Compute r = e_0 * p_0 + e_1 * p_1 + ... + e_(k-1) * p_(k-1)
+		let r = G2Projective::msm(&points, &scalars).map_err(|_| PrecompileFailure::Error {
+			exit_status: ExitError::Other("MSM failed".into()),
+		})?;
+
+		// Encode the G2 point to 256 bytes output
+		let output = encode_g2(r.into_affine());
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: output.to_vec(),
+		})
+	}
+}
+
+/// Bls12381Pairing implements EIP-2537 Pairing precompile.
+pub struct Bls12381Pairing;
+
+impl Bls12381Pairing {
+	/// https://eips.ethereum.org/EIPS/eip-2537#pairing-check
+	const BASE_GAS: u64 = 37_700;
+	const PER_PAIR_GAS: u64 = 32_600;
+}
+
+impl Precompile for Bls12381Pairing {
+	/// Implements EIP-2537 Pairing precompile logic.
+	/// > Pairing call expects `384*k` bytes as an inputs that is interpreted as byte concatenation of `k` slices. Each slice has the following structure:
+	/// > - `128` bytes of G1 point encoding
+	/// > - `256` bytes of G2 point encoding
+	/// > Output is a `32` bytes where last single byte is `0x01` if pairing result is equal to multiplicative identity in a pairing target field and `0x00` otherwise
+	/// > (which is equivalent of Big Endian encoding of Solidity values `uint256(1)` and `uin256(0)` respectively).
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		if handle.input().is_empty() || handle.input().len() % 384 != 0 {
+			return Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("invalid input length".into()),
+			});
+		}
+
+		let k = handle.input().len() / 384;
+		let gas_cost: u64 = Bls12381Pairing::BASE_GAS + (k as u64 * Bls12381Pairing::PER_PAIR_GAS);
+
+		handle.record_cost(gas_cost)?;
+
+		let input = handle.input();
+
+		let mut a = Vec::new();
+		let mut b = Vec::new();
+		// Decode G1 G2 pairs
+		for idx in 0..k {
+			let offset = idx * 384;
+			// Decode G1 point
+			let g1 = decode_g1_in_subgroup(input, offset)?;
+			// Decode G2 point
+			let g2 = decode_g2_in_subgroup(input, offset + 128)?;
+
+			a.push(g1);
+			b.push(g2);
+		}
+
+		let mut output = [0u8; 32];
+		// Compute pairing and set the output
+		if Bls12_381::multi_pairing(a, b).is_zero() {
+			output[31] = 1;
+		}
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: output.to_vec(),
+		})
+	}
+}
+
+/// Bls12381MapG1 implements EIP-2537 MapFpToG1 precompile.
+pub struct Bls12381MapG1;
+
+impl Bls12381MapG1 {
+	/// https://eips.ethereum.org/EIPS/eip-2537#mapping-fp-element-to-g1-point
+	const GAS_COST: u64 = 5_500;
+}
+
+impl Precompile for Bls12381MapG1 {
+	/// Implements EIP-2537 MapFpToG1 precompile logic.
+	/// > Field-to-curve call expects `64` bytes as an input that is interpreted as an element of the base field.
+	/// > Output of this call is `128` bytes and is an encoded G1 point, obtained with the simplified SWU map and cofactor clearing.
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		handle.record_cost(Bls12381MapG1::GAS_COST)?;
+
+		let input = handle.input();
+		if input.len() != 64 {
+			return Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("invalid input length".into()),
+			});
+		}
+
+		let fq = decode_fq_at(input, 0)?;
+		let point = WBMap::<g1::Config>::new()
+			.and_then(|map| map.map_to_curve(fq))
+			.map_err(|_| PrecompileFailure::Error {
+				exit_status: ExitError::Other("map to curve failed".into()),
+			})?;
+		let output = encode_g1(point.clear_cofactor());
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: output.to_vec(),
+		})
+	}
+}
+
+/// Bls12381MapG2 implements EIP-2537 MapFp2ToG2 precompile.
+pub struct Bls12381MapG2;
+
+impl Bls12381MapG2 {
+	/// https://eips.ethereum.org/EIPS/eip-2537#mapping-fp2-element-to-g2-point
+	const GAS_COST: u64 = 23_800;
+}
+
+impl Precompile for Bls12381MapG2 {
+	/// Implements EIP-2537 MapFp2ToG2 precompile logic.
+	/// > Field-to-curve call expects `128` bytes as an input that is interpreted as an element of the quadratic extension field.
+	/// > Output of this call is `256` bytes and is an encoded G2 point, obtained with the simplified SWU map and cofactor clearing.
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		handle.record_cost(Bls12381MapG2::GAS_COST)?;
+
+		let input = handle.input();
+		if input.len() != 128 {
+			return Err(PrecompileFailure::Error {
+				exit_status: ExitError::Other("invalid input length".into()),
+			});
+		}
+
+		let c0 = decode_fq_at(input, 0)?;
+		let c1 = decode_fq_at(input, 64)?;
+		let point = WBMap::<g2::Config>::new()
+			.and_then(|map| map.map_to_curve(Fq2::new(c0, c1)))
+			.map_err(|_| PrecompileFailure::Error {
+				exit_status: ExitError::Other("map to curve failed".into()),
+			})?;
+		let output = encode_g2(point.clear_cofactor());
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: output.to_vec(),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests;
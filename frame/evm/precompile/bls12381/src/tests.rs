@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use fp_evm::Context;
+use pallet_evm_test_vector_support::{
+	test_precompile_failure_test_vectors, test_precompile_test_vectors_ignoring_gas, MockHandle,
+};
+
+// `testdata/eip-2537` holds the vectors of the `assets/eip-2537` directory of the EIPs
+// repository, unmodified. They predate the final gas schedule, so their gas isn't compared, and
+// their `mul` vectors of the since removed G1MUL and G2MUL precompiles are single pair MSMs.
+
+#[test]
+fn process_consensus_tests() -> Result<(), String> {
+	test_precompile_test_vectors_ignoring_gas::<Bls12381G1Add>(
+		"../testdata/eip-2537/add_G1_bls.json",
+	)?;
+	test_precompile_test_vectors_ignoring_gas::<Bls12381G1MultiExp>(
+		"../testdata/eip-2537/mul_G1_bls.json",
+	)?;
+	test_precompile_test_vectors_ignoring_gas::<Bls12381G1MultiExp>(
+		"../testdata/eip-2537/multiexp_G1_bls.json",
+	)?;
+	test_precompile_test_vectors_ignoring_gas::<Bls12381G2Add>(
+		"../testdata/eip-2537/add_G2_bls.json",
+	)?;
+	test_precompile_test_vectors_ignoring_gas::<Bls12381G2MultiExp>(
+		"../testdata/eip-2537/mul_G2_bls.json",
+	)?;
+	test_precompile_test_vectors_ignoring_gas::<Bls12381G2MultiExp>(
+		"../testdata/eip-2537/multiexp_G2_bls.json",
+	)?;
+	test_precompile_test_vectors_ignoring_gas::<Bls12381Pairing>(
+		"../testdata/eip-2537/pairing_check_bls.json",
+	)?;
+	test_precompile_test_vectors_ignoring_gas::<Bls12381MapG1>(
+		"../testdata/eip-2537/map_fp_to_G1_bls.json",
+	)?;
+	test_precompile_test_vectors_ignoring_gas::<Bls12381MapG2>(
+		"../testdata/eip-2537/map_fp2_to_G2_bls.json",
+	)?;
+	Ok(())
+}
+
+#[test]
+fn process_consensus_failure_tests() -> Result<(), String> {
+	test_precompile_failure_test_vectors::<Bls12381G1Add>(
+		"../testdata/eip-2537/fail-add_G1_bls.json",
+	)?;
+	test_precompile_failure_test_vectors::<Bls12381G1MultiExp>(
+		"../testdata/eip-2537/fail-mul_G1_bls.json",
+	)?;
+	test_precompile_failure_test_vectors::<Bls12381G1MultiExp>(
+		"../testdata/eip-2537/fail-multiexp_G1_bls.json",
+	)?;
+	test_precompile_failure_test_vectors::<Bls12381G2Add>(
+		"../testdata/eip-2537/fail-add_G2_bls.json",
+	)?;
+	test_precompile_failure_test_vectors::<Bls12381G2MultiExp>(
+		"../testdata/eip-2537/fail-mul_G2_bls.json",
+	)?;
+	test_precompile_failure_test_vectors::<Bls12381G2MultiExp>(
+		"../testdata/eip-2537/fail-multiexp_G2_bls.json",
+	)?;
+	test_precompile_failure_test_vectors::<Bls12381Pairing>(
+		"../testdata/eip-2537/fail-pairing_check_bls.json",
+	)?;
+	test_precompile_failure_test_vectors::<Bls12381MapG1>(
+		"../testdata/eip-2537/fail-map_fp_to_G1_bls.json",
+	)?;
+	test_precompile_failure_test_vectors::<Bls12381MapG2>(
+		"../testdata/eip-2537/fail-map_fp2_to_G2_bls.json",
+	)?;
+	Ok(())
+}
+
+fn execute<P: Precompile>(input: Vec<u8>) -> (Vec<u8>, u64) {
+	let context = Context {
+		address: Default::default(),
+		caller: Default::default(),
+		apparent_value: From::from(0),
+	};
+	let mut handle = MockHandle::new(input, Some(10_000_000), context);
+	let output = P::execute(&mut handle).expect("valid input").output;
+	(output, handle.gas_used)
+}
+
+#[test]
+fn map_fp_to_g1_returns_subgroup_points() {
+	let mut outputs = Vec::new();
+	for value in [0u64, 1, 2, u64::MAX] {
+		let (output, gas) = execute::<Bls12381MapG1>(encode_fq(Fq::from(value)).to_vec());
+		assert_eq!(gas, 5_500);
+
+		let point = decode_g1(&output, 0).expect("on curve").into_affine();
+		assert!(!point.is_zero());
+		assert!(point.is_in_correct_subgroup_assuming_on_curve());
+		outputs.push(output);
+	}
+
+	// Distinct field elements map to distinct points.
+	outputs.sort();
+	outputs.dedup();
+	assert_eq!(outputs.len(), 4);
+}
+
+#[test]
+fn map_fp2_to_g2_returns_subgroup_points() {
+	let mut outputs = Vec::new();
+	for (c0, c1) in [(0u64, 0u64), (1, 0), (0, 1), (u64::MAX, 7)] {
+		let mut input = encode_fq(Fq::from(c0)).to_vec();
+		input.extend_from_slice(&encode_fq(Fq::from(c1)));
+		let (output, gas) = execute::<Bls12381MapG2>(input);
+		assert_eq!(gas, 23_800);
+
+		let point = decode_g2(&output, 0).expect("on curve").into_affine();
+		assert!(!point.is_zero());
+		assert!(point.is_in_correct_subgroup_assuming_on_curve());
+		outputs.push(output);
+	}
+
+	outputs.sort();
+	outputs.dedup();
+	assert_eq!(outputs.len(), 4);
+}
+
+#[test]
+fn msm_gas_is_discounted() {
+	let table = &BLS12381_G1_MULTIEXP_DISCOUNT_TABLE;
+	assert_eq!(multiexp_gas_cost(0, 12_000, table), 0);
+	assert_eq!(multiexp_gas_cost(1, 12_000, table), 12_000);
+	assert_eq!(
+		multiexp_gas_cost(2, 12_000, table),
+		2 * 12_000 * 949 / 1_000
+	);
+	// The discount is capped past the end of the table.
+	assert_eq!(
+		multiexp_gas_cost(200, 12_000, table),
+		200 * 12_000 * 519 / 1_000
+	);
+}
+
+#[test]
+fn gas_follows_final_schedule() {
+	// Points at infinity are valid inputs of every operation.
+	assert_eq!(execute::<Bls12381G1Add>(vec![0; 256]).1, 375);
+	assert_eq!(execute::<Bls12381G2Add>(vec![0; 512]).1, 600);
+	assert_eq!(execute::<Bls12381G1MultiExp>(vec![0; 160]).1, 12_000);
+	assert_eq!(execute::<Bls12381G2MultiExp>(vec![0; 288]).1, 22_500);
+	assert_eq!(
+		execute::<Bls12381Pairing>(vec![0; 2 * 384]).1,
+		37_700 + 2 * 32_600
+	);
+}
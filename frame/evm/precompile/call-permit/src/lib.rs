@@ -0,0 +1,244 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Precompile dispatching calls on behalf of the signer of an EIP-712 permit, so that a third
+//! party can pay for the transaction.
+//!
+//! The permit is the EIP-712 typed structure
+//! `CallPermit(address from,address to,uint256 value,bytes data,uint64 gaslimit,uint256 nonce,uint256 deadline)`
+//! in the domain `Call Permit Precompile`, version `1`, of the current chain and the address of
+//! the precompile. As calls are made with `from` as `msg.sender` and `value` source, the
+//! precompile must not accept delegate calls.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use frame_support::{
+	storage::types::{StorageMap, ValueQuery},
+	traits::{Get, Time},
+	Blake2_128Concat,
+};
+use sp_runtime::traits::UniqueSaturatedInto;
+
+// Frontier
+use fp_evm::{
+	Context, ExitReason, LinearCostPrecompile, PrecompileFailure, Transfer, WRITE_PROOF_SIZE,
+};
+use pallet_evm::GasWeightMapping;
+use pallet_evm_precompile_simple::ECRecover;
+use precompile_utils::prelude::*;
+
+/// `keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")`
+pub const PERMIT_DOMAIN: [u8; 32] = keccak256!(
+	"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)"
+);
+
+/// `keccak256("CallPermit(address from,address to,uint256 value,bytes data,uint64 gaslimit,uint256 nonce,uint256 deadline)")`
+pub const PERMIT_TYPEHASH: [u8; 32] = keccak256!(
+	"CallPermit(address from,address to,uint256 value,bytes data,uint64 gaslimit,uint256 nonce,uint256 deadline)"
+);
+
+/// Gas cost of the signature recovery, the one of the `ecrecover` precompile.
+const RECOVERY_COST: u64 = <ECRecover as LinearCostPrecompile>::BASE;
+
+/// `Nonces` read, 16 (hash) + 20 (key) + 32 (value).
+pub const NONCE_PROOF_SIZE: u64 = 68;
+
+/// Next permit nonce of every signer.
+#[frame_support::storage_alias]
+pub type Nonces = StorageMap<CallPermit, Blake2_128Concat, H160, U256, ValueQuery>;
+
+/// Call permit precompile.
+pub struct CallPermitPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile]
+impl<Runtime> CallPermitPrecompile<Runtime>
+where
+	Runtime: pallet_evm::Config,
+{
+	#[precompile::public(
+		"dispatch(address,address,uint256,bytes,uint64,uint256,uint8,bytes32,bytes32)"
+	)]
+	#[allow(clippy::too_many_arguments)]
+	fn dispatch(
+		handle: &mut impl PrecompileHandle,
+		from: H160,
+		to: H160,
+		value: U256,
+		data: Bytes,
+		gas_limit: u64,
+		deadline: U256,
+		v: u8,
+		r: H256,
+		s: H256,
+	) -> EvmResult<Bytes> {
+		// Hashing the permit, recovering its signer and updating its nonce.
+		handle.record_cost(
+			keccak_cost(data.as_bytes().len())
+				.saturating_add(RECOVERY_COST)
+				.saturating_add(db_access_gas::<Runtime>(1, 1)),
+		)?;
+		handle.record_external_cost(None, Some(NONCE_PROOF_SIZE + WRITE_PROOF_SIZE))?;
+
+		let now: u128 = Runtime::Timestamp::now().unique_saturated_into();
+		if deadline < U256::from(now / 1000) {
+			return Err(revert("Permit expired"));
+		}
+
+		let nonce = Nonces::get(from);
+		let digest = Self::permit_digest(
+			handle.context().address,
+			from,
+			to,
+			value,
+			data.as_bytes(),
+			gas_limit,
+			nonce,
+			deadline,
+		);
+		if recover_signer(digest, v, r, s) != Some(from) {
+			return Err(revert("Invalid permit"));
+		}
+		if gas_limit > forwardable_gas(handle.remaining_gas(), value, Runtime::config()) {
+			return Err(revert("Gaslimit is too low to dispatch provided call"));
+		}
+		Nonces::insert(from, nonce.saturating_add(U256::one()));
+
+		let transfer = (!value.is_zero()).then_some(Transfer {
+			source: from,
+			target: to,
+			value,
+		});
+		let context = Context {
+			address: to,
+			caller: from,
+			apparent_value: value,
+		};
+		let (reason, output) =
+			handle.call(to, transfer, data.into(), Some(gas_limit), false, &context);
+
+		match reason {
+			ExitReason::Succeed(_) => Ok(output.into()),
+			ExitReason::Revert(exit_status) => Err(PrecompileFailure::Revert {
+				exit_status,
+				output,
+			}),
+			ExitReason::Error(exit_status) => Err(PrecompileFailure::Error { exit_status }),
+			ExitReason::Fatal(exit_status) => Err(PrecompileFailure::Fatal { exit_status }),
+		}
+	}
+
+	#[precompile::public("nonces(address)")]
+	#[precompile::view]
+	fn nonces(handle: &mut impl PrecompileHandle, owner: H160) -> EvmResult<U256> {
+		handle.record_cost(db_access_gas::<Runtime>(1, 0))?;
+		handle.record_external_cost(None, Some(NONCE_PROOF_SIZE))?;
+
+		Ok(Nonces::get(owner))
+	}
+
+	#[precompile::public("DOMAIN_SEPARATOR()")]
+	#[precompile::view]
+	fn domain_separator(handle: &mut impl PrecompileHandle) -> EvmResult<H256> {
+		Ok(Self::compute_domain_separator(handle.context().address).into())
+	}
+}
+
+impl<Runtime> CallPermitPrecompile<Runtime>
+where
+	Runtime: pallet_evm::Config,
+{
+	/// EIP-712 domain separator of the precompile at `address`.
+	pub fn compute_domain_separator(address: H160) -> [u8; 32] {
+		let name: H256 = keccak256!("Call Permit Precompile").into();
+		let version: H256 = keccak256!("1").into();
+		let chain_id = U256::from(Runtime::ChainId::get());
+
+		sp_io::hashing::keccak_256(&encode_arguments((
+			H256::from(PERMIT_DOMAIN),
+			name,
+			version,
+			chain_id,
+			address,
+		)))
+	}
+
+	/// EIP-712 digest to sign for the permit, `address` being the one of the precompile.
+	#[allow(clippy::too_many_arguments)]
+	pub fn permit_digest(
+		address: H160,
+		from: H160,
+		to: H160,
+		value: U256,
+		data: &[u8],
+		gas_limit: u64,
+		nonce: U256,
+		deadline: U256,
+	) -> [u8; 32] {
+		let permit = sp_io::hashing::keccak_256(&encode_arguments((
+			H256::from(PERMIT_TYPEHASH),
+			from,
+			to,
+			value,
+			H256::from(sp_io::hashing::keccak_256(data)),
+			gas_limit,
+			nonce,
+			deadline,
+		)));
+
+		let mut message = Vec::with_capacity(66);
+		message.extend_from_slice(b"\x19\x01");
+		message.extend_from_slice(&Self::compute_domain_separator(address));
+		message.extend_from_slice(&permit);
+		sp_io::hashing::keccak_256(&message)
+	}
+}
+
+/// Signer of `digest`, recovered like the `ecrecover` precompile does.
+fn recover_signer(digest: [u8; 32], v: u8, r: H256, s: H256) -> Option<H160> {
+	let mut input = [0u8; 128];
+	input[..32].copy_from_slice(&digest);
+	input[63] = v;
+	input[64..96].copy_from_slice(r.as_bytes());
+	input[96..].copy_from_slice(s.as_bytes());
+
+	match <ECRecover as LinearCostPrecompile>::execute(&input, RECOVERY_COST) {
+		Ok((_, output)) if output.len() == 32 => Some(H160::from_slice(&output[12..])),
+		_ => None,
+	}
+}
+
+/// Gas cost of the `KECCAK256` opcode hashing `len` bytes.
+fn keccak_cost(len: usize) -> u64 {
+	let words = (len as u64).saturating_add(31) / 32;
+	30u64.saturating_add(words.saturating_mul(6))
+}
+
+fn db_access_gas<Runtime: pallet_evm::Config>(reads: u64, writes: u64) -> u64 {
+	let weight = <Runtime as frame_system::Config>::DbWeight::get().reads_writes(reads, writes);
+	Runtime::GasWeightMapping::weight_to_gas(weight)
+}
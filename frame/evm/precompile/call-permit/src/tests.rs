@@ -0,0 +1,230 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use fp_evm::{Precompile, WRITE_PROOF_SIZE};
+use pallet_evm_test_vector_support::MockHandle;
+
+use super::*;
+use crate::mock::*;
+
+const PRECOMPILE: H160 = H160::repeat_byte(0xca);
+const RELAYER: H160 = H160::repeat_byte(0xee);
+const TO: H160 = H160::repeat_byte(0xbb);
+const SECRET_KEY: [u8; 32] = [0x11; 32];
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into()
+}
+
+fn signer() -> H160 {
+	let secret_key = libsecp256k1::SecretKey::parse(&SECRET_KEY).unwrap();
+	let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key).serialize();
+	H160::from(H256::from(sp_io::hashing::keccak_256(&public_key[1..])))
+}
+
+fn sign(digest: [u8; 32]) -> (u8, H256, H256) {
+	let (signature, recovery_id) = libsecp256k1::sign(
+		&libsecp256k1::Message::parse(&digest),
+		&libsecp256k1::SecretKey::parse(&SECRET_KEY).unwrap(),
+	);
+	let signature = signature.serialize();
+	(
+		recovery_id.serialize() + 27,
+		H256::from_slice(&signature[..32]),
+		H256::from_slice(&signature[32..]),
+	)
+}
+
+fn selector(signature: [u8; 32]) -> u32 {
+	u32::from_be_bytes(signature[..4].try_into().unwrap())
+}
+
+/// Input of a `dispatch` call signed by [`signer`] for its current nonce.
+fn signed_dispatch(value: U256, data: &[u8], gas_limit: u64, deadline: U256) -> Vec<u8> {
+	let digest = CallPermit::permit_digest(
+		PRECOMPILE,
+		signer(),
+		TO,
+		value,
+		data,
+		gas_limit,
+		Nonces::get(signer()),
+		deadline,
+	);
+	let (v, r, s) = sign(digest);
+	encode_with_selector(
+		selector(keccak256!(
+			"dispatch(address,address,uint256,bytes,uint64,uint256,uint8,bytes32,bytes32)"
+		)),
+		(
+			signer(),
+			TO,
+			value,
+			Bytes::from(data),
+			gas_limit,
+			deadline,
+			v,
+			r,
+			s,
+		),
+	)
+}
+
+fn call(input: Vec<u8>) -> (PrecompileResult, MockHandle) {
+	let context = Context {
+		address: PRECOMPILE,
+		caller: RELAYER,
+		apparent_value: U256::zero(),
+	};
+	let mut handle = MockHandle::new(input, Some(1_000_000), context);
+	handle.enforce_gas_limit = true;
+	(CallPermit::execute(&mut handle), handle)
+}
+
+#[test]
+fn permit_is_dispatched_as_signer() {
+	new_test_ext().execute_with(|| {
+		let input = signed_dispatch(U256::from(5), b"payload", 100_000, U256::MAX);
+		let (result, handle) = call(input);
+
+		assert_eq!(
+			result.unwrap().output,
+			encode_arguments(Bytes::from("payload"))
+		);
+		assert_eq!(handle.subcalls.len(), 1);
+		let subcall = &handle.subcalls[0];
+		assert_eq!(subcall.address, TO);
+		assert_eq!(subcall.input, b"payload");
+		assert_eq!(subcall.gas_limit, Some(100_000));
+		assert_eq!(subcall.context.caller, signer());
+		assert_eq!(
+			subcall.transfer,
+			Some(Transfer {
+				source: signer(),
+				target: TO,
+				value: U256::from(5),
+			})
+		);
+		assert_eq!(Nonces::get(signer()), U256::one());
+		assert_eq!(handle.proof_size_used, NONCE_PROOF_SIZE + WRITE_PROOF_SIZE);
+	});
+}
+
+#[test]
+fn permit_cannot_be_replayed() {
+	new_test_ext().execute_with(|| {
+		let input = signed_dispatch(U256::zero(), b"", 100_000, U256::MAX);
+		assert!(call(input.clone()).0.is_ok());
+
+		let (result, handle) = call(input);
+		assert_eq!(result, Err(revert("Invalid permit")));
+		assert!(handle.subcalls.is_empty());
+		assert_eq!(Nonces::get(signer()), U256::one());
+	});
+}
+
+#[test]
+fn permit_of_another_signer_is_rejected() {
+	new_test_ext().execute_with(|| {
+		let mut input = signed_dispatch(U256::zero(), b"", 100_000, U256::MAX);
+		// Claim the permit is from someone else.
+		input[4 + 12..4 + 32].copy_from_slice(RELAYER.as_bytes());
+
+		assert_eq!(call(input).0, Err(revert("Invalid permit")));
+	});
+}
+
+#[test]
+fn expired_permit_is_rejected() {
+	new_test_ext().execute_with(|| {
+		pallet_timestamp::Pallet::<Test>::set_timestamp(20_000);
+
+		let input = signed_dispatch(U256::zero(), b"", 100_000, U256::from(19));
+		assert_eq!(call(input).0, Err(revert("Permit expired")));
+
+		let input = signed_dispatch(U256::zero(), b"", 100_000, U256::from(20));
+		assert!(call(input).0.is_ok());
+	});
+}
+
+#[test]
+fn gas_limit_above_remaining_gas_is_rejected() {
+	new_test_ext().execute_with(|| {
+		let input = signed_dispatch(U256::zero(), b"", 2_000_000, U256::MAX);
+		assert_eq!(
+			call(input).0,
+			Err(revert("Gaslimit is too low to dispatch provided call"))
+		);
+		assert_eq!(Nonces::get(signer()), U256::zero());
+	});
+}
+
+#[test]
+fn gas_limit_leaves_the_call_cost_and_a_64th() {
+	new_test_ext().execute_with(|| {
+		// 990_720 gas remain once the permit is checked: a cold call costs 2_600, and 1/64 of
+		// the rest is kept, leaving 972_681 gas to forward.
+		let input = signed_dispatch(U256::zero(), b"", 980_000, U256::MAX);
+		assert_eq!(
+			call(input).0,
+			Err(revert("Gaslimit is too low to dispatch provided call"))
+		);
+
+		let input = signed_dispatch(U256::zero(), b"", 970_000, U256::MAX);
+		assert!(call(input).0.is_ok());
+
+		// Transferring value to a possibly empty account costs 34_000 more, leaving 939_212.
+		let input = signed_dispatch(U256::from(5), b"", 950_000, U256::MAX);
+		assert_eq!(
+			call(input).0,
+			Err(revert("Gaslimit is too low to dispatch provided call"))
+		);
+
+		let input = signed_dispatch(U256::from(5), b"", 935_000, U256::MAX);
+		assert!(call(input).0.is_ok());
+	});
+}
+
+#[test]
+fn nonces_and_domain_separator() {
+	new_test_ext().execute_with(|| {
+		Nonces::insert(signer(), U256::from(3));
+
+		let input = encode_with_selector(selector(keccak256!("nonces(address)")), signer());
+		let (result, handle) = call(input);
+		assert_eq!(result.unwrap().output, encode_arguments(U256::from(3)));
+		assert_eq!(handle.proof_size_used, NONCE_PROOF_SIZE);
+
+		let input = selector(keccak256!("DOMAIN_SEPARATOR()"))
+			.to_be_bytes()
+			.to_vec();
+		let expected = sp_io::hashing::keccak_256(&encode_arguments((
+			H256::from(PERMIT_DOMAIN),
+			H256::from(keccak256!("Call Permit Precompile")),
+			H256::from(keccak256!("1")),
+			U256::from(42),
+			PRECOMPILE,
+		)));
+		assert_eq!(
+			call(input).0.unwrap().output,
+			encode_arguments(H256::from(expected))
+		);
+	});
+}
@@ -26,33 +26,63 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-use alloc::format;
+pub mod typed;
+
+use alloc::{format, vec, vec::Vec};
 use core::marker::PhantomData;
 use fp_evm::{
-	ExitError, ExitSucceed, Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput,
+	ExitError, ExitSucceed, Log, Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput,
 	PrecompileResult,
 };
 use frame_support::{
-	codec::{Decode, DecodeLimit as _},
-	dispatch::{DispatchClass, Dispatchable, GetDispatchInfo, Pays, PostDispatchInfo},
-	traits::{ConstU32, Get},
+	codec::{Compact, CompactLen, Decode, DecodeLimit as _},
+	dispatch::{
+		DispatchClass, DispatchInfo, Dispatchable, GetDispatchInfo, Pays, PostDispatchInfo,
+	},
+	storage::storage_prefix,
+	traits::{ConstBool, ConstU32, Get, PalletInfoAccess},
 };
+use frame_system::EventRecord;
 use pallet_evm::{AddressMapping, GasWeightMapping};
+use precompile_utils::{logs::emit_log, solidity::codec::encode_arguments};
+use sp_core::H160;
 
 // `DecodeLimit` specifies the max depth a call can use when decoding, as unbounded depth
 // can be used to overflow the stack.
 // Default value is 8, which is the same as in XCM call decoding.
-pub struct Dispatch<T, DispatchValidator = (), DecodeLimit = ConstU32<8>> {
-	_marker: PhantomData<(T, DispatchValidator, DecodeLimit)>,
+//
+// `EventsToLogs` selects the events deposited by the dispatched call that are emitted as logs,
+// none by default.
+//
+// `ReturnWeight` makes a successful call return the ABI-encoded actual weight
+// `(uint64 refTime, uint64 proofSize)` of the dispatched call. It is disabled by default, a
+// successful call then stops with an empty output.
+pub struct Dispatch<
+	T,
+	DispatchValidator = (),
+	DecodeLimit = ConstU32<8>,
+	EventsToLogs = (),
+	ReturnWeight = ConstBool<false>,
+> {
+	_marker: PhantomData<(
+		T,
+		DispatchValidator,
+		DecodeLimit,
+		EventsToLogs,
+		ReturnWeight,
+	)>,
 }
 
-impl<T, DispatchValidator, DecodeLimit> Precompile for Dispatch<T, DispatchValidator, DecodeLimit>
+impl<T, DispatchValidator, DecodeLimit, EventsToLogs, ReturnWeight> Precompile
+	for Dispatch<T, DispatchValidator, DecodeLimit, EventsToLogs, ReturnWeight>
 where
 	T: pallet_evm::Config,
 	T::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode,
 	<T::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<T::AccountId>>,
 	DispatchValidator: DispatchValidateT<T::AccountId, T::RuntimeCall>,
 	DecodeLimit: Get<u32>,
+	EventsToLogs: DispatchEventsToLogs<<T as frame_system::Config>::RuntimeEvent>,
+	ReturnWeight: Get<bool>,
 {
 	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
 		let input = handle.input();
@@ -84,31 +114,36 @@ where
 		handle
 			.record_external_cost(Some(info.weight.ref_time()), Some(info.weight.proof_size()))?;
 
+		let events_cursor = EventsToLogs::ENABLED.then(EventsCursor::new::<T>);
+
 		match call.dispatch(Some(origin).into()) {
 			Ok(post_info) => {
-				if post_info.pays_fee(&info) == Pays::Yes {
-					let actual_weight = post_info.actual_weight.unwrap_or(info.weight);
-					let cost = T::GasWeightMapping::weight_to_gas(actual_weight);
-					handle.record_cost(cost)?;
-
-					handle.refund_external_cost(
-						Some(
-							info.weight
-								.ref_time()
-								.saturating_sub(actual_weight.ref_time()),
-						),
-						Some(
-							info.weight
-								.proof_size()
-								.saturating_sub(actual_weight.proof_size()),
-						),
-					);
+				record_dispatch_cost::<T>(handle, &info, &post_info)?;
+
+				if let Some(events_cursor) = events_cursor {
+					let address = handle.context().address;
+					for record in events_cursor.deposited_events::<T>(handle)? {
+						if let Some(log) = EventsToLogs::event_to_log(&record.event, address) {
+							emit_log(handle, log.address, log.topics, log.data)?;
+						}
+					}
 				}
 
-				Ok(PrecompileOutput {
-					exit_status: ExitSucceed::Stopped,
-					output: Default::default(),
-				})
+				if ReturnWeight::get() {
+					let actual_weight = post_info.actual_weight.unwrap_or(info.weight);
+					Ok(PrecompileOutput {
+						exit_status: ExitSucceed::Returned,
+						output: encode_arguments((
+							actual_weight.ref_time(),
+							actual_weight.proof_size(),
+						)),
+					})
+				} else {
+					Ok(PrecompileOutput {
+						exit_status: ExitSucceed::Stopped,
+						output: Default::default(),
+					})
+				}
 			}
 			Err(e) => Err(PrecompileFailure::Error {
 				exit_status: ExitError::Other(
@@ -119,6 +154,112 @@ where
 	}
 }
 
+/// Charge the actual weight of a dispatched call, refunding the external cost it didn't use.
+fn record_dispatch_cost<T: pallet_evm::Config>(
+	handle: &mut impl PrecompileHandle,
+	info: &DispatchInfo,
+	post_info: &PostDispatchInfo,
+) -> Result<(), ExitError> {
+	if post_info.pays_fee(info) == Pays::Yes {
+		let actual_weight = post_info.actual_weight.unwrap_or(info.weight);
+		let cost = T::GasWeightMapping::weight_to_gas(actual_weight);
+		handle.record_cost(cost)?;
+
+		handle.refund_external_cost(
+			Some(
+				info.weight
+					.ref_time()
+					.saturating_sub(actual_weight.ref_time()),
+			),
+			Some(
+				info.weight
+					.proof_size()
+					.saturating_sub(actual_weight.proof_size()),
+			),
+		);
+	}
+	Ok(())
+}
+
+/// Gas charged per 32 byte word of events read back after a dispatch, the cost of copying
+/// memory in the EVM.
+const EVENTS_COPY_GAS_PER_WORD: u64 = 3;
+
+/// Position of the end of the `frame_system` events before a dispatch.
+///
+/// Events are appended to the encoded `Events` vector, so the records deposited by the call are
+/// the tail of the value and are read without decoding the earlier events of the block. As
+/// `Events` is cleared when the block is initialized, it is only ever read from the overlay and
+/// does not add to the proof size.
+struct EventsCursor {
+	count: u32,
+	records_len: u32,
+}
+
+impl EventsCursor {
+	fn new<T: frame_system::Config>() -> Self {
+		let count = frame_system::Pallet::<T>::event_count();
+		let len = sp_io::storage::read(&events_key::<T>(), &mut [], 0).unwrap_or_default();
+		Self {
+			count,
+			records_len: len.saturating_sub(Compact::<u32>::compact_len(&count) as u32),
+		}
+	}
+
+	/// Events deposited since the cursor was created, charging for reading them.
+	fn deposited_events<T: frame_system::Config>(
+		&self,
+		handle: &mut impl PrecompileHandle,
+	) -> Result<Vec<EventRecord<T::RuntimeEvent, T::Hash>>, PrecompileFailure> {
+		let count = frame_system::Pallet::<T>::event_count();
+		let deposited = count.saturating_sub(self.count);
+		if deposited == 0 {
+			return Ok(Vec::new());
+		}
+
+		let key = events_key::<T>();
+		let offset = (Compact::<u32>::compact_len(&count) as u32).saturating_add(self.records_len);
+		let len = sp_io::storage::read(&key, &mut [], offset).unwrap_or_default();
+		handle.record_cost(EVENTS_COPY_GAS_PER_WORD.saturating_mul((len as u64 + 31) / 32))?;
+
+		let mut encoded = vec![0u8; len as usize];
+		sp_io::storage::read(&key, &mut encoded, offset);
+		let mut input = &encoded[..];
+		(0..deposited)
+			.map(|_| EventRecord::decode(&mut input))
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(|_| PrecompileFailure::Error {
+				exit_status: ExitError::Other("events decoding failed".into()),
+			})
+	}
+}
+
+fn events_key<T: frame_system::Config>() -> [u8; 32] {
+	storage_prefix(
+		<frame_system::Pallet<T> as PalletInfoAccess>::name().as_bytes(),
+		b"Events",
+	)
+}
+
+/// Conversion of the events deposited by a call dispatched with [`Dispatch`] into EVM logs.
+pub trait DispatchEventsToLogs<RuntimeEvent> {
+	/// Whether the events are read at all, so that `()` costs nothing.
+	const ENABLED: bool = true;
+
+	/// Log to emit for `event`, if any, `precompile` being the address of the dispatch
+	/// precompile.
+	fn event_to_log(event: &RuntimeEvent, precompile: H160) -> Option<Log>;
+}
+
+/// No event is emitted as a log.
+impl<RuntimeEvent> DispatchEventsToLogs<RuntimeEvent> for () {
+	const ENABLED: bool = false;
+
+	fn event_to_log(_event: &RuntimeEvent, _precompile: H160) -> Option<Log> {
+		None
+	}
+}
+
 /// Dispatch validation trait.
 pub trait DispatchValidateT<AccountId, RuntimeCall> {
 	fn validate_before_dispatch(
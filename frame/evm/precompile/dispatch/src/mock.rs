@@ -50,6 +50,7 @@ frame_support::construct_runtime! {
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage},
 		EVM: pallet_evm::{Pallet, Call, Storage, Config, Event<T>},
 		Utility: pallet_utility::{Pallet, Call, Event},
+		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
 	}
 }
 
@@ -111,6 +112,12 @@ impl pallet_balances::Config for Test {
 	type MaxFreezes = ();
 }
 
+impl pallet_sudo::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type WeightInfo = ();
+}
+
 parameter_types! {
 	pub const MinimumPeriod: u64 = 1000;
 }
@@ -162,6 +169,8 @@ impl pallet_evm::Config for Test {
 	type Runner = pallet_evm::runner::stack::Runner<Self>;
 	type OnChargeTransaction = ();
 	type OnCreate = ();
+	type StorageDeposit = ();
+	type EventRevertDataLimit = ();
 	type FindAuthor = FindAuthorTruncated;
 	type GasLimitPovSizeRatio = ();
 	type Timestamp = Timestamp;
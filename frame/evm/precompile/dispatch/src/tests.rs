@@ -18,10 +18,16 @@
 #![cfg(test)]
 
 use super::*;
-use crate::mock::*;
+use crate::{
+	mock::*,
+	typed::{try_dispatch, UtilityPrecompile, CALL_LENGTH_LIMIT},
+};
 
+#[cfg(feature = "sudo")]
+use crate::typed::SudoPrecompile;
 use fp_evm::Context;
 use frame_support::{assert_err, assert_ok};
+use precompile_utils::prelude::*;
 use scale_codec::Encode;
 use sp_core::{H160, U256};
 
@@ -128,3 +134,452 @@ fn dispatch_validator_works_well() {
 		);
 	});
 }
+
+const ALICE: H160 = H160::repeat_byte(0xaa);
+const BOB: H160 = H160::repeat_byte(0xbb);
+
+/// Typed interface to a few pallet calls.
+struct TypedPrecompile;
+
+#[precompile]
+impl TypedPrecompile {
+	#[precompile::public("transfer(address,uint256)")]
+	fn transfer(handle: &mut impl PrecompileHandle, to: H160, value: U256) -> EvmResult {
+		let caller = handle.context().caller;
+		let call = pallet_balances::Call::<Test>::transfer {
+			dest: to,
+			value: value.low_u64(),
+		};
+		try_dispatch::<Test, ()>(handle, caller, call)?;
+		Ok(())
+	}
+
+	#[precompile::public("forceSetBalance(address,uint256)")]
+	fn force_set_balance(handle: &mut impl PrecompileHandle, who: H160, value: U256) -> EvmResult {
+		let caller = handle.context().caller;
+		let call = pallet_balances::Call::<Test>::force_set_balance {
+			who,
+			new_free: value.low_u64(),
+		};
+		let call = pallet_sudo::Call::<Test>::sudo {
+			call: Box::new(call.into()),
+		};
+		try_dispatch::<Test, ()>(handle, caller, call)?;
+		Ok(())
+	}
+}
+
+fn new_typed_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, 1000)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	pallet_sudo::GenesisConfig::<Test> { key: Some(ALICE) }
+		.assimilate_storage(&mut t)
+		.unwrap();
+	t.into()
+}
+
+fn call_typed(
+	caller: H160,
+	input: Vec<u8>,
+) -> (PrecompileResult, pallet_evm_test_vector_support::MockHandle) {
+	let context = Context {
+		address: H160::from_low_u64_be(1024),
+		caller,
+		apparent_value: U256::zero(),
+	};
+	let mut handle = pallet_evm_test_vector_support::MockHandle::new(input, None, context);
+	(TypedPrecompile::execute(&mut handle), handle)
+}
+
+fn selector(signature: [u8; 32]) -> u32 {
+	u32::from_be_bytes(signature[..4].try_into().unwrap())
+}
+
+#[test]
+fn typed_call_is_dispatched_and_charged() {
+	new_typed_test_ext().execute_with(|| {
+		let input = encode_with_selector(
+			selector(keccak256!("transfer(address,uint256)")),
+			(BOB, U256::from(400)),
+		);
+		let (result, handle) = call_typed(ALICE, input);
+
+		assert_ok!(result);
+		assert!(handle.gas_used > 0);
+		assert_eq!(Balances::free_balance(ALICE), 600);
+		assert_eq!(Balances::free_balance(BOB), 400);
+	});
+}
+
+#[test]
+fn typed_call_failure_reverts_with_error_name() {
+	new_typed_test_ext().execute_with(|| {
+		let input = encode_with_selector(
+			selector(keccak256!("forceSetBalance(address,uint256)")),
+			(BOB, U256::from(500)),
+		);
+
+		// Only the sudo key can dispatch the governance call.
+		assert_eq!(
+			call_typed(BOB, input.clone()).0,
+			Err(revert("Dispatched call failed with error: RequireSudo"))
+		);
+		assert_eq!(Balances::free_balance(BOB), 0);
+
+		assert_ok!(call_typed(ALICE, input).0);
+		assert_eq!(Balances::free_balance(BOB), 500);
+	});
+}
+
+#[test]
+fn failed_typed_call_is_charged() {
+	new_typed_test_ext().execute_with(|| {
+		let input = encode_with_selector(
+			selector(keccak256!("forceSetBalance(address,uint256)")),
+			(BOB, U256::from(500)),
+		);
+		let (result, handle) = call_typed(BOB, input);
+		assert_eq!(
+			result,
+			Err(revert("Dispatched call failed with error: RequireSudo"))
+		);
+
+		let call = RuntimeCall::Sudo(pallet_sudo::Call::sudo {
+			call: Box::new(RuntimeCall::Balances(
+				pallet_balances::Call::force_set_balance {
+					who: BOB,
+					new_free: 500,
+				},
+			)),
+		});
+		let weight = call.get_dispatch_info().weight;
+		assert!(weight.ref_time() > 0);
+		assert_eq!(
+			handle.gas_used,
+			<Test as pallet_evm::Config>::GasWeightMapping::weight_to_gas(weight)
+		);
+	});
+}
+
+#[test]
+fn typed_call_is_validated() {
+	new_typed_test_ext().execute_with(|| {
+		pub struct DenyAll;
+		impl DispatchValidateT<H160, RuntimeCall> for DenyAll {
+			fn validate_before_dispatch(
+				_origin: &H160,
+				_call: &RuntimeCall,
+			) -> Option<PrecompileFailure> {
+				Some(revert("This call is not allowed"))
+			}
+		}
+
+		let mut handle = pallet_evm_test_vector_support::MockHandle::new(
+			Vec::new(),
+			None,
+			Context {
+				address: H160::from_low_u64_be(1024),
+				caller: ALICE,
+				apparent_value: U256::zero(),
+			},
+		);
+		let call = pallet_balances::Call::<Test>::transfer {
+			dest: BOB,
+			value: 400,
+		};
+		assert_eq!(
+			try_dispatch::<Test, DenyAll>(&mut handle, ALICE, call),
+			Err(revert("This call is not allowed"))
+		);
+		assert_eq!(Balances::free_balance(BOB), 0);
+	});
+}
+
+std::thread_local! {
+	static DISPATCHED: core::cell::RefCell<Vec<RuntimeCall>> = Default::default();
+}
+
+/// Records the calls built by a typed precompile.
+pub struct RecordCalls;
+impl DispatchValidateT<H160, RuntimeCall> for RecordCalls {
+	fn validate_before_dispatch(_origin: &H160, call: &RuntimeCall) -> Option<PrecompileFailure> {
+		DISPATCHED.with(|calls| calls.borrow_mut().push(call.clone()));
+		None
+	}
+}
+
+#[cfg(feature = "sudo")]
+fn call_sudo(caller: H160, input: Vec<u8>) -> PrecompileResult {
+	let mut handle = pallet_evm_test_vector_support::MockHandle::new(
+		input,
+		None,
+		Context {
+			address: H160::from_low_u64_be(1026),
+			caller,
+			apparent_value: U256::zero(),
+		},
+	);
+	SudoPrecompile::<Test, RecordCalls>::execute(&mut handle)
+}
+
+#[cfg(feature = "sudo")]
+#[test]
+fn sudo_precompile_maps_abi_to_runtime_call() {
+	new_typed_test_ext().execute_with(|| {
+		assert_ok!(call_sudo(
+			ALICE,
+			encode_with_selector(selector(keccak256!("setHeapPages(uint64)")), 64u64),
+		));
+		assert_ok!(call_sudo(
+			ALICE,
+			encode_with_selector(selector(keccak256!("setKey(address)")), BOB),
+		));
+
+		assert_eq!(
+			DISPATCHED.with(|calls| calls.take()),
+			vec![
+				RuntimeCall::Sudo(pallet_sudo::Call::sudo {
+					call: Box::new(RuntimeCall::System(frame_system::Call::set_heap_pages {
+						pages: 64
+					})),
+				}),
+				RuntimeCall::Sudo(pallet_sudo::Call::set_key { new: BOB }),
+			]
+		);
+		assert_eq!(
+			sp_io::storage::get(sp_core::storage::well_known_keys::HEAP_PAGES),
+			Some(64u64.encode().into())
+		);
+		assert_eq!(Sudo::key(), Some(BOB));
+	});
+}
+
+#[cfg(feature = "sudo")]
+#[test]
+fn sudo_precompile_reverts_for_other_callers() {
+	new_typed_test_ext().execute_with(|| {
+		assert_eq!(
+			call_sudo(
+				BOB,
+				encode_with_selector(selector(keccak256!("setHeapPages(uint64)")), 64u64),
+			),
+			Err(revert("Dispatched call failed with error: RequireSudo"))
+		);
+		assert_eq!(
+			call_sudo(
+				BOB,
+				encode_with_selector(selector(keccak256!("setKey(address)")), BOB),
+			),
+			Err(revert("Dispatched call failed with error: RequireSudo"))
+		);
+
+		assert_eq!(
+			sp_io::storage::get(sp_core::storage::well_known_keys::HEAP_PAGES),
+			None
+		);
+		assert_eq!(Sudo::key(), Some(ALICE));
+	});
+}
+
+fn call_utility(caller: H160, input: Vec<u8>) -> PrecompileResult {
+	let mut handle = pallet_evm_test_vector_support::MockHandle::new(
+		input,
+		None,
+		Context {
+			address: H160::from_low_u64_be(1027),
+			caller,
+			apparent_value: U256::zero(),
+		},
+	);
+	UtilityPrecompile::<Test, RecordCalls>::execute(&mut handle)
+}
+
+fn transfer_to_bob(value: u64) -> RuntimeCall {
+	RuntimeCall::Balances(pallet_balances::Call::transfer { dest: BOB, value })
+}
+
+#[test]
+fn utility_precompile_validates_and_dispatches_every_call() {
+	new_typed_test_ext().execute_with(|| {
+		let calls = vec![transfer_to_bob(100), transfer_to_bob(200)];
+		assert_ok!(call_utility(
+			ALICE,
+			encode_with_selector(
+				selector(keccak256!("batchAll(bytes[])")),
+				calls
+					.iter()
+					.map(|call| Bytes(call.encode()))
+					.collect::<Vec<_>>(),
+			),
+		));
+
+		let mut expected = calls.clone();
+		expected.push(RuntimeCall::Utility(pallet_utility::Call::batch_all {
+			calls,
+		}));
+		assert_eq!(DISPATCHED.with(|calls| calls.take()), expected);
+		assert_eq!(Balances::free_balance(BOB), 300);
+	});
+}
+
+#[test]
+fn utility_precompile_batch_all_reverts_when_a_call_fails() {
+	new_typed_test_ext().execute_with(|| {
+		let calls = vec![
+			Bytes(transfer_to_bob(100).encode()),
+			Bytes(transfer_to_bob(5000).encode()),
+		];
+
+		assert!(call_utility(
+			ALICE,
+			encode_with_selector(selector(keccak256!("batchAll(bytes[])")), calls.clone()),
+		)
+		.is_err());
+		assert_eq!(Balances::free_balance(BOB), 0);
+
+		// `batch` keeps the calls dispatched before the failed one.
+		assert_ok!(call_utility(
+			ALICE,
+			encode_with_selector(selector(keccak256!("batch(bytes[])")), calls),
+		));
+		assert_eq!(Balances::free_balance(BOB), 100);
+	});
+}
+
+#[test]
+fn utility_precompile_rejects_undecodable_calls() {
+	new_typed_test_ext().execute_with(|| {
+		let calls = vec![Bytes(transfer_to_bob(100).encode()), Bytes(vec![0xff])];
+
+		assert_eq!(
+			call_utility(
+				ALICE,
+				encode_with_selector(selector(keccak256!("batchAll(bytes[])")), calls),
+			),
+			Err(revert("Call 1 could not be decoded"))
+		);
+		assert_eq!(
+			DISPATCHED.with(|calls| calls.take()),
+			vec![transfer_to_bob(100)]
+		);
+		assert_eq!(Balances::free_balance(BOB), 0);
+
+		let calls = vec![Bytes(vec![0; CALL_LENGTH_LIMIT as usize + 1])];
+		assert_eq!(
+			call_utility(
+				ALICE,
+				encode_with_selector(selector(keccak256!("batchAll(bytes[])")), calls),
+			),
+			Err(revert("Bytes length is larger than the limit"))
+		);
+	});
+}
+
+/// Emits the balance transfers as ERC-20 `Transfer` logs.
+struct TransferLogs;
+impl DispatchEventsToLogs<RuntimeEvent> for TransferLogs {
+	fn event_to_log(event: &RuntimeEvent, precompile: H160) -> Option<Log> {
+		match event {
+			RuntimeEvent::Balances(pallet_balances::Event::Transfer { from, to, amount }) => {
+				Some(Log {
+					address: precompile,
+					topics: vec![
+						keccak256!("Transfer(address,address,uint256)").into(),
+						(*from).into(),
+						(*to).into(),
+					],
+					data: encode_arguments(U256::from(*amount)),
+				})
+			}
+			_ => None,
+		}
+	}
+}
+
+fn transfer_handle(
+	call: &RuntimeCall,
+	precompile: H160,
+) -> pallet_evm_test_vector_support::MockHandle {
+	pallet_evm_test_vector_support::MockHandle::new(
+		call.encode(),
+		None,
+		Context {
+			address: precompile,
+			caller: ALICE,
+			apparent_value: U256::zero(),
+		},
+	)
+}
+
+#[test]
+fn dispatch_emits_selected_events_as_logs() {
+	new_typed_test_ext().execute_with(|| {
+		// Events are not deposited at genesis.
+		System::set_block_number(1);
+
+		// Events deposited earlier in the block are not emitted.
+		assert_ok!(Balances::transfer(RuntimeOrigin::signed(ALICE), BOB, 100));
+		let events_before = System::event_count();
+
+		let call = RuntimeCall::Balances(pallet_balances::Call::transfer {
+			dest: BOB,
+			value: 400,
+		});
+		let precompile = H160::from_low_u64_be(1025);
+		let mut handle = transfer_handle(&call, precompile);
+
+		let output = Dispatch::<Test, (), ConstU32<8>, TransferLogs>::execute(&mut handle)
+			.expect("dispatch succeeds");
+
+		assert!(System::event_count() > events_before);
+		assert_eq!(
+			output,
+			PrecompileOutput {
+				exit_status: ExitSucceed::Stopped,
+				output: Vec::new(),
+			}
+		);
+		assert_eq!(
+			handle.logs,
+			vec![Log {
+				address: precompile,
+				topics: vec![
+					keccak256!("Transfer(address,address,uint256)").into(),
+					ALICE.into(),
+					BOB.into(),
+				],
+				data: encode_arguments(U256::from(400)),
+			}]
+		);
+	});
+}
+
+#[test]
+fn dispatch_returns_actual_weight_when_enabled() {
+	new_typed_test_ext().execute_with(|| {
+		let call = RuntimeCall::Balances(pallet_balances::Call::transfer {
+			dest: BOB,
+			value: 400,
+		});
+		let mut handle = transfer_handle(&call, H160::from_low_u64_be(1025));
+
+		let output = Dispatch::<Test, (), ConstU32<8>, (), ConstBool<true>>::execute(&mut handle)
+			.expect("dispatch succeeds");
+
+		let actual_weight = call.get_dispatch_info().weight;
+		assert_eq!(
+			output,
+			PrecompileOutput {
+				exit_status: ExitSucceed::Returned,
+				output: encode_arguments((actual_weight.ref_time(), actual_weight.proof_size())),
+			}
+		);
+		assert!(handle.logs.is_empty());
+	});
+}
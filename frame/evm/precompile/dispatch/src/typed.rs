@@ -0,0 +1,252 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dispatching specific pallet calls from typed Solidity interfaces.
+//!
+//! Where [`Dispatch`](crate::Dispatch) takes SCALE-encoded calls, a typed precompile is written
+//! with `precompile_utils`: each Solidity function decodes its ABI arguments, builds the
+//! matching `RuntimeCall` and dispatches it with [`try_dispatch`].
+//!
+//! ```ignore
+//! pub struct ProxyPrecompile<Runtime>(PhantomData<Runtime>);
+//!
+//! #[precompile_utils::precompile]
+//! impl<Runtime> ProxyPrecompile<Runtime>
+//! where
+//!     Runtime: pallet_evm::Config + pallet_proxy::Config,
+//!     Runtime::RuntimeCall: From<pallet_proxy::Call<Runtime>>,
+//!     ...
+//! {
+//!     #[precompile::public("removeProxies()")]
+//!     fn remove_proxies(handle: &mut impl PrecompileHandle) -> EvmResult {
+//!         let caller = handle.context().caller;
+//!         try_dispatch::<Runtime, ()>(handle, caller, pallet_proxy::Call::remove_proxies {})?;
+//!         Ok(())
+//!     }
+//! }
+//! ```
+//!
+//! [`UtilityPrecompile`] is built this way. Calls requiring a privileged origin, such as
+//! governance ones, can be exposed by wrapping them in `pallet_sudo::Call::sudo`, which fails
+//! unless the caller is the sudo key. `SudoPrecompile`, available with the `sudo` feature, is
+//! built this way.
+
+#[cfg(feature = "sudo")]
+use alloc::boxed::Box;
+use alloc::{format, vec::Vec};
+use core::marker::PhantomData;
+use fp_evm::{ExitError, PrecompileFailure, PrecompileHandle};
+#[cfg(feature = "sudo")]
+use frame_support::sp_runtime::traits::StaticLookup;
+use frame_support::{
+	codec::DecodeLimit as _,
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::{ConstU32, Get, IsType},
+};
+use pallet_evm::{AddressMapping, GasWeightMapping};
+use precompile_utils::prelude::*;
+
+use crate::{record_dispatch_cost, DispatchValidateT};
+
+/// Dispatch `call` with `origin` as signer, once accepted by `DispatchValidator`.
+///
+/// The weight of the call is charged like [`Dispatch`](crate::Dispatch) does, and a failed
+/// dispatch reverts with the name of its error once its actual weight is charged.
+pub fn try_dispatch<T, DispatchValidator>(
+	handle: &mut impl PrecompileHandle,
+	origin: H160,
+	call: impl Into<T::RuntimeCall>,
+) -> EvmResult<PostDispatchInfo>
+where
+	T: pallet_evm::Config,
+	T::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	<T::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<T::AccountId>>,
+	DispatchValidator: DispatchValidateT<T::AccountId, T::RuntimeCall>,
+{
+	let call = call.into();
+	let info = call.get_dispatch_info();
+
+	if let Some(gas) = handle.gas_limit() {
+		if info.weight.ref_time() > T::GasWeightMapping::gas_to_weight(gas, false).ref_time() {
+			return Err(PrecompileFailure::Error {
+				exit_status: ExitError::OutOfGas,
+			});
+		}
+	}
+
+	let origin = T::AddressMapping::into_account_id(origin);
+	if let Some(err) = DispatchValidator::validate_before_dispatch(&origin, &call) {
+		return Err(err);
+	}
+
+	handle.record_external_cost(Some(info.weight.ref_time()), Some(info.weight.proof_size()))?;
+
+	match call.dispatch(Some(origin).into()) {
+		Ok(post_info) => {
+			record_dispatch_cost::<T>(handle, &info, &post_info)?;
+			Ok(post_info)
+		}
+		Err(e) => {
+			// A revert doesn't consume the remaining gas, the weight used must be paid for.
+			record_dispatch_cost::<T>(handle, &info, &e.post_info)?;
+			Err(revert(format!(
+				"Dispatched call failed with error: {}",
+				<&'static str>::from(e.error)
+			)))
+		}
+	}
+}
+
+/// Maximum number of calls of a batch.
+pub const BATCH_CALLS_LIMIT: u32 = 2u32.pow(9);
+
+/// Maximum length of an encoded call of a batch.
+pub const CALL_LENGTH_LIMIT: u32 = 2u32.pow(16);
+
+// The calls are bounded, as their pointers can alias the same data and make decoding them
+// allocate much more memory than the input holds.
+type EncodedCalls =
+	BoundedVec<BoundedBytes<ConstU32<CALL_LENGTH_LIMIT>>, ConstU32<BATCH_CALLS_LIMIT>>;
+
+/// Typed interface to `pallet_utility`, batching SCALE-encoded calls.
+///
+/// Each call of a batch is decoded with a depth limit of `DecodeLimit`, like
+/// [`Dispatch`](crate::Dispatch) does, and must be accepted by `DispatchValidator` as the batch
+/// itself is. `batch` stops at the first failed call and reports it with the `BatchInterrupted`
+/// event, while `batchAll` reverts.
+pub struct UtilityPrecompile<Runtime, DispatchValidator = (), DecodeLimit = ConstU32<8>>(
+	PhantomData<(Runtime, DispatchValidator, DecodeLimit)>,
+);
+
+#[precompile_utils::precompile]
+impl<Runtime, DispatchValidator, DecodeLimit>
+	UtilityPrecompile<Runtime, DispatchValidator, DecodeLimit>
+where
+	Runtime: pallet_evm::Config + pallet_utility::Config,
+	<Runtime as frame_system::Config>::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>
+		+ GetDispatchInfo
+		+ From<pallet_utility::Call<Runtime>>,
+	<<Runtime as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
+		From<Option<Runtime::AccountId>>,
+	DispatchValidator:
+		DispatchValidateT<Runtime::AccountId, <Runtime as frame_system::Config>::RuntimeCall>,
+	DecodeLimit: Get<u32>,
+{
+	#[precompile::public("batch(bytes[])")]
+	fn batch(handle: &mut impl PrecompileHandle, calls: EncodedCalls) -> EvmResult {
+		let caller = handle.context().caller;
+		let calls = decode_calls::<Runtime, DispatchValidator, DecodeLimit>(caller, calls)?;
+		try_dispatch::<Runtime, DispatchValidator>(
+			handle,
+			caller,
+			pallet_utility::Call::<Runtime>::batch { calls },
+		)?;
+		Ok(())
+	}
+
+	#[precompile::public("batchAll(bytes[])")]
+	fn batch_all(handle: &mut impl PrecompileHandle, calls: EncodedCalls) -> EvmResult {
+		let caller = handle.context().caller;
+		let calls = decode_calls::<Runtime, DispatchValidator, DecodeLimit>(caller, calls)?;
+		try_dispatch::<Runtime, DispatchValidator>(
+			handle,
+			caller,
+			pallet_utility::Call::<Runtime>::batch_all { calls },
+		)?;
+		Ok(())
+	}
+}
+
+/// Decode the calls of a batch, each of them being validated for `origin`.
+fn decode_calls<Runtime, DispatchValidator, DecodeLimit>(
+	origin: H160,
+	calls: EncodedCalls,
+) -> EvmResult<Vec<<Runtime as pallet_utility::Config>::RuntimeCall>>
+where
+	Runtime: pallet_evm::Config + pallet_utility::Config,
+	DispatchValidator:
+		DispatchValidateT<Runtime::AccountId, <Runtime as frame_system::Config>::RuntimeCall>,
+	DecodeLimit: Get<u32>,
+{
+	let origin = Runtime::AddressMapping::into_account_id(origin);
+	Vec::from(calls)
+		.into_iter()
+		.enumerate()
+		.map(|(index, call)| {
+			let call = <Runtime as pallet_utility::Config>::RuntimeCall::decode_with_depth_limit(
+				DecodeLimit::get(),
+				&mut call.as_bytes(),
+			)
+			.map_err(|_| revert(format!("Call {index} could not be decoded")))?;
+			match DispatchValidator::validate_before_dispatch(&origin, call.into_ref()) {
+				Some(err) => Err(err),
+				None => Ok(call),
+			}
+		})
+		.collect()
+}
+
+/// Typed interface to `pallet_sudo`, usable by the sudo key only.
+///
+/// Privileged calls are wrapped in `pallet_sudo::Call::sudo`: a caller other than the sudo key
+/// reverts with `RequireSudo`, while the result of the inner call is reported by the `Sudid`
+/// event.
+#[cfg(feature = "sudo")]
+pub struct SudoPrecompile<Runtime, DispatchValidator = ()>(
+	PhantomData<(Runtime, DispatchValidator)>,
+);
+
+#[cfg(feature = "sudo")]
+#[precompile_utils::precompile]
+impl<Runtime, DispatchValidator> SudoPrecompile<Runtime, DispatchValidator>
+where
+	Runtime: pallet_evm::Config + pallet_sudo::Config,
+	<Runtime as frame_system::Config>::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo>
+		+ GetDispatchInfo
+		+ From<pallet_sudo::Call<Runtime>>,
+	<<Runtime as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin:
+		From<Option<Runtime::AccountId>>,
+	<Runtime as pallet_sudo::Config>::RuntimeCall: From<frame_system::Call<Runtime>>,
+	DispatchValidator:
+		DispatchValidateT<Runtime::AccountId, <Runtime as frame_system::Config>::RuntimeCall>,
+{
+	#[precompile::public("setKey(address)")]
+	fn set_key(handle: &mut impl PrecompileHandle, new_key: H160) -> EvmResult {
+		let caller = handle.context().caller;
+		let new = Runtime::Lookup::unlookup(Runtime::AddressMapping::into_account_id(new_key));
+		try_dispatch::<Runtime, DispatchValidator>(
+			handle,
+			caller,
+			pallet_sudo::Call::<Runtime>::set_key { new },
+		)?;
+		Ok(())
+	}
+
+	#[precompile::public("setHeapPages(uint64)")]
+	fn set_heap_pages(handle: &mut impl PrecompileHandle, pages: u64) -> EvmResult {
+		let caller = handle.context().caller;
+		let call = frame_system::Call::<Runtime>::set_heap_pages { pages };
+		try_dispatch::<Runtime, DispatchValidator>(
+			handle,
+			caller,
+			pallet_sudo::Call::<Runtime>::sudo {
+				call: Box::new(call.into()),
+			},
+		)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,301 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Differential fuzzing harness for the precompiles parsing untrusted input with complex gas
+//! formulas.
+//!
+//! Each target runs precompiles through a [`MockHandle`], turning any panic into a failure that
+//! names the precompile and its input, as a panicking precompile would halt block production.
+//! Outputs are then compared against a reference:
+//! - `modexp` against a square-and-multiply [`reference::modexp`], output and gas,
+//! - `bls12377` and `bw6761` against the multi exponentiation of the same curve, as an addition is
+//!   a multi exponentiation by unit scalars and a multiplication one of a single pair, and the gas
+//!   of successful executions against [`reference::bls12377_gas`] and [`reference::bw6761_gas`].
+//!
+//! The targets are run by `cargo fuzz` from the `fuzz` directory, and replayed over a deterministic
+//! corpus by the tests of this crate.
+
+#![deny(unused_crate_dependencies)]
+
+pub mod reference;
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+	any::type_name,
+	panic::{self, AssertUnwindSafe},
+};
+
+use fp_evm::{Context, Precompile, PrecompileFailure};
+use pallet_evm_precompile_bls12377::{
+	Bls12377G1Add, Bls12377G1Mul, Bls12377G1MultiExp, Bls12377G2Add, Bls12377G2Mul,
+	Bls12377G2MultiExp, Bls12377Pairing,
+};
+use pallet_evm_precompile_bw6761::{
+	Bw6761G1Add, Bw6761G1Mul, Bw6761G1MultiExp, Bw6761G2Add, Bw6761G2Mul, Bw6761G2MultiExp,
+	Bw6761Pairing,
+};
+use pallet_evm_precompile_modexp::Modexp;
+use pallet_evm_test_vector_support::MockHandle;
+use reference::{Group, Operation};
+
+/// Result and gas used of a precompile execution.
+#[derive(Debug)]
+pub struct Outcome {
+	pub result: Result<Vec<u8>, PrecompileFailure>,
+	pub gas_used: u64,
+}
+
+/// Executes `P` on `input`, panicking with the input if the precompile panicked.
+pub fn execute<P: Precompile>(input: &[u8]) -> Outcome {
+	let context = Context {
+		address: Default::default(),
+		caller: Default::default(),
+		apparent_value: From::from(0),
+	};
+	let mut handle = MockHandle::new(input.to_vec(), None, context);
+
+	let result =
+		panic::catch_unwind(AssertUnwindSafe(|| P::execute(&mut handle))).unwrap_or_else(|_| {
+			panic!(
+				"{} panicked on input 0x{}",
+				type_name::<P>(),
+				hex::encode(input)
+			)
+		});
+
+	Outcome {
+		result: result.map(|output| output.output),
+		gas_used: handle.gas_used,
+	}
+}
+
+/// Checks `Modexp` against [`reference::modexp`].
+pub fn modexp(input: &[u8]) {
+	let outcome = execute::<Modexp>(input);
+
+	match reference::modexp(input) {
+		Some(expected) => {
+			assert_eq!(
+				outcome.result.as_ref().ok(),
+				Some(&expected.output),
+				"output mismatch on input 0x{}",
+				hex::encode(input)
+			);
+			assert_eq!(
+				outcome.gas_used,
+				expected.gas,
+				"gas mismatch on input 0x{}",
+				hex::encode(input)
+			);
+		}
+		None => assert!(
+			outcome.result.is_err(),
+			"oversized input 0x{} was accepted",
+			hex::encode(input)
+		),
+	}
+}
+
+/// Sizes of the encodings of a curve, in bytes.
+struct Encoding {
+	g1: usize,
+	g2: usize,
+	scalar: usize,
+}
+
+const BLS12377_ENCODING: Encoding = Encoding {
+	g1: 128,
+	g2: 256,
+	scalar: 32,
+};
+
+const BW6761_ENCODING: Encoding = Encoding {
+	g1: 192,
+	g2: 192,
+	scalar: 64,
+};
+
+/// Checks the BLS12-377 precompiles, the first byte of `data` selecting the operation.
+pub fn bls12377(data: &[u8]) {
+	let Some((selector, input)) = data.split_first() else {
+		return;
+	};
+
+	match selector % 5 {
+		0 => check_add::<Bls12377G1Add, Bls12377G1MultiExp>(
+			input,
+			BLS12377_ENCODING.g1,
+			BLS12377_ENCODING.scalar,
+			Group::G1,
+			reference::bls12377_gas,
+		),
+		1 => check_mul::<Bls12377G1Mul, Bls12377G1MultiExp>(
+			input,
+			BLS12377_ENCODING.g1,
+			BLS12377_ENCODING.scalar,
+			Group::G1,
+			reference::bls12377_gas,
+		),
+		2 => check_add::<Bls12377G2Add, Bls12377G2MultiExp>(
+			input,
+			BLS12377_ENCODING.g2,
+			BLS12377_ENCODING.scalar,
+			Group::G2,
+			reference::bls12377_gas,
+		),
+		3 => check_mul::<Bls12377G2Mul, Bls12377G2MultiExp>(
+			input,
+			BLS12377_ENCODING.g2,
+			BLS12377_ENCODING.scalar,
+			Group::G2,
+			reference::bls12377_gas,
+		),
+		_ => check_gas::<Bls12377Pairing>(
+			input,
+			&execute::<Bls12377Pairing>(input),
+			reference::bls12377_gas(Operation::Pairing, input.len()),
+		),
+	}
+}
+
+/// Checks the BW6-761 precompiles, the first byte of `data` selecting the operation.
+pub fn bw6761(data: &[u8]) {
+	let Some((selector, input)) = data.split_first() else {
+		return;
+	};
+
+	match selector % 5 {
+		0 => check_add::<Bw6761G1Add, Bw6761G1MultiExp>(
+			input,
+			BW6761_ENCODING.g1,
+			BW6761_ENCODING.scalar,
+			Group::G1,
+			reference::bw6761_gas,
+		),
+		1 => check_mul::<Bw6761G1Mul, Bw6761G1MultiExp>(
+			input,
+			BW6761_ENCODING.g1,
+			BW6761_ENCODING.scalar,
+			Group::G1,
+			reference::bw6761_gas,
+		),
+		2 => check_add::<Bw6761G2Add, Bw6761G2MultiExp>(
+			input,
+			BW6761_ENCODING.g2,
+			BW6761_ENCODING.scalar,
+			Group::G2,
+			reference::bw6761_gas,
+		),
+		3 => check_mul::<Bw6761G2Mul, Bw6761G2MultiExp>(
+			input,
+			BW6761_ENCODING.g2,
+			BW6761_ENCODING.scalar,
+			Group::G2,
+			reference::bw6761_gas,
+		),
+		_ => check_gas::<Bw6761Pairing>(
+			input,
+			&execute::<Bw6761Pairing>(input),
+			reference::bw6761_gas(Operation::Pairing, input.len()),
+		),
+	}
+}
+
+/// Gas of a successful curve operation on `input_len` bytes.
+type CurveGas = fn(Operation, usize) -> u64;
+
+/// Checks the gas used by a successful execution of `P` on `input`, a failed one consuming all
+/// the gas anyway.
+fn check_gas<P: Precompile>(input: &[u8], outcome: &Outcome, expected: u64) {
+	if outcome.result.is_ok() {
+		assert_eq!(
+			outcome.gas_used,
+			expected,
+			"{} gas mismatch on input 0x{}",
+			type_name::<P>(),
+			hex::encode(input)
+		);
+	}
+}
+
+/// Checks that adding two points matches their multi exponentiation by unit scalars.
+fn check_add<Add: Precompile, MultiExp: Precompile>(
+	input: &[u8],
+	point: usize,
+	scalar: usize,
+	group: Group,
+	gas: CurveGas,
+) {
+	let sum = execute::<Add>(input);
+	check_gas::<Add>(input, &sum, gas(Operation::Add(group), input.len()));
+	if input.len() != 2 * point {
+		return;
+	}
+
+	let mut unit = vec![0u8; scalar];
+	unit[scalar - 1] = 1;
+	let pairs = [&input[..point], &unit, &input[point..], &unit].concat();
+	let multi_exp = execute::<MultiExp>(&pairs);
+	check_gas::<MultiExp>(
+		&pairs,
+		&multi_exp,
+		gas(Operation::MultiExp(group), pairs.len()),
+	);
+
+	assert_eq!(
+		sum.result,
+		multi_exp.result,
+		"{} and {} mismatch on input 0x{}",
+		type_name::<Add>(),
+		type_name::<MultiExp>(),
+		hex::encode(input)
+	);
+}
+
+/// Checks that multiplying a point matches the multi exponentiation of the single pair, the
+/// input of both having the same layout.
+fn check_mul<Mul: Precompile, MultiExp: Precompile>(
+	input: &[u8],
+	point: usize,
+	scalar: usize,
+	group: Group,
+	gas: CurveGas,
+) {
+	let product = execute::<Mul>(input);
+	check_gas::<Mul>(input, &product, gas(Operation::Mul(group), input.len()));
+	if input.len() != point + scalar {
+		return;
+	}
+
+	let multi_exp = execute::<MultiExp>(input);
+	check_gas::<MultiExp>(
+		input,
+		&multi_exp,
+		gas(Operation::MultiExp(group), input.len()),
+	);
+
+	assert_eq!(
+		product.result,
+		multi_exp.result,
+		"{} and {} mismatch on input 0x{}",
+		type_name::<Mul>(),
+		type_name::<MultiExp>(),
+		hex::encode(input)
+	);
+}
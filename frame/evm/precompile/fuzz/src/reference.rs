@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reference implementations the precompiles are compared against.
+
+use num::{BigUint, Integer, One, ToPrimitive, Zero};
+
+/// Expected output and gas of an execution.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Expected {
+	pub output: Vec<u8>,
+	pub gas: u64,
+}
+
+/// Maximum length of the base, exponent and modulus accepted by the EVM pallet.
+const MODEXP_MAX_LENGTH: usize = 1024;
+
+/// MODEXP as specified by EIP-198, computed by square-and-multiply, with the gas of EIP-2565
+/// adjusted to the EVM pallet.
+///
+/// The EVM pallet deviates from the EIPs as follows:
+/// - lengths above 1024 bytes are rejected, returning `None`,
+/// - an empty modulus returns before charging any gas, where EIP-2565 charges at least 200,
+/// - the iteration count of an exponent longer than 32 bytes whose first 32 bytes are zero is
+///   one lower than the EIP-2565 one, `8 * (len - 32) - 1` instead of `8 * (len - 32)`,
+/// - the gas is 20 times higher for an even modulus, whose exponentiation is slower.
+///
+/// The second and third ones are bugs of the pallet, the gas of the EIP being [`eip2565_gas`].
+pub fn modexp(input: &[u8]) -> Option<Expected> {
+	// Missing input bytes are zeros.
+	let read = |offset: usize, len: usize| -> Vec<u8> {
+		(offset..offset + len)
+			.map(|i| input.get(i).copied().unwrap_or_default())
+			.collect()
+	};
+	let read_length = |offset: usize| -> Option<usize> {
+		let length = BigUint::from_bytes_be(&read(offset, 32));
+		if length > BigUint::from(MODEXP_MAX_LENGTH) {
+			return None;
+		}
+		length.to_usize()
+	};
+
+	let base_len = read_length(0)?;
+	let exp_len = read_length(32)?;
+	let mod_len = read_length(64)?;
+
+	if mod_len == 0 {
+		return Some(Expected {
+			output: Vec::new(),
+			gas: 0,
+		});
+	}
+
+	let base = BigUint::from_bytes_be(&read(96, base_len));
+	let exponent = read(96 + base_len, exp_len);
+	let modulus = BigUint::from_bytes_be(&read(96 + base_len + exp_len, mod_len));
+
+	let mut result = BigUint::zero();
+	if !modulus.is_zero() {
+		result = BigUint::one() % &modulus;
+		for byte in &exponent {
+			for bit in (0..8).rev() {
+				result = &result * &result % &modulus;
+				if (byte >> bit) & 1 == 1 {
+					result = result * &base % &modulus;
+				}
+			}
+		}
+	}
+
+	let bytes = result.to_bytes_be();
+	let mut output = vec![0u8; mod_len];
+	if !result.is_zero() {
+		output[mod_len - bytes.len()..].copy_from_slice(&bytes);
+	}
+
+	let mut iteration_count = eip2565_iteration_count(&exponent);
+	if exponent.len() > 32 && exponent[..32].iter().all(|byte| *byte == 0) {
+		iteration_count -= 1;
+	}
+	let gas = modexp_gas(base_len, mod_len, iteration_count).saturating_mul(if modulus.is_even() {
+		20
+	} else {
+		1
+	});
+
+	Some(Expected { output, gas })
+}
+
+/// Gas of MODEXP as specified by EIP-2565, for the given lengths and exponent.
+///
+/// https://eips.ethereum.org/EIPS/eip-2565#specification
+pub fn eip2565_gas(base_len: usize, mod_len: usize, exponent: &[u8]) -> u64 {
+	modexp_gas(base_len, mod_len, eip2565_iteration_count(exponent))
+}
+
+/// Adjusted exponent length: 8 bits per exponent byte past the first 32, plus the index of the
+/// highest set bit of the first 32 bytes, if any.
+fn eip2565_iteration_count(exponent: &[u8]) -> u64 {
+	let head = BigUint::from_bytes_be(&exponent[..exponent.len().min(32)]);
+	8 * exponent.len().saturating_sub(32) as u64 + head.bits().saturating_sub(1)
+}
+
+fn modexp_gas(base_len: usize, mod_len: usize, iteration_count: u64) -> u64 {
+	let words = (base_len.max(mod_len) as u64 + 7) / 8;
+	let multiplication_complexity = words * words;
+
+	(multiplication_complexity * iteration_count.max(1) / 3).max(200)
+}
+
+/// A group of a pairing friendly curve.
+#[derive(Clone, Copy, Debug)]
+pub enum Group {
+	G1,
+	G2,
+}
+
+/// Operations of the EIP-2539 and EIP-3026 precompiles.
+#[derive(Clone, Copy, Debug)]
+pub enum Operation {
+	Add(Group),
+	Mul(Group),
+	MultiExp(Group),
+	Pairing,
+}
+
+/// Discounts of EIP-2539 multi exponentiations of `k` pairs over `k` multiplications, in
+/// thousandths, the last one applying above 128 pairs.
+const EIP2539_MULTIEXP_DISCOUNTS: [u16; 128] = [
+	1200, 888, 764, 641, 594, 547, 500, 453, 438, 423, 408, 394, 379, 364, 349, 334, 330, 326, 322,
+	318, 314, 310, 306, 302, 298, 294, 289, 285, 281, 277, 273, 269, 268, 266, 265, 263, 262, 260,
+	259, 257, 256, 254, 253, 251, 250, 248, 247, 245, 244, 242, 241, 239, 238, 236, 235, 233, 232,
+	231, 229, 228, 226, 225, 223, 222, 221, 220, 219, 219, 218, 217, 216, 216, 215, 214, 213, 213,
+	212, 211, 211, 210, 209, 208, 208, 207, 206, 205, 205, 204, 203, 202, 202, 201, 200, 199, 199,
+	198, 197, 196, 196, 195, 194, 193, 193, 192, 191, 191, 190, 189, 188, 188, 187, 186, 185, 185,
+	184, 183, 182, 182, 181, 180, 179, 179, 178, 177, 176, 176, 175, 174,
+];
+
+/// Gas of a successful BLS12-377 operation on `input_len` bytes, as specified by EIP-2539.
+///
+/// https://eips.ethereum.org/EIPS/eip-2539#gas-schedule
+pub fn bls12377_gas(operation: Operation, input_len: usize) -> u64 {
+	let multiplication = |group| match group {
+		Group::G1 => 12_000,
+		Group::G2 => 55_000,
+	};
+
+	match operation {
+		Operation::Add(Group::G1) => 600,
+		Operation::Add(Group::G2) => 4_500,
+		Operation::Mul(group) => multiplication(group),
+		Operation::MultiExp(group) => {
+			let pair_len = match group {
+				Group::G1 => 128 + 32,
+				Group::G2 => 256 + 32,
+			};
+			let k = input_len / pair_len;
+			if k == 0 {
+				return 0;
+			}
+			let discount = EIP2539_MULTIEXP_DISCOUNTS[k.min(128) - 1];
+			k as u64 * multiplication(group) * discount as u64 / 1_000
+		}
+		Operation::Pairing => 65_000 + 55_000 * (input_len / 384) as u64,
+	}
+}
+
+/// Gas of a successful BW6-761 operation.
+///
+/// The EVM pallet does not implement the gas schedule of EIP-3026 and charges nothing for any
+/// operation, which is a bug of the pallet.
+pub fn bw6761_gas(_operation: Operation, _input_len: usize) -> u64 {
+	0
+}
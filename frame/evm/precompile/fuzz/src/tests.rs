@@ -0,0 +1,233 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fs, path::Path};
+
+use super::*;
+
+/// Number of mutants replayed per seed.
+const MUTANTS: usize = 8;
+/// Number of test vectors used as seeds per file, the arkworks precompiles being slow without
+/// optimizations.
+const SEEDS_PER_FILE: usize = 4;
+
+/// Deterministic xorshift generator, so that every run replays the same corpus.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	fn below(&mut self, bound: usize) -> usize {
+		(self.next() % bound as u64) as usize
+	}
+}
+
+/// Mutates `input` like a fuzzer would, flipping bits, overwriting bytes, truncating or extending
+/// it.
+fn mutate(rng: &mut Rng, input: &[u8]) -> Vec<u8> {
+	let mut mutant = input.to_vec();
+	for _ in 0..=rng.below(3) {
+		match rng.below(4) {
+			0 if !mutant.is_empty() => {
+				let index = rng.below(mutant.len());
+				mutant[index] ^= 1 << rng.below(8);
+			}
+			1 if !mutant.is_empty() => {
+				let index = rng.below(mutant.len());
+				mutant[index] = rng.next() as u8;
+			}
+			2 => {
+				let len = rng.below(mutant.len() + 1);
+				mutant.truncate(len);
+			}
+			_ => {
+				let len = rng.below(64);
+				mutant.extend((0..len).map(|_| rng.next() as u8));
+			}
+		}
+	}
+	mutant
+}
+
+/// Inputs of the first `limit` test vectors of a file, prefixed with `selector` if any.
+fn vector_inputs(file: &str, limit: usize, selector: Option<u8>) -> Vec<Vec<u8>> {
+	let data = fs::read_to_string(Path::new("../testdata").join(file))
+		.unwrap_or_else(|e| panic!("failed to read {file}: {e}"));
+	let vectors: Vec<serde_json::Value> = serde_json::from_str(&data).expect("expected json array");
+
+	vectors
+		.iter()
+		.take(limit)
+		.map(|vector| {
+			let input = vector["Input"].as_str().expect("expected hex input");
+			let input = hex::decode(input).expect("Could not hex-decode test input data");
+			selector.into_iter().chain(input).collect()
+		})
+		.collect()
+}
+
+/// Replays the seeds, their mutants, and the `cargo fuzz` corpus of `target` if there is one.
+fn replay(target: &str, seeds: Vec<Vec<u8>>, check: fn(&[u8])) {
+	let mut rng = Rng(0x5eed);
+	for seed in &seeds {
+		check(seed);
+		for _ in 0..MUTANTS {
+			check(&mutate(&mut rng, seed));
+		}
+	}
+
+	if let Ok(entries) = fs::read_dir(Path::new("fuzz/corpus").join(target)) {
+		for entry in entries {
+			let path = entry.expect("failed to read corpus").path();
+			check(&fs::read(path).expect("failed to read corpus input"));
+		}
+	}
+}
+
+fn modexp_input(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+	let length = |bytes: &[u8]| {
+		let mut length = [0u8; 32];
+		length[24..].copy_from_slice(&(bytes.len() as u64).to_be_bytes());
+		length
+	};
+
+	[
+		&length(base)[..],
+		&length(exponent),
+		&length(modulus),
+		base,
+		exponent,
+		modulus,
+	]
+	.concat()
+}
+
+#[test]
+fn reference_modexp_matches_test_vectors() {
+	let data = fs::read_to_string("../testdata/modexp_eip2565.json").expect("failed to read");
+	let vectors: Vec<serde_json::Value> = serde_json::from_str(&data).expect("expected json array");
+
+	for vector in vectors {
+		let input = hex::decode(vector["Input"].as_str().unwrap()).unwrap();
+		let expected = reference::modexp(&input).expect("lengths are in bounds");
+		assert_eq!(
+			hex::encode(expected.output),
+			vector["Expected"].as_str().unwrap()
+		);
+		assert_eq!(Some(expected.gas), vector["Gas"].as_u64());
+	}
+}
+
+#[test]
+fn reference_modexp_gas_deviates_from_eip2565() {
+	// An empty modulus is free.
+	assert_eq!(reference::eip2565_gas(64, 0, &[3]), 200);
+	assert_eq!(
+		reference::modexp(&modexp_input(&[2; 64], &[3], &[])).map(|expected| expected.gas),
+		Some(0)
+	);
+
+	// A zero head lowers the iteration count of a long exponent by one.
+	let exponent = [[0; 32].as_slice(), &[1]].concat();
+	assert_eq!(reference::eip2565_gas(256, 1, &exponent), 1024 * 8 / 3);
+	assert_eq!(
+		reference::modexp(&modexp_input(&[7; 256], &exponent, &[13])).map(|expected| expected.gas),
+		Some(1024 * 7 / 3)
+	);
+
+	// An even modulus is 20 times more expensive.
+	assert_eq!(reference::eip2565_gas(256, 1, &[1; 4]), 1024 * 24 / 3);
+	assert_eq!(
+		reference::modexp(&modexp_input(&[7; 256], &[1; 4], &[8])).map(|expected| expected.gas),
+		Some(1024 * 24 / 3 * 20)
+	);
+}
+
+#[test]
+fn replay_modexp() {
+	let mut seeds = vector_inputs("modexp_eip2565.json", usize::MAX, None);
+	seeds.extend([
+		Vec::new(),
+		// Empty modulus.
+		modexp_input(&[2; 64], &[3], &[]),
+		// Even modulus.
+		modexp_input(&[3], &[5], &[8]),
+		// Zero modulus.
+		modexp_input(&[2], &[3], &[0]),
+		// Exponent longer than 32 bytes, with a zero head.
+		modexp_input(&[7], &[[0; 32].as_slice(), &[1]].concat(), &[13]),
+		// Base length above the 1024 bytes bound.
+		modexp_input(&[1; 1025], &[1], &[3]),
+	]);
+
+	replay("modexp", seeds, modexp);
+}
+
+#[test]
+fn replay_bls12377() {
+	let mut seeds = Vec::new();
+	for (selector, operation) in ["G1Add", "G1Mul", "G2Add", "G2Mul"].iter().enumerate() {
+		let selector = Some(selector as u8);
+		seeds.extend(vector_inputs(
+			&format!("bls12377{operation}.json"),
+			SEEDS_PER_FILE,
+			selector,
+		));
+		seeds.extend(vector_inputs(
+			&format!("fail-bls12377{operation}.json"),
+			usize::MAX,
+			selector,
+		));
+	}
+	seeds.extend(vector_inputs(
+		"fail-bls12377Pairing.json",
+		usize::MAX,
+		Some(4),
+	));
+
+	replay("bls12377", seeds, bls12377);
+}
+
+#[test]
+fn replay_bw6761() {
+	let mut seeds = Vec::new();
+	for (selector, operation) in ["G1Add", "G1Mul", "G2Add", "G2Mul"].iter().enumerate() {
+		let selector = Some(selector as u8);
+		seeds.extend(vector_inputs(
+			&format!("bw6761{operation}.json"),
+			SEEDS_PER_FILE,
+			selector,
+		));
+		seeds.extend(vector_inputs(
+			&format!("fail-bw6761{operation}.json"),
+			usize::MAX,
+			selector,
+		));
+	}
+	seeds.extend(vector_inputs(
+		"fail-bw6761Pairing.json",
+		usize::MAX,
+		Some(4),
+	));
+
+	replay("bw6761", seeds, bw6761);
+}
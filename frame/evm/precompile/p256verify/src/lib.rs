@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! P-256 (secp256r1) signature verification precompile, as specified by
+//! [RIP-7212](https://github.com/ethereum/RIPs/blob/master/RIPS/rip-7212.md).
+//!
+//! The input is the 32-byte message hash followed by the `r` and `s` signature components and
+//! the `x` and `y` public key coordinates, all 32-byte big-endian. The output is `1` as a 32-byte
+//! word if the signature is valid, and empty otherwise, including when the input is malformed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use fp_evm::{ExitSucceed, LinearCostPrecompile, PrecompileFailure};
+use p256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+
+pub struct P256Verify;
+
+impl P256Verify {
+	/// Whether `input` holds a valid signature.
+	fn verify(input: &[u8]) -> bool {
+		if input.len() != 160 {
+			return false;
+		}
+
+		// Rejects `r` and `s` outside of `[1, n - 1]`.
+		let signature = match Signature::from_slice(&input[32..96]) {
+			Ok(signature) => signature,
+			Err(_) => return false,
+		};
+
+		// Uncompressed SEC1 encoding, rejecting points not on the curve and the identity.
+		let mut public_key = [0u8; 65];
+		public_key[0] = 0x04;
+		public_key[1..].copy_from_slice(&input[96..160]);
+		let verifying_key = match VerifyingKey::from_sec1_bytes(&public_key) {
+			Ok(verifying_key) => verifying_key,
+			Err(_) => return false,
+		};
+
+		verifying_key
+			.verify_prehash(&input[..32], &signature)
+			.is_ok()
+	}
+}
+
+impl LinearCostPrecompile for P256Verify {
+	const BASE: u64 = 3450;
+	const WORD: u64 = 0;
+
+	fn execute(input: &[u8], _: u64) -> Result<(ExitSucceed, Vec<u8>), PrecompileFailure> {
+		let output = if Self::verify(input) {
+			let mut output = [0u8; 32];
+			output[31] = 1;
+			output.to_vec()
+		} else {
+			Vec::new()
+		};
+
+		Ok((ExitSucceed::Returned, output))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pallet_evm_test_vector_support::test_precompile_test_vectors;
+
+	// `p256Verify.json` holds the Wycheproof `ecdsa_secp256r1_sha256_test` vectors whose
+	// signature is valid DER with `r` and `s` fitting in 32 bytes, the message being hashed with
+	// SHA-256, followed by malformed inputs and public keys derived from the first valid vector.
+	#[test]
+	fn process_consensus_tests() -> Result<(), String> {
+		test_precompile_test_vectors::<P256Verify>("../testdata/p256Verify.json")?;
+		Ok(())
+	}
+}
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Read-only precompile exposing runtime state to contracts.
+//!
+//! Raw storage values can only be read at the keys accepted by a [`StorageWhitelist`], while
+//! typed queries return ABI-encoded values. Besides the gas of the database reads, the size of
+//! the data read is recorded as proof size so that the PoV accounting of parachains stays
+//! correct. The proof size of a raw read is charged for the largest readable value before the
+//! value is loaded, and the unused part is refunded afterwards.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use frame_support::{
+	codec::Encode,
+	traits::{ConstU32, Get},
+};
+
+// Frontier
+use pallet_evm::GasWeightMapping;
+use precompile_utils::prelude::*;
+
+/// Gas cost of every word of returned data, like the identity precompile.
+const COST_PER_WORD: u64 = 3;
+
+/// Proof size of the trie nodes on the path to a raw storage value, estimated as a branch node
+/// holding 16 child hashes.
+pub const TRIE_NODE_PROOF_SIZE: u64 = 16 * 32;
+
+/// Default maximum length of a value read with `readStorage`.
+pub type DefaultMaxValueLen = ConstU32<4096>;
+
+/// Storage keys contracts are allowed to read.
+pub trait StorageWhitelist {
+	/// Whether contracts can read the value at `key`.
+	fn is_allowed(key: &[u8]) -> bool;
+}
+
+/// No key can be read.
+impl StorageWhitelist for () {
+	fn is_allowed(_key: &[u8]) -> bool {
+		false
+	}
+}
+
+/// Allows the keys starting with one of `Prefixes`, such as the
+/// `frame_support::storage::storage_prefix` of a storage item.
+pub struct AllowedPrefixes<Prefixes>(PhantomData<Prefixes>);
+
+impl<Prefixes: Get<Vec<Vec<u8>>>> StorageWhitelist for AllowedPrefixes<Prefixes> {
+	fn is_allowed(key: &[u8]) -> bool {
+		Prefixes::get().iter().any(|prefix| key.starts_with(prefix))
+	}
+}
+
+/// Storage reader precompile.
+///
+/// Values longer than `MaxValueLen` cannot be read, the whitelisted keys must hold values
+/// bounded by it.
+pub struct StorageReaderPrecompile<Runtime, Whitelist = (), MaxValueLen = DefaultMaxValueLen>(
+	PhantomData<(Runtime, Whitelist, MaxValueLen)>,
+);
+
+#[precompile]
+impl<Runtime, Whitelist, MaxValueLen> StorageReaderPrecompile<Runtime, Whitelist, MaxValueLen>
+where
+	Runtime: pallet_evm::Config,
+	Whitelist: StorageWhitelist,
+	MaxValueLen: Get<u32>,
+{
+	/// Whether a value is stored at `key`, and the SCALE-encoded value.
+	#[precompile::public("readStorage(bytes)")]
+	#[precompile::view]
+	fn read_storage(handle: &mut impl PrecompileHandle, key: Bytes) -> EvmResult<(bool, Bytes)> {
+		let key = key.as_bytes();
+		if !Whitelist::is_allowed(key) {
+			return Err(revert("Storage key is not allowed"));
+		}
+
+		// The value is loaded into the proof by the first read, charge the bound before it.
+		let max_len = MaxValueLen::get() as usize;
+		Self::record_db_read(handle)?;
+		Self::record_proof_size(
+			handle,
+			key.len()
+				.saturating_add(TRIE_NODE_PROOF_SIZE as usize)
+				.saturating_add(max_len),
+		)?;
+
+		let len = match sp_io::storage::read(key, &mut [], 0) {
+			Some(len) => len as usize,
+			None => {
+				handle.refund_external_cost(None, Some(max_len as u64));
+				return Ok((false, Bytes::default()));
+			}
+		};
+		if len > max_len {
+			return Err(revert("Storage value is too large"));
+		}
+		handle.refund_external_cost(None, Some((max_len - len) as u64));
+		handle.record_cost(words(len).saturating_mul(COST_PER_WORD))?;
+
+		let value = sp_io::storage::get(key).unwrap_or_default();
+		Ok((true, value.to_vec().into()))
+	}
+
+	/// Author of the current block, found with `pallet_evm::Config::FindAuthor`.
+	#[precompile::public("blockAuthor()")]
+	#[precompile::view]
+	fn block_author(handle: &mut impl PrecompileHandle) -> EvmResult<H160> {
+		Self::record_db_read(handle)?;
+		let digest = frame_system::Pallet::<Runtime>::digest();
+		Self::record_proof_size(handle, digest.encoded_size())?;
+
+		Ok(pallet_evm::Pallet::<Runtime>::find_author())
+	}
+}
+
+impl<Runtime, Whitelist, MaxValueLen> StorageReaderPrecompile<Runtime, Whitelist, MaxValueLen>
+where
+	Runtime: pallet_evm::Config,
+{
+	fn record_db_read(handle: &mut impl PrecompileHandle) -> EvmResult {
+		let weight = <Runtime as frame_system::Config>::DbWeight::get().reads(1);
+		handle.record_cost(Runtime::GasWeightMapping::weight_to_gas(weight))?;
+		Ok(())
+	}
+
+	fn record_proof_size(handle: &mut impl PrecompileHandle, size: usize) -> EvmResult {
+		handle.record_external_cost(None, Some(size as u64))?;
+		Ok(())
+	}
+}
+
+/// Number of 32-byte words needed to hold `len` bytes.
+fn words(len: usize) -> u64 {
+	(len as u64).saturating_add(31) / 32
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use pallet_evm::{Context, Precompile};
+use pallet_evm_test_vector_support::MockHandle;
+
+use super::*;
+use crate::mock::*;
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap()
+		.into()
+}
+
+fn call(input: Vec<u8>) -> (PrecompileResult, MockHandle) {
+	let context = Context {
+		address: H160::from_low_u64_be(2060),
+		caller: H160::repeat_byte(0xaa),
+		apparent_value: U256::zero(),
+	};
+	let mut handle = MockHandle::new(input, None, context);
+	handle.is_static = true;
+	(StorageReader::execute(&mut handle), handle)
+}
+
+fn selector(signature: [u8; 32]) -> u32 {
+	u32::from_be_bytes(signature[..4].try_into().unwrap())
+}
+
+fn read_storage(key: &[u8]) -> (PrecompileResult, MockHandle) {
+	call(encode_with_selector(
+		selector(keccak256!("readStorage(bytes)")),
+		Bytes::from(key),
+	))
+}
+
+#[test]
+fn reads_whitelisted_key_and_records_proof_size() {
+	new_test_ext().execute_with(|| {
+		sp_io::storage::set(b"allowed:key", &[1, 2, 3]);
+
+		let (result, handle) = read_storage(b"allowed:key");
+		assert_eq!(
+			result.expect("call succeeds").output,
+			encode_arguments((true, Bytes::from(&[1u8, 2, 3][..])))
+		);
+		assert_eq!(handle.proof_size_used, 11 + TRIE_NODE_PROOF_SIZE + 3);
+		assert!(handle.gas_used > 0);
+	});
+}
+
+#[test]
+fn missing_value_is_not_found() {
+	new_test_ext().execute_with(|| {
+		let (result, handle) = read_storage(b"allowed:missing");
+		assert_eq!(
+			result.expect("call succeeds").output,
+			encode_arguments((false, Bytes::default()))
+		);
+		assert_eq!(handle.proof_size_used, 15 + TRIE_NODE_PROOF_SIZE);
+	});
+}
+
+#[test]
+fn value_longer_than_bound_reverts() {
+	new_test_ext().execute_with(|| {
+		sp_io::storage::set(b"allowed:large", &[1; 17]);
+
+		let (result, handle) = read_storage(b"allowed:large");
+		assert_eq!(result, Err(revert("Storage value is too large")));
+		// The bound stays charged.
+		assert_eq!(handle.proof_size_used, 13 + TRIE_NODE_PROOF_SIZE + 16);
+	});
+}
+
+#[test]
+fn key_outside_whitelist_reverts() {
+	new_test_ext().execute_with(|| {
+		sp_io::storage::set(b"secret", &[1]);
+
+		let (result, handle) = read_storage(b"secret");
+		assert_eq!(result, Err(revert("Storage key is not allowed")));
+		assert_eq!(handle.proof_size_used, 0);
+	});
+}
+
+#[test]
+fn block_author_is_returned() {
+	new_test_ext().execute_with(|| {
+		let (result, handle) = call(selector(keccak256!("blockAuthor()")).to_be_bytes().to_vec());
+		assert_eq!(
+			result.expect("call succeeds").output,
+			encode_arguments(H160::repeat_byte(0x12))
+		);
+		assert!(handle.proof_size_used > 0);
+	});
+}
@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![deny(unused_crate_dependencies)]
+
+mod precompile;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+use tiny_keccak::{Hasher, Keccak};
+
+fn keccak(input: &[u8]) -> [u8; 32] {
+	let mut keccak = Keccak::v256();
+	let mut output = [0u8; 32];
+	keccak.update(input);
+	keccak.finalize(&mut output);
+	output
+}
+
+/// Keccak-256 hash of a string literal, as a `[u8; 32]` computed at compile time.
+///
+/// ```ignore
+/// const TRANSFER_TOPIC: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+/// ```
+#[proc_macro]
+pub fn keccak256(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as LitStr);
+	let hash = keccak(input.value().as_bytes());
+	quote!([#(#hash),*]).into()
+}
+
+/// Implement `fp_evm::Precompile` for the type of an `impl` block, dispatching calls to its
+/// functions by Solidity selector.
+///
+/// Functions are exposed with `#[precompile::public("name(type,..)")]`, which can be repeated
+/// for aliases. Their first parameter is the precompile handle, the others are decoded from the
+/// call input, and they return an `EvmResult` of the encoded output. Functions are non-payable
+/// by default, `#[precompile::view]` and `#[precompile::payable]` change their mutability.
+/// A function annotated with `#[precompile::fallback]` and taking only the handle is called for
+/// unknown selectors.
+#[proc_macro_attribute]
+pub fn precompile(attr: TokenStream, item: TokenStream) -> TokenStream {
+	if !attr.is_empty() {
+		return syn::Error::new(
+			proc_macro2::Span::call_site(),
+			"`precompile` takes no arguments",
+		)
+		.to_compile_error()
+		.into();
+	}
+
+	let item = parse_macro_input!(item as syn::ItemImpl);
+	match precompile::expand(item) {
+		Ok(expanded) => expanded.into(),
+		Err(err) => err.to_compile_error().into(),
+	}
+}
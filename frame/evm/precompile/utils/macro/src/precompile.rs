@@ -0,0 +1,290 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, Attribute, Error, FnArg, ImplItem, ImplItemFn, ItemImpl, LitStr};
+
+#[derive(Clone, Copy)]
+enum Modifier {
+	View,
+	NonPayable,
+	Payable,
+}
+
+/// A function exposed by the precompile.
+struct Function {
+	ident: syn::Ident,
+	selectors: Vec<u32>,
+	modifier: Modifier,
+	arguments: Vec<syn::Type>,
+	fallback: bool,
+}
+
+fn is_precompile_attr(attr: &Attribute, name: &str) -> bool {
+	let segments: Vec<_> = attr.path().segments.iter().collect();
+	segments.len() == 2 && segments[0].ident == "precompile" && segments[1].ident == name
+}
+
+/// Number of top level parameters of a Solidity signature `name(type,..)`.
+fn signature_arity(signature: &LitStr) -> syn::Result<usize> {
+	let value = signature.value();
+	let invalid = || Error::new(signature.span(), "Expected a signature `name(type,..)`");
+	let start = value.find('(').ok_or_else(invalid)?;
+	if start == 0 || !value.ends_with(')') || value.contains(' ') {
+		return Err(invalid());
+	}
+
+	let parameters = &value[start + 1..value.len() - 1];
+	if parameters.is_empty() {
+		return Ok(0);
+	}
+
+	let mut depth = 0i32;
+	let mut arity = 1;
+	for c in parameters.chars() {
+		match c {
+			'(' => depth += 1,
+			')' => depth -= 1,
+			',' if depth == 0 => arity += 1,
+			_ => {}
+		}
+		if depth < 0 {
+			return Err(invalid());
+		}
+	}
+	Ok(arity)
+}
+
+fn parse_function(function: &mut ImplItemFn) -> syn::Result<Option<Function>> {
+	let mut signatures = Vec::new();
+	let mut view = false;
+	let mut payable = false;
+	let mut fallback = false;
+
+	let mut error = None;
+	function.attrs.retain(|attr| {
+		if is_precompile_attr(attr, "public") {
+			match attr.parse_args::<LitStr>() {
+				Ok(signature) => signatures.push(signature),
+				Err(err) => error = Some(err),
+			}
+		} else if is_precompile_attr(attr, "view") {
+			view = true;
+		} else if is_precompile_attr(attr, "payable") {
+			payable = true;
+		} else if is_precompile_attr(attr, "fallback") {
+			fallback = true;
+		} else {
+			return true;
+		}
+		false
+	});
+	if let Some(err) = error {
+		return Err(err);
+	}
+
+	if signatures.is_empty() && !fallback {
+		if view || payable {
+			return Err(Error::new(
+				function.sig.span(),
+				"Function must be `#[precompile::public(..)]` or `#[precompile::fallback]`",
+			));
+		}
+		return Ok(None);
+	}
+
+	let modifier = match (view, payable) {
+		(true, true) => {
+			return Err(Error::new(
+				function.sig.span(),
+				"Function can't be both view and payable",
+			))
+		}
+		(true, false) => Modifier::View,
+		(false, true) => Modifier::Payable,
+		(false, false) => Modifier::NonPayable,
+	};
+
+	let mut inputs = function.sig.inputs.iter();
+	match inputs.next() {
+		Some(FnArg::Typed(_)) => {}
+		_ => {
+			return Err(Error::new(
+				function.sig.span(),
+				"Function must be an associated function taking the precompile handle first",
+			))
+		}
+	}
+	let arguments = inputs
+		.map(|input| match input {
+			FnArg::Typed(typed) => Ok((*typed.ty).clone()),
+			FnArg::Receiver(receiver) => Err(Error::new(receiver.span(), "Unexpected receiver")),
+		})
+		.collect::<syn::Result<Vec<_>>>()?;
+
+	if fallback && !arguments.is_empty() {
+		return Err(Error::new(
+			function.sig.span(),
+			"Fallback must only take the precompile handle",
+		));
+	}
+
+	let mut selectors = Vec::new();
+	for signature in &signatures {
+		let arity = signature_arity(signature)?;
+		if arity != arguments.len() {
+			return Err(Error::new(
+				signature.span(),
+				format!(
+					"Signature has {} parameters but the function takes {} arguments",
+					arity,
+					arguments.len()
+				),
+			));
+		}
+		let hash = crate::keccak(signature.value().as_bytes());
+		selectors.push(u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]));
+	}
+
+	Ok(Some(Function {
+		ident: function.sig.ident.clone(),
+		selectors,
+		modifier,
+		arguments,
+		fallback,
+	}))
+}
+
+fn dispatch(function: &Function) -> TokenStream {
+	let ident = &function.ident;
+	let modifier = match function.modifier {
+		Modifier::View => quote!(View),
+		Modifier::NonPayable => quote!(NonPayable),
+		Modifier::Payable => quote!(Payable),
+	};
+	let check = quote! {
+		::precompile_utils::check_function_modifier(
+			handle,
+			::precompile_utils::FunctionModifier::#modifier,
+		)?;
+	};
+
+	if function.fallback {
+		return quote! {
+			#check
+			let output = Self::#ident(handle)?;
+			Ok(::precompile_utils::succeed(
+				::precompile_utils::solidity::encode_arguments(output),
+			))
+		};
+	}
+
+	let names: Vec<_> = (0..function.arguments.len())
+		.map(|i| format_ident!("arg{}", i))
+		.collect();
+	let types = &function.arguments;
+	quote! {
+		#check
+		let (#(#names,)*) = ::precompile_utils::solidity::decode_arguments::<(#(#types,)*)>(
+			&handle.input()[4..],
+		)?;
+		let output = Self::#ident(handle, #(#names),*)?;
+		Ok(::precompile_utils::succeed(
+			::precompile_utils::solidity::encode_arguments(output),
+		))
+	}
+}
+
+pub fn expand(mut item: ItemImpl) -> syn::Result<TokenStream> {
+	if let Some((_, path, _)) = &item.trait_ {
+		return Err(Error::new(
+			path.span(),
+			"`precompile` must be used on an inherent impl block",
+		));
+	}
+
+	let mut functions = Vec::new();
+	for impl_item in item.items.iter_mut() {
+		if let ImplItem::Fn(function) = impl_item {
+			if let Some(function) = parse_function(function)? {
+				functions.push(function);
+			}
+		}
+	}
+
+	let mut seen = BTreeMap::new();
+	for function in &functions {
+		for selector in &function.selectors {
+			if let Some(other) = seen.insert(*selector, &function.ident) {
+				return Err(Error::new(
+					function.ident.span(),
+					format!("Selector 0x{:08x} is already used by `{}`", selector, other),
+				));
+			}
+		}
+	}
+
+	let mut fallbacks = functions.iter().filter(|function| function.fallback);
+	let fallback = fallbacks.next();
+	if let Some(other) = fallbacks.next() {
+		return Err(Error::new(
+			other.ident.span(),
+			"Only one fallback function is allowed",
+		));
+	}
+
+	let arms = functions
+		.iter()
+		.filter(|function| !function.fallback)
+		.map(|function| {
+			let selectors = &function.selectors;
+			let body = dispatch(function);
+			quote! {
+				#(Some(#selectors))|* => { #body }
+			}
+		});
+	let default = match fallback {
+		Some(fallback) => dispatch(fallback),
+		None => quote! {
+			Err(::precompile_utils::solidity::revert(
+				if selector.is_some() { "Unknown selector" } else { "Tried to read selector out of bounds" },
+			))
+		},
+	};
+
+	let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+	let self_ty = &item.self_ty;
+
+	Ok(quote! {
+		#item
+
+		impl #impl_generics ::precompile_utils::fp_evm::Precompile for #self_ty #where_clause {
+			fn execute(
+				handle: &mut impl ::precompile_utils::fp_evm::PrecompileHandle,
+			) -> ::precompile_utils::fp_evm::PrecompileResult {
+				let selector = ::precompile_utils::solidity::selector(handle.input());
+				match selector {
+					#(#arms)*
+					_ => { #default }
+				}
+			}
+		}
+	})
+}
@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Utilities to write Solidity-facing precompiles.
+//!
+//! A precompile is declared as an `impl` block annotated with [`precompile`], whose functions
+//! are annotated with their Solidity signature. Arguments and return values are decoded and
+//! encoded with the [`solidity::Codec`] trait.
+//!
+//! ```ignore
+//! pub struct Storage;
+//!
+//! #[precompile_utils::precompile]
+//! impl Storage {
+//!     #[precompile::public("get(uint256)")]
+//!     #[precompile::view]
+//!     fn get(handle: &mut impl PrecompileHandle, key: U256) -> EvmResult<U256> {
+//!         ...
+//!     }
+//!
+//!     #[precompile::public("set(uint256,uint256)")]
+//!     fn set(handle: &mut impl PrecompileHandle, key: U256, value: U256) -> EvmResult {
+//!         ...
+//!     }
+//! }
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(unused_crate_dependencies)]
+
+extern crate alloc;
+// Allows the macros to refer to `::precompile_utils` from within this crate.
+extern crate self as precompile_utils;
+
+pub mod logs;
+pub mod solidity;
+#[cfg(test)]
+mod tests;
+
+use alloc::vec::Vec;
+
+// Substrate
+use sp_core::U256;
+// Frontier
+pub use fp_evm;
+use fp_evm::{ExitSucceed, PrecompileFailure, PrecompileHandle, PrecompileOutput};
+pub use precompile_utils_macro::{keccak256, precompile};
+
+use self::solidity::revert;
+
+/// Result of a precompile function.
+pub type EvmResult<T = ()> = Result<T, PrecompileFailure>;
+
+/// Successful precompile output.
+pub fn succeed(output: Vec<u8>) -> PrecompileOutput {
+	PrecompileOutput {
+		exit_status: ExitSucceed::Returned,
+		output,
+	}
+}
+
+/// Solidity state mutability of a precompile function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FunctionModifier {
+	/// Doesn't modify state and doesn't accept value.
+	View,
+	/// Can modify state but doesn't accept value.
+	NonPayable,
+	/// Can modify state and accept value.
+	Payable,
+}
+
+/// Ensure the call context of `handle` is allowed for a function with `modifier`.
+pub fn check_function_modifier(
+	handle: &impl PrecompileHandle,
+	modifier: FunctionModifier,
+) -> EvmResult {
+	if modifier != FunctionModifier::Payable && !handle.context().apparent_value.is_zero() {
+		return Err(revert("Function is not payable"));
+	}
+
+	if modifier != FunctionModifier::View && handle.is_static() {
+		return Err(revert("Can't call non-static function in static context"));
+	}
+
+	Ok(())
+}
+
+/// Largest gas limit a subcall transferring `value` can be given out of `remaining_gas`.
+///
+/// The EVM first charges the cost of the call, the target being assumed cold and, when receiving
+/// value, empty. Since EIP-150 it then forwards at most 63/64 of the gas left.
+pub fn forwardable_gas(remaining_gas: u64, value: U256, config: &fp_evm::Config) -> u64 {
+	// `G_callvalue` and `G_newaccount` of the Yellow Paper.
+	const CALL_VALUE_COST: u64 = 9_000;
+	const NEW_ACCOUNT_COST: u64 = 25_000;
+
+	let mut call_cost = if config.increase_state_access_gas {
+		config.gas_account_access_cold
+	} else {
+		config.gas_call
+	};
+	if !value.is_zero() {
+		call_cost = call_cost.saturating_add(CALL_VALUE_COST);
+	}
+	// Before EIP-161, calling an empty account costs `G_newaccount` even without value.
+	if !value.is_zero() || config.empty_considered_exists {
+		call_cost = call_cost.saturating_add(NEW_ACCOUNT_COST);
+	}
+
+	let available = remaining_gas.saturating_sub(call_cost);
+	if config.call_l64_after_gas {
+		available - available / 64
+	} else {
+		available
+	}
+}
+
+pub mod prelude {
+	pub use crate::{
+		check_function_modifier, forwardable_gas, keccak256,
+		logs::emit_log,
+		precompile,
+		solidity::{
+			decode_arguments, encode_arguments, encode_with_selector, revert, BoundedBytes,
+			BoundedVec, Bytes, Codec, Reader, Writer,
+		},
+		succeed, EvmResult, FunctionModifier,
+	};
+	pub use fp_evm::{PrecompileHandle, PrecompileOutput, PrecompileResult};
+	pub use sp_core::{H160, H256, U256};
+}
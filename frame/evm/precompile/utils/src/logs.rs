@@ -0,0 +1,46 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::vec::Vec;
+
+use fp_evm::PrecompileHandle;
+use sp_core::{H160, H256};
+
+use crate::EvmResult;
+
+const G_LOG: u64 = 375;
+const G_LOGTOPIC: u64 = 375;
+const G_LOGDATA: u64 = 8;
+
+/// Gas cost of a `LOG` opcode with `topics` topics and `data_len` bytes of data.
+pub fn log_cost(topics: usize, data_len: usize) -> u64 {
+	G_LOG
+		.saturating_add(G_LOGTOPIC.saturating_mul(topics as u64))
+		.saturating_add(G_LOGDATA.saturating_mul(data_len as u64))
+}
+
+/// Charge the cost of a log like the `LOG` opcode would, then emit it.
+pub fn emit_log(
+	handle: &mut impl PrecompileHandle,
+	address: H160,
+	topics: Vec<H256>,
+	data: Vec<u8>,
+) -> EvmResult {
+	handle.record_cost(log_cost(topics.len(), data.len()))?;
+	handle.log(address, topics, data)?;
+	Ok(())
+}
@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Arrays and bytes whose length is bounded when decoded.
+//!
+//! Pointers of an input can all point to the same data, so decoding a `Vec<Bytes>` can allocate
+//! much more memory than the input holds. Bounding the length of the arrays and bytes of an
+//! argument bounds the memory used to decode it.
+
+use alloc::{format, string::String, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+use sp_core::Get;
+
+use super::{
+	bytes::{read_bytes, write_bytes},
+	native::{read_array, write_array},
+	Codec, Reader, Writer,
+};
+use crate::EvmResult;
+
+/// Solidity `bytes` of at most `S` bytes, reverting when decoding longer data.
+pub struct BoundedBytes<S> {
+	inner: Vec<u8>,
+	_phantom: PhantomData<S>,
+}
+
+impl<S> BoundedBytes<S> {
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.inner
+	}
+}
+
+impl<S> Clone for BoundedBytes<S> {
+	fn clone(&self) -> Self {
+		self.inner.clone().into()
+	}
+}
+
+impl<S> fmt::Debug for BoundedBytes<S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("BoundedBytes").field(&self.inner).finish()
+	}
+}
+
+impl<S> PartialEq for BoundedBytes<S> {
+	fn eq(&self, other: &Self) -> bool {
+		self.inner == other.inner
+	}
+}
+
+impl<S> Eq for BoundedBytes<S> {}
+
+impl<S> From<Vec<u8>> for BoundedBytes<S> {
+	fn from(inner: Vec<u8>) -> Self {
+		Self {
+			inner,
+			_phantom: PhantomData,
+		}
+	}
+}
+
+impl<S> From<&[u8]> for BoundedBytes<S> {
+	fn from(bytes: &[u8]) -> Self {
+		bytes.to_vec().into()
+	}
+}
+
+impl<S> From<BoundedBytes<S>> for Vec<u8> {
+	fn from(bytes: BoundedBytes<S>) -> Self {
+		bytes.inner
+	}
+}
+
+impl<S: Get<u32>> Codec for BoundedBytes<S> {
+	fn read(reader: &mut Reader) -> EvmResult<Self> {
+		Ok(read_bytes(reader, S::get() as usize)?.into())
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		write_bytes(writer, &value.inner)
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+
+	fn signature() -> String {
+		String::from("bytes")
+	}
+}
+
+/// Solidity array of at most `S` items, reverting when decoding a longer array.
+pub struct BoundedVec<T, S> {
+	inner: Vec<T>,
+	_phantom: PhantomData<S>,
+}
+
+impl<T, S> BoundedVec<T, S> {
+	pub fn as_slice(&self) -> &[T] {
+		&self.inner
+	}
+}
+
+impl<T: Clone, S> Clone for BoundedVec<T, S> {
+	fn clone(&self) -> Self {
+		self.inner.clone().into()
+	}
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for BoundedVec<T, S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("BoundedVec").field(&self.inner).finish()
+	}
+}
+
+impl<T: PartialEq, S> PartialEq for BoundedVec<T, S> {
+	fn eq(&self, other: &Self) -> bool {
+		self.inner == other.inner
+	}
+}
+
+impl<T: Eq, S> Eq for BoundedVec<T, S> {}
+
+impl<T, S> From<Vec<T>> for BoundedVec<T, S> {
+	fn from(inner: Vec<T>) -> Self {
+		Self {
+			inner,
+			_phantom: PhantomData,
+		}
+	}
+}
+
+impl<T, S> From<BoundedVec<T, S>> for Vec<T> {
+	fn from(array: BoundedVec<T, S>) -> Self {
+		array.inner
+	}
+}
+
+impl<T: Codec, S: Get<u32>> Codec for BoundedVec<T, S> {
+	fn read(reader: &mut Reader) -> EvmResult<Self> {
+		Ok(read_array(reader, S::get() as usize)?.into())
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		write_array(writer, value.inner)
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+
+	fn signature() -> String {
+		format!("{}[]", T::signature())
+	}
+}
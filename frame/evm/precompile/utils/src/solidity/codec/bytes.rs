@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::{string::String, vec::Vec};
+
+use sp_core::U256;
+
+use super::{Codec, Reader, Writer};
+use crate::{solidity::revert, EvmResult};
+
+/// Solidity `bytes`. `Vec<u8>` is encoded as `uint8[]`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.0
+	}
+}
+
+impl From<Vec<u8>> for Bytes {
+	fn from(bytes: Vec<u8>) -> Self {
+		Self(bytes)
+	}
+}
+
+impl From<&[u8]> for Bytes {
+	fn from(bytes: &[u8]) -> Self {
+		Self(bytes.to_vec())
+	}
+}
+
+impl From<&str> for Bytes {
+	fn from(string: &str) -> Self {
+		Self(string.as_bytes().to_vec())
+	}
+}
+
+impl From<Bytes> for Vec<u8> {
+	fn from(bytes: Bytes) -> Self {
+		bytes.0
+	}
+}
+
+/// Read `bytes` or `string` data of at most `max_len` bytes.
+pub(super) fn read_bytes<'a>(reader: &mut Reader<'a>, max_len: usize) -> EvmResult<&'a [u8]> {
+	let mut data = reader.read_pointer()?;
+	let len = data.read_usize()?;
+	if len > max_len {
+		return Err(revert("Bytes length is larger than the limit"));
+	}
+	data.read_raw_bytes(len)
+}
+
+pub(super) fn write_bytes(writer: &mut Writer, bytes: &[u8]) {
+	let padded_len = (bytes.len() + 31) / 32 * 32;
+	let mut data = alloc::vec![0u8; 32 + padded_len];
+	U256::from(bytes.len()).to_big_endian(&mut data[..32]);
+	data[32..32 + bytes.len()].copy_from_slice(bytes);
+	writer.write_pointer(data);
+}
+
+impl Codec for Bytes {
+	fn read(reader: &mut Reader) -> EvmResult<Self> {
+		Ok(Self(read_bytes(reader, usize::MAX)?.to_vec()))
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		write_bytes(writer, &value.0)
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+
+	fn signature() -> String {
+		String::from("bytes")
+	}
+}
+
+impl Codec for String {
+	fn read(reader: &mut Reader) -> EvmResult<Self> {
+		let bytes = read_bytes(reader, usize::MAX)?;
+		String::from_utf8(bytes.to_vec()).map_err(|_| revert("Value is not a valid UTF-8 string"))
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		write_bytes(writer, value.as_bytes())
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+
+	fn signature() -> String {
+		String::from("string")
+	}
+}
@@ -0,0 +1,216 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Solidity ABI codec.
+//!
+//! Values are laid out in a head of 32 bytes words, where dynamically sized values are replaced
+//! by a pointer to their data in the tail. Pointers are offsets relative to the start of the
+//! enclosing tuple or array.
+
+mod bounded;
+mod bytes;
+mod native;
+
+use alloc::{string::String, vec::Vec};
+
+use sp_core::U256;
+
+pub use self::{
+	bounded::{BoundedBytes, BoundedVec},
+	bytes::Bytes,
+};
+use super::revert;
+use crate::EvmResult;
+
+/// A type that can be read from and written in Solidity ABI format.
+pub trait Codec: Sized {
+	/// Read a value at the current position of `reader`.
+	fn read(reader: &mut Reader) -> EvmResult<Self>;
+
+	/// Write `value` at the current position of `writer`.
+	fn write(writer: &mut Writer, value: Self);
+
+	/// Whether the type is encoded in place, rather than behind a pointer.
+	fn has_static_size() -> bool;
+
+	/// Solidity type of the value, as used in function signatures.
+	fn signature() -> String;
+
+	/// Read `Self` as the list of arguments of a call. Tuples are read as one argument per
+	/// member, other types as a single argument.
+	fn read_arguments(reader: &mut Reader) -> EvmResult<Self> {
+		Self::read(reader)
+	}
+
+	/// Write `value` as the list of arguments of a call, see [`Codec::read_arguments`].
+	fn write_arguments(writer: &mut Writer, value: Self) {
+		Self::write(writer, value)
+	}
+}
+
+/// Reads values from an ABI encoded input.
+#[derive(Clone, Copy, Debug)]
+pub struct Reader<'a> {
+	input: &'a [u8],
+	cursor: usize,
+}
+
+impl<'a> Reader<'a> {
+	/// Reader positioned at the start of `input`.
+	pub fn new(input: &'a [u8]) -> Self {
+		Self { input, cursor: 0 }
+	}
+
+	/// Read a value of type `T`.
+	pub fn read<T: Codec>(&mut self) -> EvmResult<T> {
+		T::read(self)
+	}
+
+	/// Number of bytes left after the cursor.
+	pub fn remaining_len(&self) -> usize {
+		self.input.len().saturating_sub(self.cursor)
+	}
+
+	/// Read `len` raw bytes.
+	pub fn read_raw_bytes(&mut self, len: usize) -> EvmResult<&'a [u8]> {
+		let end = self
+			.cursor
+			.checked_add(len)
+			.ok_or_else(|| revert("Data is too large"))?;
+		let data = self
+			.input
+			.get(self.cursor..end)
+			.ok_or_else(|| revert("Tried to read out of bounds"))?;
+		self.cursor = end;
+		Ok(data)
+	}
+
+	/// Read a 32 bytes word.
+	pub fn read_word(&mut self) -> EvmResult<&'a [u8; 32]> {
+		let word = self.read_raw_bytes(32)?;
+		Ok(word.try_into().expect("32 bytes were read; qed"))
+	}
+
+	/// Read a length or an offset.
+	pub fn read_usize(&mut self) -> EvmResult<usize> {
+		let value = U256::from_big_endian(self.read_word()?);
+		if value > U256::from(u32::MAX) {
+			return Err(revert("Value is too large for a length or an offset"));
+		}
+		Ok(value.low_u32() as usize)
+	}
+
+	/// Read a pointer, returning a reader positioned at the data it points to.
+	pub fn read_pointer(&mut self) -> EvmResult<Reader<'a>> {
+		let offset = self.read_usize()?;
+		let input = self
+			.input
+			.get(offset..)
+			.ok_or_else(|| revert("Pointer points out of bounds"))?;
+		Ok(Reader::new(input))
+	}
+
+	/// Reader whose start is the current position of `self`.
+	fn scoped(&self) -> Reader<'a> {
+		Reader::new(&self.input[self.cursor.min(self.input.len())..])
+	}
+}
+
+/// Writes values in ABI format.
+#[derive(Clone, Debug, Default)]
+pub struct Writer {
+	head: Vec<u8>,
+	/// Data of dynamically sized values, with the position of their pointer in `head`.
+	tail: Vec<(usize, Vec<u8>)>,
+	selector: Option<u32>,
+}
+
+impl Writer {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Writer prefixing the output with a function selector.
+	pub fn new_with_selector(selector: impl Into<u32>) -> Self {
+		Self {
+			selector: Some(selector.into()),
+			..Self::default()
+		}
+	}
+
+	/// Write a value of type `T`.
+	pub fn write<T: Codec>(mut self, value: T) -> Self {
+		T::write(&mut self, value);
+		self
+	}
+
+	/// Write a 32 bytes word.
+	pub fn write_word(&mut self, word: [u8; 32]) {
+		self.head.extend_from_slice(&word);
+	}
+
+	/// Write a pointer to `data`, which is appended to the tail.
+	pub fn write_pointer(&mut self, data: Vec<u8>) {
+		self.tail.push((self.head.len(), data));
+		self.head.extend_from_slice(&[0u8; 32]);
+	}
+
+	/// Encoded output.
+	pub fn build(self) -> Vec<u8> {
+		let mut output = self.head;
+		for (position, data) in self.tail {
+			let offset = U256::from(output.len());
+			offset.to_big_endian(&mut output[position..position + 32]);
+			output.extend(data);
+		}
+
+		match self.selector {
+			Some(selector) => {
+				let mut with_selector = selector.to_be_bytes().to_vec();
+				with_selector.extend(output);
+				with_selector
+			}
+			None => output,
+		}
+	}
+}
+
+/// Function selector of a call `input`, if long enough.
+pub fn selector(input: &[u8]) -> Option<u32> {
+	input
+		.get(0..4)
+		.map(|selector| u32::from_be_bytes(selector.try_into().expect("4 bytes; qed")))
+}
+
+/// Decode the arguments of a call, `input` excluding the selector.
+pub fn decode_arguments<T: Codec>(input: &[u8]) -> EvmResult<T> {
+	T::read_arguments(&mut Reader::new(input))
+}
+
+/// Encode `value` as a list of arguments or return values.
+pub fn encode_arguments<T: Codec>(value: T) -> Vec<u8> {
+	let mut writer = Writer::new();
+	T::write_arguments(&mut writer, value);
+	writer.build()
+}
+
+/// Encode a call to `selector` with `arguments`.
+pub fn encode_with_selector<T: Codec>(selector: u32, arguments: T) -> Vec<u8> {
+	let mut writer = Writer::new_with_selector(selector);
+	T::write_arguments(&mut writer, arguments);
+	writer.build()
+}
@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::{format, string::String, vec::Vec};
+use core::mem::size_of;
+
+use sp_core::{H160, H256, U256};
+
+use super::{Codec, Reader, Writer};
+use crate::{solidity::revert, EvmResult};
+
+macro_rules! impl_codec_for_uint {
+	($($ty:ty),*) => {$(
+		impl Codec for $ty {
+			fn read(reader: &mut Reader) -> EvmResult<Self> {
+				let value = U256::from_big_endian(reader.read_word()?);
+				if value > U256::from(<$ty>::MAX) {
+					return Err(revert(concat!("Value is too large for ", stringify!($ty))));
+				}
+				Ok(value.low_u128() as $ty)
+			}
+
+			fn write(writer: &mut Writer, value: Self) {
+				let mut word = [0u8; 32];
+				U256::from(value).to_big_endian(&mut word);
+				writer.write_word(word);
+			}
+
+			fn has_static_size() -> bool {
+				true
+			}
+
+			fn signature() -> String {
+				format!("uint{}", size_of::<$ty>() * 8)
+			}
+		}
+	)*};
+}
+
+impl_codec_for_uint!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_codec_for_int {
+	($($ty:ty),*) => {$(
+		impl Codec for $ty {
+			fn read(reader: &mut Reader) -> EvmResult<Self> {
+				let word = reader.read_word()?;
+				let (padding, value) = word.split_at(32 - size_of::<$ty>());
+				// Values are sign extended to 32 bytes.
+				let fill = if value[0] & 0x80 == 0 { 0x00 } else { 0xff };
+				if padding.iter().any(|byte| *byte != fill) {
+					return Err(revert(concat!("Value is out of range for ", stringify!($ty))));
+				}
+				Ok(<$ty>::from_be_bytes(value.try_into().expect("size of the type; qed")))
+			}
+
+			fn write(writer: &mut Writer, value: Self) {
+				let mut word = if value < 0 { [0xff; 32] } else { [0x00; 32] };
+				word[32 - size_of::<$ty>()..].copy_from_slice(&value.to_be_bytes());
+				writer.write_word(word);
+			}
+
+			fn has_static_size() -> bool {
+				true
+			}
+
+			fn signature() -> String {
+				format!("int{}", size_of::<$ty>() * 8)
+			}
+		}
+	)*};
+}
+
+impl_codec_for_int!(i8, i16, i32, i64, i128);
+
+impl Codec for U256 {
+	fn read(reader: &mut Reader) -> EvmResult<Self> {
+		Ok(U256::from_big_endian(reader.read_word()?))
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		let mut word = [0u8; 32];
+		value.to_big_endian(&mut word);
+		writer.write_word(word);
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		String::from("uint256")
+	}
+}
+
+impl Codec for bool {
+	fn read(reader: &mut Reader) -> EvmResult<Self> {
+		let word = reader.read_word()?;
+		match (word[..31].iter().all(|byte| *byte == 0), word[31]) {
+			(true, 0) => Ok(false),
+			(true, 1) => Ok(true),
+			_ => Err(revert("Value is not a bool")),
+		}
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		let mut word = [0u8; 32];
+		word[31] = value as u8;
+		writer.write_word(word);
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		String::from("bool")
+	}
+}
+
+impl Codec for H160 {
+	fn read(reader: &mut Reader) -> EvmResult<Self> {
+		let word = reader.read_word()?;
+		if word[..12].iter().any(|byte| *byte != 0) {
+			return Err(revert("Value is not an address"));
+		}
+		Ok(H160::from_slice(&word[12..]))
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		let mut word = [0u8; 32];
+		word[12..].copy_from_slice(value.as_bytes());
+		writer.write_word(word);
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		String::from("address")
+	}
+}
+
+impl Codec for H256 {
+	fn read(reader: &mut Reader) -> EvmResult<Self> {
+		Ok(H256(*reader.read_word()?))
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		writer.write_word(value.0);
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		String::from("bytes32")
+	}
+}
+
+/// Read an array of at most `max_len` items.
+pub(super) fn read_array<T: Codec>(reader: &mut Reader, max_len: usize) -> EvmResult<Vec<T>> {
+	let mut array = reader.read_pointer()?;
+	let len = array.read_usize()?;
+	if len > max_len {
+		return Err(revert("Array length is larger than the limit"));
+	}
+	// Every item takes at least one word, don't allocate for more than the input holds.
+	if len > array.remaining_len() / 32 {
+		return Err(revert("Array length is larger than the input"));
+	}
+
+	let mut items = array.scoped();
+	(0..len).map(|_| items.read()).collect()
+}
+
+pub(super) fn write_array<T: Codec>(writer: &mut Writer, value: Vec<T>) {
+	let mut data = alloc::vec![0u8; 32];
+	U256::from(value.len()).to_big_endian(&mut data);
+
+	let mut items = Writer::new();
+	for item in value {
+		T::write(&mut items, item);
+	}
+	data.extend(items.build());
+	writer.write_pointer(data);
+}
+
+impl<T: Codec> Codec for Vec<T> {
+	fn read(reader: &mut Reader) -> EvmResult<Self> {
+		read_array(reader, usize::MAX)
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		write_array(writer, value)
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+
+	fn signature() -> String {
+		format!("{}[]", T::signature())
+	}
+}
+
+impl Codec for () {
+	fn read(_reader: &mut Reader) -> EvmResult<Self> {
+		Ok(())
+	}
+
+	fn write(_writer: &mut Writer, _value: Self) {}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		String::from("()")
+	}
+}
+
+macro_rules! impl_codec_for_tuple {
+	($($ty:ident),+) => {
+		#[allow(non_snake_case)]
+		impl<$($ty: Codec),+> Codec for ($($ty,)+) {
+			fn read(reader: &mut Reader) -> EvmResult<Self> {
+				if Self::has_static_size() {
+					Self::read_arguments(reader)
+				} else {
+					Self::read_arguments(&mut reader.read_pointer()?)
+				}
+			}
+
+			fn write(writer: &mut Writer, value: Self) {
+				if Self::has_static_size() {
+					Self::write_arguments(writer, value)
+				} else {
+					let mut inner = Writer::new();
+					Self::write_arguments(&mut inner, value);
+					writer.write_pointer(inner.build());
+				}
+			}
+
+			fn has_static_size() -> bool {
+				$($ty::has_static_size())&&+
+			}
+
+			fn signature() -> String {
+				let members: Vec<String> = alloc::vec![$($ty::signature()),+];
+				format!("({})", members.join(","))
+			}
+
+			fn read_arguments(reader: &mut Reader) -> EvmResult<Self> {
+				Ok(($($ty::read(reader)?,)+))
+			}
+
+			fn write_arguments(writer: &mut Writer, value: Self) {
+				let ($($ty,)+) = value;
+				$($ty::write(writer, $ty);)+
+			}
+		}
+	};
+}
+
+impl_codec_for_tuple!(A);
+impl_codec_for_tuple!(A, B);
+impl_codec_for_tuple!(A, B, C);
+impl_codec_for_tuple!(A, B, C, D);
+impl_codec_for_tuple!(A, B, C, D, E);
+impl_codec_for_tuple!(A, B, C, D, E, F);
+impl_codec_for_tuple!(A, B, C, D, E, F, G);
+impl_codec_for_tuple!(A, B, C, D, E, F, G, H);
+impl_codec_for_tuple!(A, B, C, D, E, F, G, H, I);
+impl_codec_for_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_codec_for_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_codec_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Solidity ABI encoding and decoding.
+
+pub mod codec;
+pub mod revert;
+
+pub use self::{
+	codec::{
+		decode_arguments, encode_arguments, encode_with_selector, selector, BoundedBytes,
+		BoundedVec, Bytes, Codec, Reader, Writer,
+	},
+	revert::revert,
+};
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reverts with a Solidity `Error(string)` reason.
+
+use alloc::vec::Vec;
+
+use fp_evm::{ExitRevert, PrecompileFailure};
+
+use super::codec::{Bytes, Writer};
+
+/// Selector of Solidity's `Error(string)`.
+pub const ERROR_SELECTOR: u32 = 0x08c379a0;
+
+/// Revert with `message` as reason, like Solidity's `revert(message)`.
+pub fn revert(message: impl AsRef<[u8]>) -> PrecompileFailure {
+	PrecompileFailure::Revert {
+		exit_status: ExitRevert::Reverted,
+		output: encode_revert_reason(message),
+	}
+}
+
+/// ABI encoding of an `Error(string)` with `message` as reason.
+pub fn encode_revert_reason(message: impl AsRef<[u8]>) -> Vec<u8> {
+	Writer::new_with_selector(ERROR_SELECTOR)
+		.write(Bytes::from(message.as_ref()))
+		.build()
+}
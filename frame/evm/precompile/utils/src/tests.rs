@@ -0,0 +1,346 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use hex_literal::hex;
+use sp_core::ConstU32;
+
+use fp_evm::{Context, ExitRevert, Precompile, PrecompileFailure};
+use pallet_evm_test_vector_support::MockHandle;
+
+use crate::{
+	prelude::*,
+	solidity::{codec::selector, revert::encode_revert_reason},
+};
+
+fn assert_round_trip<T: Codec + Clone + PartialEq + core::fmt::Debug>(value: T) {
+	let encoded = encode_arguments((value.clone(),));
+	assert_eq!(decode_arguments::<(T,)>(&encoded).unwrap(), (value,));
+}
+
+fn assert_reverted_with<T: core::fmt::Debug>(result: EvmResult<T>, message: &str) {
+	match result {
+		Err(PrecompileFailure::Revert { output, .. }) => {
+			assert_eq!(output, encode_revert_reason(message))
+		}
+		other => panic!("unexpected result: {:?}", other),
+	}
+}
+
+#[test]
+fn static_values_are_encoded_in_place() {
+	let encoded = encode_arguments((
+		U256::from(1),
+		H160::repeat_byte(0x11),
+		true,
+		-1i32,
+		H256::repeat_byte(0x22),
+	));
+	assert_eq!(
+		encoded,
+		[
+			hex!("0000000000000000000000000000000000000000000000000000000000000001"),
+			hex!("0000000000000000000000001111111111111111111111111111111111111111"),
+			hex!("0000000000000000000000000000000000000000000000000000000000000001"),
+			hex!("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"),
+			hex!("2222222222222222222222222222222222222222222222222222222222222222"),
+		]
+		.concat()
+	);
+}
+
+#[test]
+fn dynamic_values_are_encoded_behind_pointers() {
+	// Example from the Solidity ABI specification:
+	// `f(uint256,uint32[],bytes10,bytes)` with `(0x123, [0x456, 0x789], "1234567890",
+	// "Hello, world!")`, with `bytes10` replaced by `uint256`.
+	let encoded = encode_with_selector(
+		0x8be65246,
+		(
+			U256::from(0x123),
+			vec![0x456u32, 0x789],
+			U256::from(0x31),
+			Bytes::from("Hello, world!"),
+		),
+	);
+	assert_eq!(
+		encoded,
+		[
+			&hex!("8be65246")[..],
+			&hex!("0000000000000000000000000000000000000000000000000000000000000123"),
+			&hex!("0000000000000000000000000000000000000000000000000000000000000080"),
+			&hex!("0000000000000000000000000000000000000000000000000000000000000031"),
+			&hex!("00000000000000000000000000000000000000000000000000000000000000e0"),
+			&hex!("0000000000000000000000000000000000000000000000000000000000000002"),
+			&hex!("0000000000000000000000000000000000000000000000000000000000000456"),
+			&hex!("0000000000000000000000000000000000000000000000000000000000000789"),
+			&hex!("000000000000000000000000000000000000000000000000000000000000000d"),
+			&hex!("48656c6c6f2c20776f726c642100000000000000000000000000000000000000"),
+		]
+		.concat()
+	);
+
+	let decoded: (U256, Vec<u32>, U256, Bytes) = decode_arguments(&encoded[4..]).unwrap();
+	assert_eq!(decoded.1, vec![0x456, 0x789]);
+	assert_eq!(decoded.3, Bytes::from("Hello, world!"));
+}
+
+#[test]
+fn nested_values_round_trip() {
+	assert_round_trip(u8::MAX);
+	assert_round_trip(i128::MIN);
+	assert_round_trip(String::from("Frontier"));
+	assert_round_trip(vec![Bytes::from("a"), Bytes::from(&[0u8; 40][..])]);
+	assert_round_trip(vec![(H160::repeat_byte(1), U256::MAX)]);
+	assert_round_trip((U256::one(), String::from("dynamic tuple")));
+	assert_round_trip(vec![vec![1u64, 2], vec![], vec![3]]);
+}
+
+#[test]
+fn signatures_are_solidity_types() {
+	assert_eq!(<(H160, U256)>::signature(), "(address,uint256)");
+	assert_eq!(
+		Vec::<(u8, Bytes, String)>::signature(),
+		"(uint8,bytes,string)[]"
+	);
+	assert_eq!(i64::signature(), "int64");
+	assert_eq!(H256::signature(), "bytes32");
+}
+
+#[test]
+fn invalid_values_revert() {
+	let word = |byte: u8| {
+		let mut word = [0u8; 32];
+		word[31] = byte;
+		word[0] = byte;
+		word
+	};
+
+	assert_reverted_with(
+		decode_arguments::<u8>(&word(1)),
+		"Value is too large for u8",
+	);
+	assert_reverted_with(decode_arguments::<bool>(&word(1)), "Value is not a bool");
+	assert_reverted_with(
+		decode_arguments::<H160>(&word(1)),
+		"Value is not an address",
+	);
+	assert_reverted_with(
+		decode_arguments::<i8>(&word(0x80)),
+		"Value is out of range for i8",
+	);
+	assert_reverted_with(
+		decode_arguments::<U256>(&[0u8; 31]),
+		"Tried to read out of bounds",
+	);
+	// Pointer larger than any input.
+	assert_reverted_with(
+		decode_arguments::<Bytes>(&word(0x40)[..]),
+		"Value is too large for a length or an offset",
+	);
+}
+
+#[test]
+fn oversized_array_length_reverts() {
+	let input = [
+		hex!("0000000000000000000000000000000000000000000000000000000000000020"),
+		hex!("00000000000000000000000000000000000000000000000000000000ffffffff"),
+	]
+	.concat();
+	assert_reverted_with(
+		decode_arguments::<Vec<U256>>(&input),
+		"Array length is larger than the input",
+	);
+}
+
+#[test]
+fn bounded_values_revert_above_their_limit() {
+	type CallData = BoundedVec<BoundedBytes<ConstU32<4>>, ConstU32<2>>;
+
+	assert_round_trip(CallData::from(vec![
+		BoundedBytes::from(&b"abcd"[..]),
+		BoundedBytes::from(&b""[..]),
+	]));
+	assert_eq!(CallData::signature(), "bytes[]");
+
+	let input = encode_arguments((vec![Bytes::from("a"), Bytes::from("b"), Bytes::from("c")],));
+	assert_reverted_with(
+		decode_arguments::<(CallData,)>(&input),
+		"Array length is larger than the limit",
+	);
+
+	let input = encode_arguments((vec![Bytes::from("abcde")],));
+	assert_reverted_with(
+		decode_arguments::<(CallData,)>(&input),
+		"Bytes length is larger than the limit",
+	);
+}
+
+#[test]
+fn keccak256_macro() {
+	assert_eq!(
+		keccak256!("Transfer(address,address,uint256)"),
+		hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
+	);
+}
+
+struct Calculator;
+
+#[precompile]
+impl Calculator {
+	#[precompile::public("add(uint256,uint256)")]
+	#[precompile::public("plus(uint256,uint256)")]
+	#[precompile::view]
+	fn add(_handle: &mut impl PrecompileHandle, a: U256, b: U256) -> EvmResult<U256> {
+		a.checked_add(b).ok_or_else(|| revert("Overflow"))
+	}
+
+	#[precompile::public("concat(string,string)")]
+	#[precompile::view]
+	fn concat(
+		_handle: &mut impl PrecompileHandle,
+		a: String,
+		b: String,
+	) -> EvmResult<(String, u32)> {
+		let output = a + &b;
+		let len = output.len() as u32;
+		Ok((output, len))
+	}
+
+	#[precompile::public("deposit()")]
+	#[precompile::payable]
+	fn deposit(handle: &mut impl PrecompileHandle) -> EvmResult<U256> {
+		Ok(handle.context().apparent_value)
+	}
+
+	#[precompile::public("reset()")]
+	fn reset(_handle: &mut impl PrecompileHandle) -> EvmResult {
+		Ok(())
+	}
+}
+
+fn call(input: Vec<u8>, value: U256, is_static: bool) -> PrecompileResult {
+	let context = Context {
+		address: H160::repeat_byte(0xcc),
+		caller: H160::repeat_byte(0xaa),
+		apparent_value: value,
+	};
+	let mut handle = MockHandle::new(input, None, context);
+	handle.is_static = is_static;
+	Calculator::execute(&mut handle)
+}
+
+fn output(result: PrecompileResult) -> Vec<u8> {
+	result.expect("call succeeds").output
+}
+
+fn revert_reason(result: PrecompileResult) -> Vec<u8> {
+	match result {
+		Err(PrecompileFailure::Revert {
+			exit_status: ExitRevert::Reverted,
+			output,
+		}) => output,
+		other => panic!("unexpected result: {:?}", other),
+	}
+}
+
+#[test]
+fn precompile_dispatches_by_selector() {
+	let add = selector(&keccak256!("add(uint256,uint256)")).unwrap();
+	let plus = selector(&keccak256!("plus(uint256,uint256)")).unwrap();
+	for selector in [add, plus] {
+		let input = encode_with_selector(selector, (U256::from(2), U256::from(3)));
+		assert_eq!(
+			output(call(input, U256::zero(), true)),
+			encode_arguments(U256::from(5))
+		);
+	}
+
+	let concat = selector(&keccak256!("concat(string,string)")).unwrap();
+	let input = encode_with_selector(concat, (String::from("foo"), String::from("bar")));
+	assert_eq!(
+		output(call(input, U256::zero(), false)),
+		encode_arguments((String::from("foobar"), 6u32))
+	);
+
+	let reset = selector(&keccak256!("reset()")).unwrap();
+	assert!(output(call(reset.to_be_bytes().to_vec(), U256::zero(), false)).is_empty());
+}
+
+#[test]
+fn precompile_checks_modifiers() {
+	let deposit = selector(&keccak256!("deposit()")).unwrap();
+	let reset = selector(&keccak256!("reset()")).unwrap();
+
+	assert_eq!(
+		output(call(deposit.to_be_bytes().to_vec(), U256::from(7), false)),
+		encode_arguments(U256::from(7))
+	);
+	assert_eq!(
+		revert_reason(call(reset.to_be_bytes().to_vec(), U256::from(7), false)),
+		encode_revert_reason("Function is not payable")
+	);
+	assert_eq!(
+		revert_reason(call(reset.to_be_bytes().to_vec(), U256::zero(), true)),
+		encode_revert_reason("Can't call non-static function in static context")
+	);
+}
+
+#[test]
+fn precompile_reverts_on_invalid_input() {
+	assert_eq!(
+		revert_reason(call(vec![0x12, 0x34], U256::zero(), false)),
+		encode_revert_reason("Tried to read selector out of bounds")
+	);
+	assert_eq!(
+		revert_reason(call(vec![0x12, 0x34, 0x56, 0x78], U256::zero(), false)),
+		encode_revert_reason("Unknown selector")
+	);
+
+	let add = selector(&keccak256!("add(uint256,uint256)")).unwrap();
+	assert_eq!(
+		revert_reason(call(
+			encode_with_selector(add, (U256::MAX, U256::one())),
+			U256::zero(),
+			false
+		)),
+		encode_revert_reason("Overflow")
+	);
+	assert_eq!(
+		revert_reason(call(add.to_be_bytes().to_vec(), U256::zero(), false)),
+		encode_revert_reason("Tried to read out of bounds")
+	);
+}
+
+#[test]
+fn forwardable_gas_leaves_the_call_cost_and_a_64th() {
+	let config = fp_evm::Config::shanghai();
+	// A cold call costs 2_600, and 34_000 more when transferring value.
+	assert_eq!(
+		forwardable_gas(102_600, U256::zero(), &config),
+		100_000 - 100_000 / 64
+	);
+	assert_eq!(
+		forwardable_gas(136_600, U256::one(), &config),
+		100_000 - 100_000 / 64
+	);
+	assert_eq!(forwardable_gas(30_000, U256::one(), &config), 0);
+
+	// Before EIP-150 and EIP-161, a call costs 40 gas plus 25_000 for a possibly empty target,
+	// and the gas left is forwarded as is.
+	let config = fp_evm::Config::frontier();
+	assert_eq!(forwardable_gas(125_040, U256::zero(), &config), 100_000);
+}
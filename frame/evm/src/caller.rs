@@ -0,0 +1,179 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed EVM calls for other pallets.
+//!
+//! [`EvmCaller`] wraps [`Runner::call`] for runtime code that needs to call a contract, such as
+//! an XCM handler transferring an ERC-20 or a pallet reading an oracle. Arguments and return
+//! values are ABI encoded with [`precompile_utils::solidity::Codec`].
+
+use core::marker::PhantomData;
+// Substrate
+use frame_support::{
+	dispatch::{Pays, PostDispatchInfo},
+	storage::{with_transaction, TransactionOutcome},
+	weights::Weight,
+};
+use sp_core::{H160, U256};
+use sp_runtime::{traits::UniqueSaturatedInto, DispatchError, DispatchErrorWithPostInfo};
+use sp_std::vec::Vec;
+// Frontier
+use fp_evm::WeightInfo;
+use precompile_utils::solidity::{decode_arguments, encode_with_selector, Codec};
+
+use crate::{Config, Error, ExitError, ExitReason, GasWeightMapping, Runner};
+
+/// ABI encoded input of a contract call.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AbiCall(Vec<u8>);
+
+impl AbiCall {
+	/// Call the function identified by `selector` with `arguments`.
+	///
+	/// ```ignore
+	/// // transfer(address,uint256)
+	/// let call = AbiCall::new(0xa9059cbb, (recipient, amount));
+	/// ```
+	pub fn new<Args: Codec>(selector: u32, arguments: Args) -> Self {
+		Self(encode_with_selector(selector, arguments))
+	}
+
+	/// Use already encoded call data as is.
+	pub fn from_raw(input: Vec<u8>) -> Self {
+		Self(input)
+	}
+
+	pub fn into_inner(self) -> Vec<u8> {
+		self.0
+	}
+}
+
+/// Decoded output of a successful [`EvmCaller::call`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EvmCallOutput<Output> {
+	/// Return value of the called function.
+	pub value: Output,
+	/// Weight used by the call.
+	pub weight: Weight,
+}
+
+/// Calls EVM contracts from the runtime.
+///
+/// Calls are non-transactional: no fee is charged and the nonce of `from` is not checked. The
+/// caller is expected to account for the returned weight itself.
+pub struct EvmCaller<T>(PhantomData<T>);
+
+impl<T: Config> EvmCaller<T> {
+	/// Call `to` from `from`, with a gas limit converted from `weight_limit` by
+	/// [`Config::GasWeightMapping`], and decode the return data as `Output`. The storage read
+	/// is limited by the proof size of `weight_limit`, when not zero.
+	///
+	/// All storage changes, including the nonce increment of `from`, are rolled back unless the
+	/// call succeeds and its output decodes. Errors carry the weight used so far.
+	pub fn call<Output: Codec>(
+		from: H160,
+		to: H160,
+		call: AbiCall,
+		weight_limit: Weight,
+	) -> Result<EvmCallOutput<Output>, DispatchErrorWithPostInfo> {
+		with_transaction(|| {
+			let result = Self::call_inner(from, to, call, weight_limit);
+			if result.is_ok() {
+				TransactionOutcome::Commit(result)
+			} else {
+				TransactionOutcome::Rollback(result)
+			}
+		})
+	}
+
+	fn call_inner<Output: Codec>(
+		from: H160,
+		to: H160,
+		call: AbiCall,
+		weight_limit: Weight,
+	) -> Result<EvmCallOutput<Output>, DispatchErrorWithPostInfo> {
+		let gas_limit = T::GasWeightMapping::weight_to_gas(weight_limit);
+		// The proof size is metered when the limit has one, the input being already in the proof.
+		let proof_size_base_cost = (weight_limit.proof_size() > 0).then_some(0);
+		let info = T::Runner::call(
+			from,
+			to,
+			call.into_inner(),
+			U256::zero(),
+			gas_limit,
+			None,
+			None,
+			None,
+			Vec::new(),
+			false, // non-transactional
+			false, // not validated
+			Some(weight_limit),
+			proof_size_base_cost,
+			T::config(),
+		)
+		.map_err(|e| with_weight(e.error.into(), e.weight))?;
+
+		let weight = used_weight::<T>(info.used_gas.effective, info.weight_info);
+		let error: DispatchError = match info.exit_reason {
+			ExitReason::Succeed(_) => {
+				return decode_arguments::<Output>(&info.value)
+					.map(|value| EvmCallOutput { value, weight })
+					.map_err(|_| with_weight(Error::<T>::InvalidCallOutput.into(), weight));
+			}
+			ExitReason::Revert(_) => {
+				log::debug!(
+					target: "evm",
+					"Call to {:?} reverted [output: {:?}]",
+					to,
+					info.value,
+				);
+				Error::<T>::CallReverted.into()
+			}
+			ExitReason::Error(ExitError::OutOfGas) => Error::<T>::GasLimitTooLow.into(),
+			ExitReason::Error(_) | ExitReason::Fatal(_) => Error::<T>::CallFailed.into(),
+		};
+		Err(with_weight(error, weight))
+	}
+}
+
+/// Weight of a call using `effective_gas`, with the ref time and proof size recorded in
+/// `weight_info` when metered.
+///
+/// No extrinsic is dispatched, so the base extrinsic weight is not deducted.
+fn used_weight<T: Config>(effective_gas: U256, weight_info: Option<WeightInfo>) -> Weight {
+	let mut weight =
+		T::GasWeightMapping::gas_to_weight(effective_gas.unique_saturated_into(), false);
+	if let Some(weight_info) = weight_info {
+		if let Some(ref_time_usage) = weight_info.ref_time_usage {
+			*weight.ref_time_mut() = weight.ref_time().max(ref_time_usage);
+		}
+		if let Some(proof_size_usage) = weight_info.proof_size_usage {
+			*weight.proof_size_mut() = proof_size_usage;
+		}
+	}
+	weight
+}
+
+fn with_weight(error: DispatchError, weight: Weight) -> DispatchErrorWithPostInfo {
+	DispatchErrorWithPostInfo {
+		post_info: PostDispatchInfo {
+			actual_weight: Some(weight),
+			pays_fee: Pays::Yes,
+		},
+		error,
+	}
+}
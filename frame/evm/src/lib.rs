@@ -58,6 +58,7 @@
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
 
+pub mod caller;
 #[cfg(test)]
 mod mock;
 pub mod runner;
@@ -93,15 +94,18 @@ use sp_runtime::{
 use sp_std::{cmp::min, collections::btree_map::BTreeMap, vec::Vec};
 // Frontier
 use fp_account::AccountId20;
-use fp_evm::GenesisAccount;
 pub use fp_evm::{
 	Account, CallInfo, CreateInfo, ExecutionInfoV2 as ExecutionInfo, FeeCalculator,
 	InvalidEvmTransactionError, IsPrecompileResult, LinearCostPrecompile, Log, Precompile,
 	PrecompileFailure, PrecompileHandle, PrecompileOutput, PrecompileResult, PrecompileSet,
 	Vicinity,
 };
+use fp_evm::{
+	GenesisAccount, IsContract, ACCOUNT_CODES_KEY_PROOF_SIZE, ACCOUNT_CODES_METADATA_PROOF_SIZE,
+};
 
 pub use self::{
+	caller::{AbiCall, EvmCallOutput, EvmCaller},
 	pallet::*,
 	runner::{Runner, RunnerError},
 	weights::WeightInfo,
@@ -161,6 +165,15 @@ pub mod pallet {
 		/// Called on create calls, used to record owner
 		type OnCreate: OnCreate<Self>;
 
+		/// To handle the deposit held for contract storage slots. Newly created slots are charged
+		/// to the transaction origin, and the deposit is refunded to that account when the slot is
+		/// cleared or the contract is deleted. Use `()` to disable storage deposits.
+		type StorageDeposit: OnChargeStorageDeposit<Self>;
+
+		/// Maximum length of the revert data carried by execution events of this pallet and
+		/// `pallet_ethereum`. `None` leaves [`ExecutionDetails`] out of the events entirely.
+		type EventRevertDataLimit: Get<Option<u32>>;
+
 		/// Find author for the current block.
 		type FindAuthor: FindAuthor<H160>;
 
@@ -177,6 +190,13 @@ pub mod pallet {
 		fn config() -> &'static EvmConfig {
 			&SHANGHAI_CONFIG
 		}
+
+		/// Whether SELFDESTRUCT follows EIP-6780, introduced in the Cancun hard fork. If enabled,
+		/// code and storage are only removed when the contract was created in the same
+		/// transaction, otherwise only the balance is moved to the beneficiary.
+		fn eip_6780() -> bool {
+			false
+		}
 	}
 
 	#[pallet::call]
@@ -252,12 +272,23 @@ pub mod pallet {
 				}
 			};
 
+			let details = Pallet::<T>::execution_details(
+				&info.exit_reason,
+				info.used_gas.effective,
+				&info.value,
+			);
 			match info.exit_reason {
 				ExitReason::Succeed(_) => {
-					Pallet::<T>::deposit_event(Event::<T>::Executed { address: target });
+					Pallet::<T>::deposit_event(Event::<T>::Executed {
+						address: target,
+						details,
+					});
 				}
 				_ => {
-					Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed { address: target });
+					Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed {
+						address: target,
+						details,
+					});
 				}
 			};
 
@@ -327,6 +358,8 @@ pub mod pallet {
 				}
 			};
 
+			let details =
+				Pallet::<T>::execution_details(&info.exit_reason, info.used_gas.effective, &[]);
 			match info {
 				CreateInfo {
 					exit_reason: ExitReason::Succeed(_),
@@ -335,6 +368,7 @@ pub mod pallet {
 				} => {
 					Pallet::<T>::deposit_event(Event::<T>::Created {
 						address: create_address,
+						details,
 					});
 				}
 				CreateInfo {
@@ -344,6 +378,7 @@ pub mod pallet {
 				} => {
 					Pallet::<T>::deposit_event(Event::<T>::CreatedFailed {
 						address: create_address,
+						details,
 					});
 				}
 			}
@@ -415,6 +450,8 @@ pub mod pallet {
 				}
 			};
 
+			let details =
+				Pallet::<T>::execution_details(&info.exit_reason, info.used_gas.effective, &[]);
 			match info {
 				CreateInfo {
 					exit_reason: ExitReason::Succeed(_),
@@ -423,6 +460,7 @@ pub mod pallet {
 				} => {
 					Pallet::<T>::deposit_event(Event::<T>::Created {
 						address: create_address,
+						details,
 					});
 				}
 				CreateInfo {
@@ -432,6 +470,7 @@ pub mod pallet {
 				} => {
 					Pallet::<T>::deposit_event(Event::<T>::CreatedFailed {
 						address: create_address,
+						details,
 					});
 				}
 			}
@@ -452,6 +491,23 @@ pub mod pallet {
 				pays_fee: Pays::No,
 			})
 		}
+
+		/// Release the deposits of the storage slots of `contract` which were cleared when it was
+		/// destroyed or its storage reset, looking at `limit` deposits at most.
+		///
+		/// The deposits are refunded to their payers, whoever signs the call.
+		#[pallet::call_index(4)]
+		#[pallet::weight(Pallet::<T>::storage_deposit_release_weight(*limit))]
+		pub fn release_storage_deposits(
+			origin: OriginFor<T>,
+			contract: H160,
+			limit: u32,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			let visited = Pallet::<T>::release_cleared_storage_deposits(&contract, limit);
+			Ok(Some(Pallet::<T>::storage_deposit_release_weight(visited)).into())
+		}
 	}
 
 	#[pallet::event]
@@ -460,13 +516,25 @@ pub mod pallet {
 		/// Ethereum events from contracts.
 		Log { log: Log },
 		/// A contract has been created at given address.
-		Created { address: H160 },
+		Created {
+			address: H160,
+			details: Option<ExecutionDetails>,
+		},
 		/// A contract was attempted to be created, but the execution failed.
-		CreatedFailed { address: H160 },
+		CreatedFailed {
+			address: H160,
+			details: Option<ExecutionDetails>,
+		},
 		/// A contract has been executed successfully with states applied.
-		Executed { address: H160 },
+		Executed {
+			address: H160,
+			details: Option<ExecutionDetails>,
+		},
 		/// A contract has been executed with errors. States are reverted with only gas fees applied.
-		ExecutedFailed { address: H160 },
+		ExecutedFailed {
+			address: H160,
+			details: Option<ExecutionDetails>,
+		},
 	}
 
 	#[pallet::error]
@@ -493,6 +561,12 @@ pub mod pallet {
 		Reentrancy,
 		/// EIP-3607,
 		TransactionMustComeFromEOA,
+		/// A call made through `EvmCaller` reverted.
+		CallReverted,
+		/// A call made through `EvmCaller` failed with an EVM error.
+		CallFailed,
+		/// The return data of a call made through `EvmCaller` could not be decoded.
+		InvalidCallOutput,
 	}
 
 	impl<T> From<InvalidEvmTransactionError> for Error<T> {
@@ -523,19 +597,15 @@ pub mod pallet {
 		U256: UniqueSaturatedInto<BalanceOf<T>>,
 	{
 		fn build(&self) {
-			const MAX_ACCOUNT_NONCE: usize = 100;
-
 			for (address, account) in &self.accounts {
 				let account_id = T::AddressMapping::into_account_id(*address);
 
-				// ASSUME: in one single EVM transaction, the nonce will not increase more than
-				// `u128::max_value()`.
-				for _ in 0..min(
-					MAX_ACCOUNT_NONCE,
-					UniqueSaturatedInto::<usize>::unique_saturated_into(account.nonce),
-				) {
-					frame_system::Pallet::<T>::inc_account_nonce(&account_id);
-				}
+				// Imported state may carry arbitrarily large nonces, so set them directly
+				// (saturating) rather than incrementing one by one.
+				let nonce: T::Index =
+					UniqueSaturatedInto::<u128>::unique_saturated_into(account.nonce)
+						.unique_saturated_into();
+				frame_system::Account::<T>::mutate(&account_id, |info| info.nonce = nonce);
 
 				T::Currency::deposit_creating(&account_id, account.balance.unique_saturated_into());
 
@@ -558,6 +628,30 @@ pub mod pallet {
 	#[pallet::storage]
 	pub type AccountStorages<T: Config> =
 		StorageDoubleMap<_, Blake2_128Concat, H160, Blake2_128Concat, H256, H256, ValueQuery>;
+
+	/// Total storage deposit currently held for each contract, by payer.
+	#[pallet::storage]
+	pub type AccountStorageDeposits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		H160,
+		Blake2_128Concat,
+		H160,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Payer and amount of the deposit held for each contract storage slot.
+	#[pallet::storage]
+	pub type AccountStorageDepositors<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		H160,
+		Blake2_128Concat,
+		H256,
+		(H160, BalanceOf<T>),
+		OptionQuery,
+	>;
 }
 
 /// Type alias for currency balance.
@@ -593,6 +687,16 @@ impl CodeMetadata {
 	}
 }
 
+/// Execution details attached to events when enabled by [`Config::EventRevertDataLimit`].
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub struct ExecutionDetails {
+	/// Effective gas used by the execution.
+	pub used_gas: U256,
+	/// Revert data of the execution, truncated to the configured limit. Always empty for
+	/// successful executions and for creates, whose revert data is not returned.
+	pub revert_data: Vec<u8>,
+}
+
 pub trait EnsureAddressOrigin<OuterOrigin> {
 	/// Success return type.
 	type Success;
@@ -772,6 +876,24 @@ impl<T: Config> GasWeightMapping for FixedGasWeightMapping<T> {
 static SHANGHAI_CONFIG: EvmConfig = EvmConfig::shanghai();
 
 impl<T: Config> Pallet<T> {
+	/// Execution details of a call or create to put in events, if enabled.
+	pub fn execution_details(
+		exit_reason: &ExitReason,
+		used_gas: U256,
+		output: &[u8],
+	) -> Option<ExecutionDetails> {
+		let limit = T::EventRevertDataLimit::get()? as usize;
+		let revert_data = match exit_reason {
+			ExitReason::Revert(_) => output[..min(output.len(), limit)].to_vec(),
+			_ => Vec::new(),
+		};
+
+		Some(ExecutionDetails {
+			used_gas,
+			revert_data,
+		})
+	}
+
 	/// Check whether an account is empty.
 	pub fn is_account_empty(address: &H160) -> bool {
 		let (account, _) = Self::account_basic(address);
@@ -870,6 +992,101 @@ impl<T: Config> Pallet<T> {
 
 		T::FindAuthor::find_author(pre_runtime_digests).unwrap_or_default()
 	}
+
+	/// Charge `payer` the deposit for the newly created storage slot `index` of `contract`.
+	pub fn hold_storage_deposit(
+		payer: &H160,
+		contract: &H160,
+		index: &H256,
+	) -> Result<(), Error<T>> {
+		let deposit = T::StorageDeposit::deposit_per_slot();
+		if deposit.is_zero() {
+			return Ok(());
+		}
+
+		// The slot may still have the deposit of a previous value, cleared when the storage of
+		// `contract` was reset.
+		Self::release_storage_deposit(contract, index);
+
+		T::StorageDeposit::hold(payer, contract, deposit)?;
+		<AccountStorageDeposits<T>>::mutate(contract, payer, |total| {
+			*total = total.saturating_add(deposit)
+		});
+		<AccountStorageDepositors<T>>::insert(contract, index, (*payer, deposit));
+		Ok(())
+	}
+
+	/// Refund the deposit of the cleared storage slot `index` of `contract` to its payer.
+	pub fn release_storage_deposit(contract: &H160, index: &H256) {
+		if let Some((payer, deposit)) = <AccountStorageDepositors<T>>::take(contract, index) {
+			let deposit = min(deposit, <AccountStorageDeposits<T>>::get(contract, payer));
+			Self::do_release_storage_deposit(&payer, contract, deposit);
+		}
+	}
+
+	/// Refund the deposits of the slots of `contract` that were cleared without being released,
+	/// when it was destroyed or its storage reset, looking at `limit` deposits at most.
+	///
+	/// Deposits aren't released when the storage of a contract is removed, as it is not bounded.
+	/// Returns the number of deposits looked at.
+	pub fn release_cleared_storage_deposits(contract: &H160, limit: u32) -> u32 {
+		let cleared = <AccountStorageDepositors<T>>::iter_key_prefix(contract)
+			.take(limit as usize)
+			.collect::<Vec<_>>();
+		let visited = cleared.len() as u32;
+		for index in cleared {
+			if !<AccountStorages<T>>::contains_key(contract, index) {
+				Self::release_storage_deposit(contract, &index);
+			}
+		}
+		visited
+	}
+
+	/// Weight of looking at and releasing `count` storage deposits: the payer of the slot, its
+	/// value, the deposit total of the payer and the payer and contract accounts are read, all
+	/// but the value are written.
+	pub fn storage_deposit_release_weight(count: u32) -> Weight {
+		T::DbWeight::get()
+			.reads_writes(5, 4)
+			.saturating_mul(count.into())
+	}
+
+	fn do_release_storage_deposit(payee: &H160, contract: &H160, deposit: BalanceOf<T>) {
+		if deposit.is_zero() {
+			return;
+		}
+
+		T::StorageDeposit::release(payee, contract, deposit);
+		<AccountStorageDeposits<T>>::mutate_exists(contract, payee, |total| {
+			let remaining = total.unwrap_or_default().saturating_sub(deposit);
+			*total = if remaining.is_zero() {
+				None
+			} else {
+				Some(remaining)
+			};
+		});
+	}
+}
+
+impl<T: Config> IsContract for Pallet<T> {
+	fn is_contract(handle: &mut impl PrecompileHandle, address: H160) -> Result<bool, ExitError> {
+		let read = T::DbWeight::get().reads(1).ref_time();
+		handle.record_external_cost(Some(read), Some(ACCOUNT_CODES_METADATA_PROOF_SIZE))?;
+		if let Some(meta) = <AccountCodesMetadata<T>>::get(address) {
+			return Ok(meta.size > 0);
+		}
+
+		// Without cached metadata the whole code ends up in the proof. Like the runner, record
+		// `create_contract_limit` first and refund the difference with the actual size.
+		let size_limit = T::config().create_contract_limit.unwrap_or_default() as u64;
+		handle.record_external_cost(
+			Some(read),
+			Some(ACCOUNT_CODES_KEY_PROOF_SIZE.saturating_add(size_limit)),
+		)?;
+		let size = <AccountCodes<T>>::decode_len(address).unwrap_or_default() as u64;
+		handle.refund_external_cost(None, Some(size_limit.saturating_sub(size)));
+		Ok(size > 0)
+	}
 }
 
 /// Handle withdrawing, refunding and depositing of transaction fees.
@@ -1031,6 +1248,67 @@ U256: UniqueSaturatedInto<BalanceOf<T>>,
 	}
 }
 
+/// Handle holding and releasing the deposit of contract storage slots.
+pub trait OnChargeStorageDeposit<T: Config> {
+	/// Deposit required for every newly created storage slot.
+	fn deposit_per_slot() -> BalanceOf<T>;
+
+	/// Take `amount` from `payer` and hold it on behalf of `contract`.
+	fn hold(payer: &H160, contract: &H160, amount: BalanceOf<T>) -> Result<(), Error<T>>;
+
+	/// Return `amount` of the deposit held on behalf of `contract` to `payee`.
+	fn release(payee: &H160, contract: &H160, amount: BalanceOf<T>);
+}
+
+/// Implementation for () disables storage deposits.
+impl<T: Config> OnChargeStorageDeposit<T> for () {
+	fn deposit_per_slot() -> BalanceOf<T> {
+		Zero::zero()
+	}
+
+	fn hold(_payer: &H160, _contract: &H160, _amount: BalanceOf<T>) -> Result<(), Error<T>> {
+		Ok(())
+	}
+
+	fn release(_payee: &H160, _contract: &H160, _amount: BalanceOf<T>) {}
+}
+
+/// Holds storage deposits as reserved balance of the contract account, using a currency
+/// implementing `ReservableCurrency` (eg. the pallet_balances).
+pub struct EVMReserveStorageDeposit<C, Deposit>(sp_std::marker::PhantomData<(C, Deposit)>);
+
+impl<T, C, Deposit> OnChargeStorageDeposit<T> for EVMReserveStorageDeposit<C, Deposit>
+where
+	T: Config,
+	C: ReservableCurrency<<T as frame_system::Config>::AccountId, Balance = BalanceOf<T>>,
+	Deposit: Get<BalanceOf<T>>,
+{
+	fn deposit_per_slot() -> BalanceOf<T> {
+		Deposit::get()
+	}
+
+	fn hold(payer: &H160, contract: &H160, amount: BalanceOf<T>) -> Result<(), Error<T>> {
+		let payer = T::AddressMapping::into_account_id(*payer);
+		let contract = T::AddressMapping::into_account_id(*contract);
+		C::transfer(&payer, &contract, amount, ExistenceRequirement::AllowDeath)
+			.map_err(|_| Error::<T>::BalanceLow)?;
+		C::reserve(&contract, amount).map_err(|_| Error::<T>::BalanceLow)
+	}
+
+	fn release(payee: &H160, contract: &H160, amount: BalanceOf<T>) {
+		let payee = T::AddressMapping::into_account_id(*payee);
+		let contract = T::AddressMapping::into_account_id(*contract);
+		// Only move what was actually unreserved.
+		let unreserved = amount.saturating_sub(C::unreserve(&contract, amount));
+		let _ = C::transfer(
+			&contract,
+			&payee,
+			unreserved,
+			ExistenceRequirement::AllowDeath,
+		);
+	}
+}
+
 pub trait OnCreate<T> {
 	fn on_create(owner: H160, contract: H160);
 }
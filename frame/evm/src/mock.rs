@@ -30,9 +30,12 @@ use sp_runtime::{
 };
 use sp_std::{boxed::Box, prelude::*, str::FromStr};
 
+use fp_evm::{ExitSucceed, IsContract};
+
 use crate::{
 	EnsureAddressNever, EnsureAddressRoot, FeeCalculator, IdentityAddressMapping,
-	IsPrecompileResult, Precompile, PrecompileHandle, PrecompileResult, PrecompileSet,
+	IsPrecompileResult, Pallet, Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput,
+	PrecompileResult, PrecompileSet,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -137,6 +140,9 @@ parameter_types! {
 	pub const GasLimitPovSizeRatio: u64 = BLOCK_GAS_LIMIT.saturating_div(MAX_POV_SIZE);
 	pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
 	pub MockPrecompiles: MockPrecompileSet = MockPrecompileSet;
+	pub static StorageDepositPerSlot: u64 = 0;
+	pub static Eip6780: bool = false;
+	pub static EventRevertDataLimit: Option<u32> = None;
 }
 impl crate::Config for Test {
 	type FeeCalculator = FixedGasPrice;
@@ -158,13 +164,39 @@ impl crate::Config for Test {
 	type Runner = crate::runner::stack::Runner<Self>;
 	type OnChargeTransaction = ();
 	type OnCreate = ();
+	type StorageDeposit = crate::EVMReserveStorageDeposit<Balances, StorageDepositPerSlot>;
+	type EventRevertDataLimit = EventRevertDataLimit;
 	type FindAuthor = FindAuthorTruncated;
 	type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
 	type Timestamp = Timestamp;
 	type WeightInfo = ();
+
+	fn eip_6780() -> bool {
+		Eip6780::get()
+	}
 }
 
-/// Example PrecompileSet with only Identity precompile.
+/// Returns whether the address in its input is a contract.
+pub struct IsContractPrecompile;
+
+impl Precompile for IsContractPrecompile {
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		let address =
+			H256::from_slice(handle.input().get(..32).ok_or(PrecompileFailure::Error {
+				exit_status: fp_evm::ExitError::Other("invalid input".into()),
+			})?);
+		let is_contract = <Pallet<Test> as IsContract>::is_contract(handle, address.into())?;
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: H256::from_low_u64_be(is_contract as u64)
+				.as_bytes()
+				.to_vec(),
+		})
+	}
+}
+
+/// Example PrecompileSet with the Identity precompile, and `IsContractPrecompile` at address 2.
 pub struct MockPrecompileSet;
 
 impl PrecompileSet for MockPrecompileSet {
@@ -176,6 +208,9 @@ impl PrecompileSet for MockPrecompileSet {
 		if address == H160::from_low_u64_be(1) {
 			return Some(pallet_evm_precompile_simple::Identity::execute(handle));
 		}
+		if address == H160::from_low_u64_be(2) {
+			return Some(IsContractPrecompile::execute(handle));
+		}
 
 		None
 	}
@@ -185,7 +220,8 @@ impl PrecompileSet for MockPrecompileSet {
 	/// `execute` already performs a check internally.
 	fn is_precompile(&self, address: H160, _gas: u64) -> IsPrecompileResult {
 		IsPrecompileResult::Answer {
-			is_precompile: address == H160::from_low_u64_be(1),
+			is_precompile: address == H160::from_low_u64_be(1)
+				|| address == H160::from_low_u64_be(2),
 			extra_cost: 0,
 		}
 	}
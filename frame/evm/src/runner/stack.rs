@@ -32,7 +32,7 @@ use frame_support::{
 	weights::Weight,
 };
 use sp_core::{H160, H256, U256};
-use sp_runtime::traits::UniqueSaturatedInto;
+use sp_runtime::traits::{UniqueSaturatedInto, Zero};
 use sp_std::{
 	boxed::Box,
 	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
@@ -43,14 +43,16 @@ use sp_std::{
 // Frontier
 use fp_evm::{
 	AccessedStorage, CallInfo, CreateInfo, ExecutionInfoV2, IsPrecompileResult, Log, PrecompileSet,
-	Vicinity, WeightInfo, ACCOUNT_BASIC_PROOF_SIZE, ACCOUNT_CODES_METADATA_PROOF_SIZE,
-	ACCOUNT_STORAGE_PROOF_SIZE, IS_EMPTY_CHECK_PROOF_SIZE, WRITE_PROOF_SIZE,
+	Vicinity, WeightInfo, ACCOUNT_BASIC_PROOF_SIZE, ACCOUNT_CODES_KEY_PROOF_SIZE,
+	ACCOUNT_CODES_METADATA_PROOF_SIZE, ACCOUNT_STORAGE_DEPOSITOR_PROOF_SIZE,
+	ACCOUNT_STORAGE_DEPOSIT_PROOF_SIZE, ACCOUNT_STORAGE_PROOF_SIZE, IS_EMPTY_CHECK_PROOF_SIZE,
+	WRITE_PROOF_SIZE,
 };
 
 use crate::{
 	runner::Runner as RunnerT, AccountCodes, AccountCodesMetadata, AccountStorages, AddressMapping,
 	BalanceOf, BlockHashMapping, Config, Error, Event, FeeCalculator, OnChargeEVMTransaction,
-	OnCreate, Pallet, RunnerError,
+	OnChargeStorageDeposit, OnCreate, Pallet, RunnerError,
 };
 
 #[cfg(feature = "forbid-evm-reentrancy")]
@@ -569,6 +571,7 @@ where
 struct SubstrateStackSubstate<'config> {
 	metadata: StackSubstateMetadata<'config>,
 	deletes: BTreeSet<H160>,
+	creates: BTreeSet<H160>,
 	logs: Vec<Log>,
 	parent: Option<Box<SubstrateStackSubstate<'config>>>,
 }
@@ -587,6 +590,7 @@ impl<'config> SubstrateStackSubstate<'config> {
 			metadata: self.metadata.spit_child(gas_limit, is_static),
 			parent: None,
 			deletes: BTreeSet::new(),
+			creates: BTreeSet::new(),
 			logs: Vec::new(),
 		};
 		mem::swap(&mut entering, self);
@@ -603,6 +607,7 @@ impl<'config> SubstrateStackSubstate<'config> {
 		self.metadata.swallow_commit(exited.metadata)?;
 		self.logs.append(&mut exited.logs);
 		self.deletes.append(&mut exited.deletes);
+		self.creates.append(&mut exited.creates);
 
 		sp_io::storage::commit_transaction();
 		Ok(())
@@ -642,6 +647,22 @@ impl<'config> SubstrateStackSubstate<'config> {
 		self.deletes.insert(address);
 	}
 
+	pub fn created(&self, address: H160) -> bool {
+		if self.creates.contains(&address) {
+			return true;
+		}
+
+		if let Some(parent) = self.parent.as_ref() {
+			return parent.created(address);
+		}
+
+		false
+	}
+
+	pub fn set_created(&mut self, address: H160) {
+		self.creates.insert(address);
+	}
+
 	pub fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) {
 		self.logs.push(Log {
 			address,
@@ -691,6 +712,7 @@ impl<'vicinity, 'config, T: Config> SubstrateStackState<'vicinity, 'config, T> {
 			substate: SubstrateStackSubstate {
 				metadata,
 				deletes: BTreeSet::new(),
+				creates: BTreeSet::new(),
 				logs: Vec::new(),
 				parent: None,
 			},
@@ -714,6 +736,70 @@ impl<'vicinity, 'config, T: Config> SubstrateStackState<'vicinity, 'config, T> {
 	}
 }
 
+impl<'vicinity, 'config, T: Config> SubstrateStackState<'vicinity, 'config, T>
+where
+	BalanceOf<T>: TryFrom<U256> + Into<U256>,
+{
+	/// Hold or release the storage deposit of slot `index` changing from `current` to `new`.
+	fn record_storage_deposit(
+		&mut self,
+		address: H160,
+		index: H256,
+		current: H256,
+		new: H256,
+	) -> Result<(), ExitError> {
+		if T::StorageDeposit::deposit_per_slot().is_zero() {
+			return Ok(());
+		}
+
+		let created = current == H256::default() && new != H256::default();
+		let cleared = current != H256::default() && new == H256::default();
+		if !created && !cleared {
+			return Ok(());
+		}
+
+		// Payer and contract accounts are read and written, as are the deposit total of the payer
+		// and the payer of the slot. A created slot may first release the deposit of a value
+		// cleared by a storage reset, to its own payer.
+		let (payers, accesses) = if created { (2, 6) } else { (1, 4) };
+		self.record_external_cost(
+			Some(
+				T::DbWeight::get()
+					.reads_writes(accesses, accesses)
+					.ref_time(),
+			),
+			Some(
+				ACCOUNT_BASIC_PROOF_SIZE
+					.saturating_mul(payers + 1)
+					.saturating_add(ACCOUNT_STORAGE_DEPOSIT_PROOF_SIZE.saturating_mul(payers))
+					.saturating_add(ACCOUNT_STORAGE_DEPOSITOR_PROOF_SIZE),
+			),
+		)?;
+
+		if created {
+			let origin = self.vicinity.origin;
+			log::debug!(
+				target: "evm",
+				"Holding storage deposit for {:?} [index: {:?}, payer: {:?}]",
+				address,
+				index,
+				origin,
+			);
+			Pallet::<T>::hold_storage_deposit(&origin, &address, &index)
+				.map_err(|_| ExitError::OutOfFund)?;
+		} else {
+			log::debug!(
+				target: "evm",
+				"Releasing storage deposit for {:?} [index: {:?}]",
+				address,
+				index,
+			);
+			Pallet::<T>::release_storage_deposit(&address, &index);
+		}
+		Ok(())
+	}
+}
+
 impl<'vicinity, 'config, T: Config> BackendT for SubstrateStackState<'vicinity, 'config, T>
 where
 	BalanceOf<T>: TryFrom<U256> + Into<U256>,
@@ -881,14 +967,37 @@ where
 	}
 
 	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) {
+		#[cfg(feature = "tracing")]
+		super::tracing::log(&Log {
+			address,
+			topics: topics.clone(),
+			data: data.clone(),
+		});
+
 		self.substate.log(address, topics, data)
 	}
 
 	fn set_deleted(&mut self, address: H160) {
+		// EIP-6780: code and storage are only removed if the contract was created in the same
+		// transaction. A contract without code is still running its init code, so it is being
+		// created as well. Otherwise SELFDESTRUCT only moves the balance, which the executor
+		// already did through `transfer`.
+		if T::eip_6780()
+			&& !self.substate.created(address)
+			&& <AccountCodes<T>>::decode_len(address).unwrap_or(0) != 0
+		{
+			log::debug!(
+				target: "evm",
+				"Keeping account at {:?}, not created in this transaction",
+				address
+			);
+			return;
+		}
 		self.substate.set_deleted(address)
 	}
 
 	fn set_code(&mut self, address: H160, code: Vec<u8>) {
+		self.substate.set_created(address);
 		log::debug!(
 			target: "evm",
 			"Inserting code ({} bytes) at {:?}",
@@ -1005,9 +1114,16 @@ where
 	fn record_external_dynamic_opcode_cost(
 		&mut self,
 		opcode: Opcode,
-		_gas_cost: GasCost,
+		gas_cost: GasCost,
 		target: evm::gasometer::StorageTarget,
 	) -> Result<(), ExitError> {
+		// Storage deposits are charged regardless of weight metering being enabled.
+		if let (GasCost::SStore { current, new, .. }, StorageTarget::Slot(address, index)) =
+			(&gas_cost, &target)
+		{
+			self.record_storage_deposit(*address, *index, *current, *new)?;
+		}
+
 		// If account code or storage slot is in the overlay it is already accounted for and early exit
 		let mut accessed_storage: Option<AccessedStorage> = match target {
 			StorageTarget::Address(address) => {
@@ -1137,9 +1253,15 @@ where
 			// address's balance. We need to account for both:
 			//	- Target basic account read
 			//	- 5 bytes of `decode_len`
+			// With EIP-6780, `set_deleted` may also read the length of the self destructing
+			// address's code.
 			Opcode::SUICIDE => {
 				accessed_storage = None;
-				U256::from(IS_EMPTY_CHECK_PROOF_SIZE)
+				let mut cost = IS_EMPTY_CHECK_PROOF_SIZE;
+				if T::eip_6780() {
+					cost = cost.saturating_add(ACCOUNT_CODES_KEY_PROOF_SIZE);
+				}
+				U256::from(cost)
 			}
 			// Rest of dynamic opcodes that do not involve proof size recording, do nothing
 			_ => return Ok(()),
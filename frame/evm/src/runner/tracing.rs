@@ -0,0 +1,201 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! EVM execution tracer hooks.
+//!
+//! A [`Tracer`] installed with [`using`] observes every EVM execution made by the runner within
+//! the given closure, whether it originates from a transaction, a runtime API call or another
+//! pallet. Events are collected from the `evm` and `evm-runtime` tracing listeners, plus the
+//! logs emitted by the runner's stack state.
+
+use evm::{Context, ExitReason, Memory, Opcode, Stack};
+use sp_core::{H160, H256, U256};
+// Frontier
+use fp_evm::Log;
+
+environmental::environmental!(tracer: dyn Tracer + 'static);
+
+/// Frame entered by the EVM.
+#[derive(Debug, Clone, Copy)]
+pub enum CallEnter<'a> {
+	/// A message call, including calls made by precompiles.
+	Call {
+		caller: H160,
+		address: H160,
+		code_address: H160,
+		value: U256,
+		input: &'a [u8],
+		gas_limit: Option<u64>,
+		is_static: bool,
+	},
+	/// A contract creation.
+	Create {
+		caller: H160,
+		address: H160,
+		value: U256,
+		init_code: &'a [u8],
+		gas_limit: Option<u64>,
+	},
+}
+
+/// Contract storage access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageAccess {
+	/// `SLOAD` of `value` at `index`.
+	Read {
+		address: H160,
+		index: H256,
+		value: H256,
+	},
+	/// `SSTORE` of `value` at `index`.
+	Write {
+		address: H160,
+		index: H256,
+		value: H256,
+	},
+}
+
+/// Hooks receiving EVM execution events. All hooks default to doing nothing.
+pub trait Tracer {
+	/// The opcode at `pc` is about to be executed in the frame of `context`.
+	fn step(
+		&mut self,
+		_context: &Context,
+		_opcode: Opcode,
+		_pc: usize,
+		_stack: &Stack,
+		_memory: &Memory,
+	) {
+	}
+
+	/// A new frame is entered.
+	fn call_enter(&mut self, _call: CallEnter<'_>) {}
+
+	/// The current frame exited.
+	fn call_exit(&mut self, _reason: &ExitReason, _return_value: &[u8]) {}
+
+	/// Contract storage was read or written.
+	fn storage_access(&mut self, _access: StorageAccess) {}
+
+	/// A log was emitted. Logs of frames that are later reverted are reported as well.
+	fn log(&mut self, _log: &Log) {}
+}
+
+/// Run `f` with `new` receiving the events of every EVM execution made within it.
+pub fn using<R, F: FnOnce() -> R>(new: &mut (dyn Tracer + 'static), f: F) -> R {
+	tracer::using(new, || {
+		evm::tracing::using(&mut EvmListener, || {
+			evm_runtime::tracing::using(&mut RuntimeListener, f)
+		})
+	})
+}
+
+/// Report a log emitted by the stack state.
+pub(crate) fn log(log: &Log) {
+	tracer::with(|tracer| tracer.log(log));
+}
+
+struct EvmListener;
+
+impl evm::tracing::EventListener for EvmListener {
+	fn event(&mut self, event: evm::tracing::Event) {
+		use evm::tracing::Event;
+
+		tracer::with(|tracer| match event {
+			Event::Call {
+				code_address,
+				input,
+				target_gas,
+				is_static,
+				context,
+				..
+			}
+			| Event::PrecompileSubcall {
+				code_address,
+				input,
+				target_gas,
+				is_static,
+				context,
+				..
+			} => tracer.call_enter(CallEnter::Call {
+				caller: context.caller,
+				address: context.address,
+				code_address,
+				value: context.apparent_value,
+				input,
+				gas_limit: target_gas,
+				is_static,
+			}),
+			Event::Create {
+				caller,
+				address,
+				value,
+				init_code,
+				target_gas,
+				..
+			} => tracer.call_enter(CallEnter::Create {
+				caller,
+				address,
+				value,
+				init_code,
+				gas_limit: target_gas,
+			}),
+			Event::Exit {
+				reason,
+				return_value,
+			} => tracer.call_exit(reason, return_value),
+			_ => (),
+		});
+	}
+}
+
+struct RuntimeListener;
+
+impl evm_runtime::tracing::EventListener for RuntimeListener {
+	fn event(&mut self, event: evm_runtime::tracing::Event) {
+		use evm_runtime::tracing::Event;
+
+		tracer::with(|tracer| match event {
+			Event::Step {
+				context,
+				opcode,
+				position: Ok(pc),
+				stack,
+				memory,
+			} => tracer.step(context, opcode, *pc, stack, memory),
+			Event::SLoad {
+				address,
+				index,
+				value,
+			} => tracer.storage_access(StorageAccess::Read {
+				address,
+				index,
+				value,
+			}),
+			Event::SStore {
+				address,
+				index,
+				value,
+			} => tracer.storage_access(StorageAccess::Write {
+				address,
+				index,
+				value,
+			}),
+			_ => (),
+		});
+	}
+}
@@ -622,6 +622,55 @@ mod proof_size_test {
 			assert_eq!(used_gas.effective, U256::from(actual_proof_size * ratio));
 		});
 	}
+	#[test]
+	fn is_contract_records_code_without_caching_metadata() {
+		new_test_ext().execute_with(|| {
+			let config = <Test as Config>::config().clone();
+			let call = |address: H160| {
+				let gas_limit: u64 = 1_000_000;
+				let weight_limit = FixedGasWeightMapping::<Test>::gas_to_weight(gas_limit, true);
+				<Test as Config>::Runner::call(
+					H160::default(),
+					H160::from_low_u64_be(2),
+					H256::from(address).as_bytes().to_vec(),
+					U256::zero(),
+					gas_limit,
+					Some(FixedGasPrice::min_gas_price().0),
+					None,
+					None,
+					Vec::new(),
+					true, // transactional
+					true, // must be validated
+					Some(weight_limit),
+					Some(0),
+					&config,
+				)
+				.expect("call succeeds")
+			};
+			let proof_size = |info: &CallInfo| {
+				info.weight_info
+					.expect("weight info")
+					.proof_size_usage
+					.expect("proof size usage")
+			};
+
+			let code_len = 1_000;
+			let contract = H160::repeat_byte(0xcc);
+			AccountCodes::<Test>::insert(contract, vec![0; code_len]);
+
+			let with_code = call(contract);
+			assert_eq!(with_code.value, H256::from_low_u64_be(1).as_bytes());
+			let without_code = call(H160::repeat_byte(0xdd));
+			assert_eq!(without_code.value, H256::zero().as_bytes());
+
+			// Only the size of the code is recorded on top of the bound, and nothing is written.
+			assert_eq!(
+				proof_size(&with_code) - proof_size(&without_code),
+				code_len as u64
+			);
+			assert!(AccountCodesMetadata::<Test>::get(contract).is_none());
+		});
+	}
 }
 
 type Balances = pallet_balances::Pallet<Test>;
@@ -709,6 +758,76 @@ fn fail_call_return_ok() {
 	});
 }
 
+#[test]
+fn failed_call_event_carries_bounded_revert_data() {
+	// PUSH1 0xab PUSH1 0x00 MSTORE8 PUSH1 0xcd PUSH1 0x01 MSTORE8 PUSH1 0x02 PUSH1 0x00 REVERT
+	let code = hex::decode("60ab60005360cd60015360026000fd").unwrap();
+	let contract = H160::from_str("1000000000000000000000000000000000000005").unwrap();
+	let call = || {
+		assert_ok!(EVM::call(
+			RuntimeOrigin::root(),
+			H160::default(),
+			contract,
+			Vec::new(),
+			U256::default(),
+			1000000,
+			U256::from(1_000_000_000),
+			None,
+			None,
+			Vec::new(),
+		));
+		match System::events().pop().expect("event deposited").event {
+			RuntimeEvent::EVM(Event::ExecutedFailed { address, details }) => {
+				assert_eq!(address, contract);
+				details
+			}
+			event => panic!("unexpected event {:?}", event),
+		}
+	};
+
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		EVM::create_account(contract, code);
+
+		assert_eq!(call(), None);
+
+		EventRevertDataLimit::set(Some(1));
+		let details = call().expect("details enabled");
+		assert_eq!(details.revert_data, vec![0xab]);
+		assert!(details.used_gas > U256::from(21_000));
+
+		EventRevertDataLimit::set(Some(32));
+		assert_eq!(
+			call().expect("details enabled").revert_data,
+			vec![0xab, 0xcd]
+		);
+	});
+}
+
+#[test]
+fn genesis_nonce_is_not_capped() {
+	let mut t = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+
+	let address = H160::from_str("1000000000000000000000000000000000000003").unwrap();
+	let mut accounts = BTreeMap::new();
+	accounts.insert(
+		address,
+		GenesisAccount {
+			nonce: U256::from(1_000),
+			balance: U256::from(1000000),
+			storage: Default::default(),
+			code: vec![],
+		},
+	);
+	GenesisBuild::<Test>::assimilate_storage(&crate::GenesisConfig { accounts }, &mut t).unwrap();
+
+	sp_io::TestExternalities::from(t).execute_with(|| {
+		assert_eq!(EVM::account_basic(&address).0.nonce, U256::from(1_000));
+	});
+}
+
 #[test]
 fn fee_deduction() {
 	new_test_ext().execute_with(|| {
@@ -1254,3 +1373,664 @@ fn metadata_empty_dont_code_gets_cached() {
 		assert!(<AccountCodesMetadata<Test>>::get(&address).is_none());
 	});
 }
+
+mod storage_deposit_test {
+	use super::*;
+
+	// Stores the second calldata word at the slot given by the first calldata word.
+	//
+	// PUSH1 0x20 CALLDATALOAD PUSH1 0x00 CALLDATALOAD SSTORE STOP
+	const STORE_CONTRACT_CODE: [u8; 8] = [0x60, 0x20, 0x35, 0x60, 0x00, 0x35, 0x55, 0x00];
+
+	fn store(source: H160, contract: H160, index: u8, value: u8) -> ExitReason {
+		let mut input = [0u8; 64];
+		input[31] = index;
+		input[63] = value;
+		<Test as Config>::Runner::call(
+			source,
+			contract,
+			input.to_vec(),
+			U256::zero(),
+			1_000_000,
+			None,
+			None,
+			None,
+			Vec::new(),
+			false, // non-transactional
+			false, // not validated
+			None,
+			None,
+			&<Test as Config>::config().clone(),
+		)
+		.expect("call succeeds")
+		.exit_reason
+	}
+
+	#[test]
+	fn storage_deposit_is_held_and_released() {
+		new_test_ext().execute_with(|| {
+			StorageDepositPerSlot::set(10);
+			let payer = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+			let contract = H160::from_str("1000000000000000000000000000000000000005").unwrap();
+			let _ = Balances::deposit_creating(&payer, 100);
+			EVM::create_account(contract, STORE_CONTRACT_CODE.to_vec());
+
+			// A new slot is charged to the transaction origin.
+			assert_eq!(store(payer, contract, 1, 1), ExitSucceed::Stopped.into());
+			assert_eq!(Balances::free_balance(&payer), 90);
+			assert_eq!(Balances::reserved_balance(&contract), 10);
+			assert_eq!(<AccountStorageDeposits<Test>>::get(contract, payer), 10);
+
+			// Updating an existing slot is free.
+			assert_eq!(store(payer, contract, 1, 2), ExitSucceed::Stopped.into());
+			assert_eq!(Balances::free_balance(&payer), 90);
+			assert_eq!(<AccountStorageDeposits<Test>>::get(contract, payer), 10);
+
+			// Clearing the slot refunds the deposit.
+			assert_eq!(store(payer, contract, 1, 0), ExitSucceed::Stopped.into());
+			assert_eq!(Balances::free_balance(&payer), 100);
+			assert_eq!(Balances::reserved_balance(&contract), 0);
+			assert!(!<AccountStorageDeposits<Test>>::contains_key(
+				contract, payer
+			));
+		});
+	}
+
+	#[test]
+	fn storage_deposit_is_refunded_to_payer() {
+		new_test_ext().execute_with(|| {
+			StorageDepositPerSlot::set(10);
+			let payer = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+			let contract = H160::from_str("1000000000000000000000000000000000000005").unwrap();
+			let other = H160::from_str("1000000000000000000000000000000000000006").unwrap();
+			let _ = Balances::deposit_creating(&payer, 100);
+			let _ = Balances::deposit_creating(&other, 100);
+			EVM::create_account(contract, STORE_CONTRACT_CODE.to_vec());
+
+			assert_eq!(store(payer, contract, 1, 1), ExitSucceed::Stopped.into());
+			assert_eq!(
+				<AccountStorageDepositors<Test>>::get(contract, H256::from_low_u64_be(1)),
+				Some((payer, 10))
+			);
+
+			// The slot is cleared by another account, the deposit goes back to its payer.
+			assert_eq!(store(other, contract, 1, 0), ExitSucceed::Stopped.into());
+			assert_eq!(Balances::free_balance(&payer), 100);
+			assert_eq!(Balances::free_balance(&other), 100);
+			assert_eq!(Balances::reserved_balance(&contract), 0);
+			assert!(!<AccountStorageDeposits<Test>>::contains_key(
+				contract, payer
+			));
+			assert!(
+				<AccountStorageDepositors<Test>>::get(contract, H256::from_low_u64_be(1)).is_none()
+			);
+		});
+	}
+
+	#[test]
+	fn storage_deposits_are_refunded_on_deletion() {
+		new_test_ext().execute_with(|| {
+			StorageDepositPerSlot::set(10);
+			let payer = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+			let contract = H160::from_str("1000000000000000000000000000000000000005").unwrap();
+			let other = H160::from_str("1000000000000000000000000000000000000006").unwrap();
+			let _ = Balances::deposit_creating(&payer, 100);
+			let _ = Balances::deposit_creating(&other, 100);
+			EVM::create_account(contract, STORE_CONTRACT_CODE.to_vec());
+
+			assert_eq!(store(payer, contract, 1, 1), ExitSucceed::Stopped.into());
+			assert_eq!(store(payer, contract, 2, 1), ExitSucceed::Stopped.into());
+			assert_eq!(store(other, contract, 3, 1), ExitSucceed::Stopped.into());
+			assert_eq!(Balances::reserved_balance(&contract), 30);
+
+			// Deleting the contract keeps the deposits, they are released in batches.
+			EVM::remove_account(&contract);
+			assert_eq!(Balances::reserved_balance(&contract), 30);
+
+			let result = EVM::release_storage_deposits(RuntimeOrigin::signed(other), contract, 2);
+			assert_eq!(
+				result.unwrap().actual_weight,
+				Some(EVM::storage_deposit_release_weight(2))
+			);
+			assert_eq!(Balances::reserved_balance(&contract), 10);
+
+			let result = EVM::release_storage_deposits(RuntimeOrigin::signed(other), contract, 2);
+			assert_eq!(
+				result.unwrap().actual_weight,
+				Some(EVM::storage_deposit_release_weight(1))
+			);
+			assert_eq!(Balances::free_balance(&payer), 100);
+			assert_eq!(Balances::free_balance(&other), 100);
+			assert_eq!(Balances::reserved_balance(&contract), 0);
+			assert_eq!(
+				<AccountStorageDeposits<Test>>::iter_prefix(contract).count(),
+				0
+			);
+			assert_eq!(
+				<AccountStorageDepositors<Test>>::iter_prefix(contract).count(),
+				0
+			);
+		});
+	}
+
+	#[test]
+	fn storage_deposits_of_live_slots_are_not_released() {
+		new_test_ext().execute_with(|| {
+			StorageDepositPerSlot::set(10);
+			let payer = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+			let contract = H160::from_str("1000000000000000000000000000000000000005").unwrap();
+			let other = H160::from_str("1000000000000000000000000000000000000006").unwrap();
+			let _ = Balances::deposit_creating(&payer, 100);
+			let _ = Balances::deposit_creating(&other, 100);
+			EVM::create_account(contract, STORE_CONTRACT_CODE.to_vec());
+
+			assert_eq!(store(payer, contract, 1, 1), ExitSucceed::Stopped.into());
+			assert_ok!(EVM::release_storage_deposits(
+				RuntimeOrigin::signed(other),
+				contract,
+				10
+			));
+			assert_eq!(Balances::reserved_balance(&contract), 10);
+
+			// A slot created again after its storage was removed releases the previous deposit.
+			EVM::remove_account(&contract);
+			EVM::create_account(contract, STORE_CONTRACT_CODE.to_vec());
+			assert_eq!(store(other, contract, 1, 1), ExitSucceed::Stopped.into());
+			assert_eq!(Balances::free_balance(&payer), 100);
+			assert_eq!(Balances::free_balance(&other), 90);
+			assert_eq!(
+				<AccountStorageDepositors<Test>>::get(contract, H256::from_low_u64_be(1)),
+				Some((other, 10))
+			);
+		});
+	}
+
+	#[test]
+	fn storage_deposit_requires_balance() {
+		new_test_ext().execute_with(|| {
+			StorageDepositPerSlot::set(10);
+			let payer = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+			let contract = H160::from_str("1000000000000000000000000000000000000005").unwrap();
+			let _ = Balances::deposit_creating(&payer, 5);
+			EVM::create_account(contract, STORE_CONTRACT_CODE.to_vec());
+
+			assert_eq!(store(payer, contract, 1, 1), ExitError::OutOfFund.into());
+			assert_eq!(Balances::free_balance(&payer), 5);
+			assert_eq!(
+				<AccountStorages<Test>>::get(contract, H256::from_low_u64_be(1)),
+				H256::zero()
+			);
+			assert!(!<AccountStorageDeposits<Test>>::contains_key(
+				contract, payer
+			));
+		});
+	}
+
+	#[test]
+	fn storage_deposit_disabled_by_default() {
+		new_test_ext().execute_with(|| {
+			let payer = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+			let contract = H160::from_str("1000000000000000000000000000000000000005").unwrap();
+			EVM::create_account(contract, STORE_CONTRACT_CODE.to_vec());
+
+			assert_eq!(store(payer, contract, 1, 1), ExitSucceed::Stopped.into());
+			assert_eq!(
+				<AccountStorages<Test>>::get(contract, H256::from_low_u64_be(1)),
+				H256::from_low_u64_be(1)
+			);
+			assert!(!<AccountStorageDeposits<Test>>::contains_key(
+				contract, payer
+			));
+		});
+	}
+}
+
+mod selfdestruct_test {
+	use super::*;
+
+	// CALLER SELFDESTRUCT
+	const SELFDESTRUCT_CODE: [u8; 2] = [0x33, 0xff];
+	// PUSH1 0x01 PUSH1 0x00 SSTORE CALLER SELFDESTRUCT
+	const STORE_AND_SELFDESTRUCT_INIT_CODE: [u8; 7] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x33, 0xff];
+	// PUSH2 SELFDESTRUCT_CODE PUSH1 0x00 MSTORE PUSH1 0x02 PUSH1 0x1e RETURN
+	const SELFDESTRUCT_INIT_CODE: [u8; 11] = [
+		0x61, 0x33, 0xff, 0x60, 0x00, 0x52, 0x60, 0x02, 0x60, 0x1e, 0xf3,
+	];
+	// PUSH11 SELFDESTRUCT_INIT_CODE PUSH1 0x00 MSTORE
+	// PUSH1 0x0b PUSH1 0x15 PUSH1 0x00 CREATE
+	// DUP1 PUSH1 0x00 SSTORE
+	// PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 PUSH1 0x00 DUP6 GAS CALL STOP
+	//
+	// Creates a contract destroying itself when called, stores its address in slot 0, and calls
+	// it.
+	const CREATE_AND_CALL_CODE: [u8; 40] = [
+		0x6a, 0x61, 0x33, 0xff, 0x60, 0x00, 0x52, 0x60, 0x02, 0x60, 0x1e, 0xf3, 0x60, 0x00, 0x52,
+		0x60, 0x0b, 0x60, 0x15, 0x60, 0x00, 0xf0, 0x80, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00,
+		0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x85, 0x5a, 0xf1, 0x00,
+	];
+
+	fn destroy_existing_contract() -> (H160, H160) {
+		let caller = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+		let contract = H160::from_str("1000000000000000000000000000000000000005").unwrap();
+		EVM::create_account(contract, SELFDESTRUCT_CODE.to_vec());
+		<AccountStorages<Test>>::insert(contract, H256::zero(), H256::from_low_u64_be(1));
+		let _ = Balances::deposit_creating(&contract, 100);
+
+		let info = <Test as Config>::Runner::call(
+			caller,
+			contract,
+			Vec::new(),
+			U256::zero(),
+			1_000_000,
+			None,
+			None,
+			None,
+			Vec::new(),
+			false, // non-transactional
+			false, // not validated
+			None,
+			None,
+			&<Test as Config>::config().clone(),
+		)
+		.expect("call succeeds");
+		assert_eq!(info.exit_reason, ExitSucceed::Suicided.into());
+		(caller, contract)
+	}
+
+	#[test]
+	fn selfdestruct_removes_existing_contract_before_eip_6780() {
+		new_test_ext().execute_with(|| {
+			let (caller, contract) = destroy_existing_contract();
+
+			assert_eq!(Balances::free_balance(&caller), 100);
+			assert!(<AccountCodes<Test>>::get(contract).is_empty());
+			assert_eq!(
+				<AccountStorages<Test>>::get(contract, H256::zero()),
+				H256::zero()
+			);
+		});
+	}
+
+	#[test]
+	fn selfdestruct_only_moves_balance_of_existing_contract_with_eip_6780() {
+		new_test_ext().execute_with(|| {
+			Eip6780::set(true);
+			let (caller, contract) = destroy_existing_contract();
+
+			assert_eq!(Balances::free_balance(&caller), 100);
+			assert_eq!(Balances::free_balance(&contract), 0);
+			assert_eq!(
+				<AccountCodes<Test>>::get(contract),
+				SELFDESTRUCT_CODE.to_vec()
+			);
+			assert_eq!(
+				<AccountStorages<Test>>::get(contract, H256::zero()),
+				H256::from_low_u64_be(1)
+			);
+		});
+	}
+
+	#[test]
+	fn selfdestruct_removes_contract_created_in_same_transaction_with_eip_6780() {
+		new_test_ext().execute_with(|| {
+			Eip6780::set(true);
+			let info = <Test as Config>::Runner::create(
+				H160::default(),
+				STORE_AND_SELFDESTRUCT_INIT_CODE.to_vec(),
+				U256::from(100),
+				1_000_000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not validated
+				None,
+				None,
+				&<Test as Config>::config().clone(),
+			)
+			.expect("create succeeds");
+			assert_eq!(info.exit_reason, ExitSucceed::Suicided.into());
+
+			let contract = info.value;
+			assert_eq!(Balances::free_balance(&contract), 0);
+			assert!(<AccountCodes<Test>>::get(contract).is_empty());
+			assert_eq!(
+				<AccountStorages<Test>>::get(contract, H256::zero()),
+				H256::zero()
+			);
+		});
+	}
+
+	#[test]
+	fn selfdestruct_removes_contract_created_earlier_in_same_transaction_with_eip_6780() {
+		new_test_ext().execute_with(|| {
+			Eip6780::set(true);
+			let caller = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+			let factory = H160::from_str("1000000000000000000000000000000000000006").unwrap();
+			EVM::create_account(factory, CREATE_AND_CALL_CODE.to_vec());
+
+			let info = <Test as Config>::Runner::call(
+				caller,
+				factory,
+				Vec::new(),
+				U256::zero(),
+				1_000_000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not validated
+				None,
+				None,
+				&<Test as Config>::config().clone(),
+			)
+			.expect("call succeeds");
+			assert_eq!(info.exit_reason, ExitSucceed::Stopped.into());
+
+			// The contract was created by `set_code`, then destroyed by a later call.
+			let contract = H160::from(<AccountStorages<Test>>::get(factory, H256::zero()));
+			assert_ne!(contract, H160::zero());
+			assert!(<AccountCodes<Test>>::get(contract).is_empty());
+		});
+	}
+
+	#[test]
+	fn selfdestruct_only_moves_balance_of_contract_created_in_earlier_transaction_with_eip_6780() {
+		new_test_ext().execute_with(|| {
+			Eip6780::set(true);
+			let info = <Test as Config>::Runner::create(
+				H160::default(),
+				SELFDESTRUCT_INIT_CODE.to_vec(),
+				U256::from(100),
+				1_000_000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not validated
+				None,
+				None,
+				&<Test as Config>::config().clone(),
+			)
+			.expect("create succeeds");
+			assert_eq!(info.exit_reason, ExitSucceed::Returned.into());
+			let contract = info.value;
+			assert_eq!(
+				<AccountCodes<Test>>::get(contract),
+				SELFDESTRUCT_CODE.to_vec()
+			);
+
+			let caller = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+			let info = <Test as Config>::Runner::call(
+				caller,
+				contract,
+				Vec::new(),
+				U256::zero(),
+				1_000_000,
+				None,
+				None,
+				None,
+				Vec::new(),
+				false, // non-transactional
+				false, // not validated
+				None,
+				None,
+				&<Test as Config>::config().clone(),
+			)
+			.expect("call succeeds");
+			assert_eq!(info.exit_reason, ExitSucceed::Suicided.into());
+
+			assert_eq!(Balances::free_balance(&caller), 100);
+			assert_eq!(Balances::free_balance(&contract), 0);
+			assert_eq!(
+				<AccountCodes<Test>>::get(contract),
+				SELFDESTRUCT_CODE.to_vec()
+			);
+		});
+	}
+
+	fn selfdestruct_proof_size(eip_6780: bool) -> u64 {
+		new_test_ext().execute_with(|| {
+			Eip6780::set(eip_6780);
+			let contract = H160::from_str("1000000000000000000000000000000000000005").unwrap();
+			EVM::create_account(contract, SELFDESTRUCT_CODE.to_vec());
+
+			let gas_limit: u64 = 1_000_000;
+			let weight_limit = FixedGasWeightMapping::<Test>::gas_to_weight(gas_limit, true);
+			let info = <Test as Config>::Runner::call(
+				H160::default(),
+				contract,
+				Vec::new(),
+				U256::zero(),
+				gas_limit,
+				Some(FixedGasPrice::min_gas_price().0),
+				None,
+				None,
+				Vec::new(),
+				true, // transactional
+				true, // must be validated
+				Some(weight_limit),
+				Some(0),
+				&<Test as Config>::config().clone(),
+			)
+			.expect("call succeeds");
+			assert_eq!(info.exit_reason, ExitSucceed::Suicided.into());
+
+			info.weight_info
+				.expect("weight info")
+				.proof_size_usage
+				.expect("proof size usage")
+		})
+	}
+
+	#[test]
+	fn selfdestruct_records_code_length_read_with_eip_6780() {
+		assert_eq!(
+			selfdestruct_proof_size(true) - selfdestruct_proof_size(false),
+			fp_evm::ACCOUNT_CODES_KEY_PROOF_SIZE
+		);
+	}
+}
+
+mod caller_test {
+	use super::*;
+
+	// Returns the sum of the two arguments following the selector.
+	//
+	// PUSH1 0x24 CALLDATALOAD PUSH1 0x04 CALLDATALOAD ADD
+	// PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN
+	const ADD_CONTRACT_CODE: [u8; 15] = [
+		0x60, 0x24, 0x35, 0x60, 0x04, 0x35, 0x01, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3,
+	];
+	// PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+	const STORE_CONTRACT_CODE: [u8; 6] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x00];
+	// PUSH1 0x00 PUSH1 0x00 REVERT
+	const REVERT_CONTRACT_CODE: [u8; 5] = [0x60, 0x00, 0x60, 0x00, 0xfd];
+
+	// add(uint256,uint256)
+	const ADD_SELECTOR: u32 = 0x771602f7;
+
+	fn caller_and_contract(code: &[u8]) -> (H160, H160) {
+		let caller = H160::from_str("1000000000000000000000000000000000000004").unwrap();
+		let contract = H160::from_str("1000000000000000000000000000000000000005").unwrap();
+		EVM::create_account(contract, code.to_vec());
+		(caller, contract)
+	}
+
+	fn weight_limit() -> Weight {
+		<Test as Config>::GasWeightMapping::gas_to_weight(1_000_000, true)
+	}
+
+	#[test]
+	fn call_decodes_output() {
+		new_test_ext().execute_with(|| {
+			let (caller, contract) = caller_and_contract(&ADD_CONTRACT_CODE);
+
+			let output = EvmCaller::<Test>::call::<U256>(
+				caller,
+				contract,
+				AbiCall::new(ADD_SELECTOR, (U256::from(2), U256::from(3))),
+				weight_limit(),
+			)
+			.expect("call succeeds");
+			assert_eq!(output.value, U256::from(5));
+			// The base cost of the call is part of its weight, and reading the contract code
+			// adds to the proof size.
+			assert!(
+				output.weight.ref_time()
+					>= <Test as Config>::GasWeightMapping::gas_to_weight(21_000, false).ref_time()
+			);
+			assert!(output.weight.proof_size() > 0);
+			assert!(output.weight.all_lte(weight_limit()));
+		});
+	}
+
+	#[test]
+	fn call_maps_revert_to_dispatch_error() {
+		new_test_ext().execute_with(|| {
+			let (caller, contract) = caller_and_contract(&REVERT_CONTRACT_CODE);
+
+			let error = EvmCaller::<Test>::call::<()>(
+				caller,
+				contract,
+				AbiCall::from_raw(Vec::new()),
+				weight_limit(),
+			)
+			.unwrap_err();
+			assert_eq!(error.error, Error::<Test>::CallReverted.into());
+			assert!(error.post_info.actual_weight.is_some());
+		});
+	}
+
+	#[test]
+	fn call_maps_out_of_gas_to_dispatch_error() {
+		new_test_ext().execute_with(|| {
+			let (caller, contract) = caller_and_contract(&ADD_CONTRACT_CODE);
+
+			let error = EvmCaller::<Test>::call::<U256>(
+				caller,
+				contract,
+				AbiCall::new(ADD_SELECTOR, (U256::from(2), U256::from(3))),
+				<Test as Config>::GasWeightMapping::gas_to_weight(21_010, true),
+			)
+			.unwrap_err();
+			assert_eq!(error.error, Error::<Test>::GasLimitTooLow.into());
+		});
+	}
+
+	#[test]
+	fn call_rolls_back_when_output_is_invalid() {
+		new_test_ext().execute_with(|| {
+			let (caller, contract) = caller_and_contract(&STORE_CONTRACT_CODE);
+
+			let error = EvmCaller::<Test>::call::<U256>(
+				caller,
+				contract,
+				AbiCall::from_raw(Vec::new()),
+				weight_limit(),
+			)
+			.unwrap_err();
+			assert_eq!(error.error, Error::<Test>::InvalidCallOutput.into());
+			assert_eq!(
+				<AccountStorages<Test>>::get(contract, H256::zero()),
+				H256::zero()
+			);
+		});
+	}
+}
+
+#[cfg(feature = "tracing")]
+mod tracing_test {
+	use super::*;
+	use crate::runner::tracing::{self, CallEnter, StorageAccess, Tracer};
+	use evm::{Context, Memory, Opcode, Stack};
+
+	// PUSH1 0x2a PUSH1 0x01 SSTORE PUSH1 0x00 PUSH1 0x00 LOG0 STOP
+	const CONTRACT_CODE: [u8; 11] = [
+		0x60, 0x2a, 0x60, 0x01, 0x55, 0x60, 0x00, 0x60, 0x00, 0xa0, 0x00,
+	];
+
+	#[derive(Default)]
+	struct Recorder {
+		opcodes: Vec<Opcode>,
+		calls: Vec<H160>,
+		exits: Vec<ExitReason>,
+		storage: Vec<StorageAccess>,
+		logs: Vec<Log>,
+	}
+
+	impl Tracer for Recorder {
+		fn step(&mut self, _: &Context, opcode: Opcode, _: usize, _: &Stack, _: &Memory) {
+			self.opcodes.push(opcode);
+		}
+
+		fn call_enter(&mut self, call: CallEnter<'_>) {
+			if let CallEnter::Call { address, .. } = call {
+				self.calls.push(address);
+			}
+		}
+
+		fn call_exit(&mut self, reason: &ExitReason, _: &[u8]) {
+			self.exits.push(reason.clone());
+		}
+
+		fn storage_access(&mut self, access: StorageAccess) {
+			self.storage.push(access);
+		}
+
+		fn log(&mut self, log: &Log) {
+			self.logs.push(log.clone());
+		}
+	}
+
+	#[test]
+	fn tracer_receives_execution_events() {
+		new_test_ext().execute_with(|| {
+			let contract = H160::from_str("1000000000000000000000000000000000000006").unwrap();
+			EVM::create_account(contract, CONTRACT_CODE.to_vec());
+
+			let mut recorder = Recorder::default();
+			let result = tracing::using(&mut recorder, || {
+				<Test as Config>::Runner::call(
+					H160::default(),
+					contract,
+					Vec::new(),
+					U256::zero(),
+					1_000_000,
+					None,
+					None,
+					None,
+					Vec::new(),
+					false, // non-transactional
+					false, // not validated
+					None,
+					None,
+					&<Test as Config>::config().clone(),
+				)
+				.expect("call succeeds")
+			});
+			assert_eq!(result.exit_reason, ExitSucceed::Stopped.into());
+
+			assert_eq!(recorder.calls, vec![contract]);
+			assert!(recorder.opcodes.contains(&Opcode::SSTORE));
+			assert!(recorder.opcodes.contains(&Opcode::LOG0));
+			assert_eq!(
+				recorder.storage,
+				vec![StorageAccess::Write {
+					address: contract,
+					index: H256::from_low_u64_be(1),
+					value: H256::from_low_u64_be(42),
+				}]
+			);
+			assert_eq!(recorder.logs.len(), 1);
+			assert_eq!(recorder.logs[0].address, contract);
+			assert_eq!(
+				recorder.exits.last(),
+				Some(&ExitReason::Succeed(ExitSucceed::Stopped))
+			);
+		});
+	}
+}
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Format of the `GeneralStateTests` fixtures.
+
+use std::collections::BTreeMap;
+
+use serde::{de::Error as _, Deserialize, Deserializer};
+use sp_core::{H160, H256, U256};
+
+/// A value encoded as a `0x`-prefixed hex string.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hex<T>(pub T);
+
+pub trait FromHex: Sized {
+	fn from_hex(hex: &str) -> Result<Self, String>;
+}
+
+impl<'de, T: FromHex> Deserialize<'de> for Hex<T> {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let hex = String::deserialize(deserializer)?;
+		T::from_hex(&hex).map(Hex).map_err(D::Error::custom)
+	}
+}
+
+fn strip_prefix(hex: &str) -> &str {
+	hex.strip_prefix("0x").unwrap_or(hex)
+}
+
+impl FromHex for Vec<u8> {
+	fn from_hex(hex: &str) -> Result<Self, String> {
+		hex::decode(strip_prefix(hex)).map_err(|e| format!("invalid hex {hex}: {e}"))
+	}
+}
+
+impl FromHex for U256 {
+	fn from_hex(hex: &str) -> Result<Self, String> {
+		let digits = strip_prefix(hex);
+		if digits.is_empty() {
+			return Ok(U256::zero());
+		}
+		U256::from_str_radix(digits, 16).map_err(|e| format!("invalid quantity {hex}: {e:?}"))
+	}
+}
+
+/// Left-padded to 32 bytes, as storage keys and values are not always given in full.
+impl FromHex for H256 {
+	fn from_hex(hex: &str) -> Result<Self, String> {
+		let bytes = Vec::<u8>::from_hex(hex)?;
+		if bytes.len() > 32 {
+			return Err(format!("invalid hash {hex}"));
+		}
+		let mut hash = H256::zero();
+		hash[32 - bytes.len()..].copy_from_slice(&bytes);
+		Ok(hash)
+	}
+}
+
+impl FromHex for H160 {
+	fn from_hex(hex: &str) -> Result<Self, String> {
+		let bytes = Vec::<u8>::from_hex(hex)?;
+		if bytes.len() != 20 {
+			return Err(format!("invalid address {hex}"));
+		}
+		Ok(H160::from_slice(&bytes))
+	}
+}
+
+/// An empty string is the destination of contract creations.
+impl FromHex for Option<H160> {
+	fn from_hex(hex: &str) -> Result<Self, String> {
+		if strip_prefix(hex).is_empty() {
+			return Ok(None);
+		}
+		H160::from_hex(hex).map(Some)
+	}
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StateTest {
+	pub env: Environment,
+	pub pre: BTreeMap<Hex<H160>, PreAccount>,
+	pub transaction: Transaction,
+	/// Expected post-states, per fork.
+	pub post: BTreeMap<String, Vec<PostState>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Environment {
+	pub current_coinbase: Hex<H160>,
+	pub current_gas_limit: Hex<U256>,
+	pub current_number: Hex<U256>,
+	pub current_timestamp: Hex<U256>,
+	/// Only given from the London hard fork.
+	pub current_base_fee: Option<Hex<U256>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreAccount {
+	pub balance: Hex<U256>,
+	pub code: Hex<Vec<u8>>,
+	pub nonce: Hex<U256>,
+	pub storage: BTreeMap<Hex<H256>, Hex<H256>>,
+}
+
+/// A transaction template, the post-states selecting the data, gas limit and value of each
+/// execution by index.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+	pub data: Vec<Hex<Vec<u8>>>,
+	pub gas_limit: Vec<Hex<U256>>,
+	pub value: Vec<Hex<U256>>,
+	pub gas_price: Option<Hex<U256>>,
+	pub max_fee_per_gas: Option<Hex<U256>>,
+	pub max_priority_fee_per_gas: Option<Hex<U256>>,
+	pub nonce: Hex<U256>,
+	pub secret_key: Hex<H256>,
+	/// Only given by recent fixtures, otherwise derived from the secret key.
+	pub sender: Option<Hex<H160>>,
+	pub to: Hex<Option<H160>>,
+	/// Access list of each data index, if any.
+	pub access_lists: Option<Vec<Option<Vec<AccessListItem>>>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListItem {
+	pub address: Hex<H160>,
+	pub storage_keys: Vec<Hex<H256>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostState {
+	pub hash: Hex<H256>,
+	pub logs: Hex<H256>,
+	pub indexes: Indexes,
+	/// Set when the transaction is invalid.
+	pub expect_exception: Option<String>,
+}
+
+/// Indexes of the transaction data, gas limit and value of an execution.
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct Indexes {
+	pub data: usize,
+	pub gas: usize,
+	pub value: usize,
+}
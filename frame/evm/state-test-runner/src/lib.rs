@@ -0,0 +1,403 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runner for the `GeneralStateTests` fixtures of [ethereum/tests](https://github.com/ethereum/tests),
+//! measuring the conformance of the EVM pallet.
+//!
+//! For each fork of a fixture's `post` section, the pre-state is written to the `AccountCodes`,
+//! `AccountStorages`, balances and nonces of a fresh [`runtime::Runtime`], the transaction is
+//! executed through [`pallet_evm::Runner`], and the resulting state root and logs hash are compared
+//! to the expected ones. Forks without a supported EVM config are reported as skipped.
+//!
+//! `BlockchainTests` fixtures, which import whole blocks, are not supported and fail the run.
+//!
+//! The fixture under `testdata` only exercises the runner. Conformance is measured by the CI on
+//! the unmodified `GeneralStateTests` of a pinned ethereum/tests release.
+
+#![deny(unused_crate_dependencies)]
+
+mod fixture;
+pub mod runtime;
+
+#[cfg(test)]
+mod tests;
+
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use frame_support::traits::Currency;
+use sp_core::{hashing::keccak_256, H160, H256, U256};
+// Frontier
+use pallet_evm::{AccountCodes, AccountStorages, EvmConfig, Log, Runner as _};
+
+pub use self::fixture::Indexes;
+use self::{
+	fixture::{Environment, Hex, PostState, PreAccount, StateTest},
+	runtime::{Balances, Runtime, System, Timestamp, EVM},
+};
+
+static ISTANBUL_CONFIG: EvmConfig = EvmConfig::istanbul();
+static BERLIN_CONFIG: EvmConfig = EvmConfig::berlin();
+static LONDON_CONFIG: EvmConfig = EvmConfig::london();
+static MERGE_CONFIG: EvmConfig = EvmConfig::merge();
+static SHANGHAI_CONFIG: EvmConfig = EvmConfig::shanghai();
+
+/// EVM config of a fork, as named by the fixtures.
+fn fork_config(fork: &str) -> Option<&'static EvmConfig> {
+	match fork {
+		"Istanbul" => Some(&ISTANBUL_CONFIG),
+		"Berlin" => Some(&BERLIN_CONFIG),
+		"London" => Some(&LONDON_CONFIG),
+		"Merge" | "Paris" => Some(&MERGE_CONFIG),
+		"Shanghai" => Some(&SHANGHAI_CONFIG),
+		_ => None,
+	}
+}
+
+/// Outcome of the executed fixtures, per fork.
+#[derive(Debug, Default)]
+pub struct Report {
+	pub forks: BTreeMap<String, ForkReport>,
+}
+
+impl Report {
+	/// Whether no execution failed.
+	pub fn is_success(&self) -> bool {
+		self.forks.values().all(|fork| fork.failures.is_empty())
+	}
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (fork, report) in &self.forks {
+			writeln!(
+				f,
+				"{fork}: {} passed, {} failed, {} skipped",
+				report.passed,
+				report.failures.len(),
+				report.skipped
+			)?;
+			for failure in &report.failures {
+				writeln!(
+					f,
+					"  {} (data {}, gas {}, value {}): {}",
+					failure.test,
+					failure.indexes.data,
+					failure.indexes.gas,
+					failure.indexes.value,
+					failure.reason
+				)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+#[derive(Debug, Default)]
+pub struct ForkReport {
+	pub passed: usize,
+	pub skipped: usize,
+	pub failures: Vec<Failure>,
+}
+
+#[derive(Debug)]
+pub struct Failure {
+	pub test: String,
+	pub indexes: Indexes,
+	pub reason: String,
+}
+
+/// Runs all the fixtures of a directory and its subdirectories.
+pub fn run_directory(path: &Path) -> Result<Report, String> {
+	let mut report = Report::default();
+	visit(path, &mut report)?;
+	Ok(report)
+}
+
+fn visit(path: &Path, report: &mut Report) -> Result<(), String> {
+	if path.is_dir() {
+		let mut entries = fs::read_dir(path)
+			.and_then(|entries| {
+				entries
+					.map(|entry| entry.map(|entry| entry.path()))
+					.collect::<Result<Vec<_>, _>>()
+			})
+			.map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+		entries.sort();
+
+		for entry in entries {
+			visit(&entry, report)?;
+		}
+		Ok(())
+	} else if path
+		.extension()
+		.map_or(false, |extension| extension == "json")
+	{
+		run_file(path, report)
+	} else {
+		Ok(())
+	}
+}
+
+/// Runs the fixtures of a file, adding their outcome to `report`.
+pub fn run_file(path: &Path, report: &mut Report) -> Result<(), String> {
+	let data =
+		fs::read_to_string(path).map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+	run_fixtures(&data, report).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Runs the fixtures of a JSON document, adding their outcome to `report`.
+fn run_fixtures(data: &str, report: &mut Report) -> Result<(), String> {
+	let fixtures: BTreeMap<String, serde_json::Value> =
+		serde_json::from_str(data).map_err(|e| format!("failed to parse: {e}"))?;
+
+	for (name, fixture) in fixtures {
+		if fixture.get("blocks").is_some() {
+			return Err(format!(
+				"{name} is a BlockchainTests fixture, which is not supported"
+			));
+		}
+		let test: StateTest =
+			serde_json::from_value(fixture).map_err(|e| format!("failed to parse {name}: {e}"))?;
+		run_test(&name, &test, report);
+	}
+
+	Ok(())
+}
+
+fn run_test(name: &str, test: &StateTest, report: &mut Report) {
+	for (fork, posts) in &test.post {
+		let fork_report = report.forks.entry(fork.clone()).or_default();
+		let Some(config) = fork_config(fork) else {
+			fork_report.skipped += posts.len();
+			continue;
+		};
+
+		for post in posts {
+			match execute(test, post, config) {
+				Ok(()) => fork_report.passed += 1,
+				Err(reason) => fork_report.failures.push(Failure {
+					test: name.to_owned(),
+					indexes: post.indexes,
+					reason,
+				}),
+			}
+		}
+	}
+}
+
+/// Executes the transaction selected by `post` on the pre-state of `test`, and checks the
+/// resulting state.
+fn execute(test: &StateTest, post: &PostState, config: &EvmConfig) -> Result<(), String> {
+	let transaction = &test.transaction;
+	let indexes = post.indexes;
+
+	let input = transaction
+		.data
+		.get(indexes.data)
+		.ok_or("data index out of range")?
+		.0
+		.clone();
+	let gas_limit = transaction
+		.gas_limit
+		.get(indexes.gas)
+		.ok_or("gas index out of range")?
+		.0;
+	let gas_limit = u64::try_from(gas_limit).map_err(|_| "gas limit does not fit u64")?;
+	let value = transaction
+		.value
+		.get(indexes.value)
+		.ok_or("value index out of range")?
+		.0;
+	let access_list = transaction
+		.access_lists
+		.as_ref()
+		.and_then(|access_lists| access_lists.get(indexes.data).cloned().flatten())
+		.unwrap_or_default()
+		.into_iter()
+		.map(|item| {
+			(
+				item.address.0,
+				item.storage_keys.into_iter().map(|key| key.0).collect(),
+			)
+		})
+		.collect::<Vec<(H160, Vec<H256>)>>();
+	// Legacy and EIP-2930 transactions pay their gas price as priority fee, like in
+	// `pallet_ethereum`.
+	let (max_fee_per_gas, max_priority_fee_per_gas) = match &transaction.gas_price {
+		Some(gas_price) => (Some(gas_price.0), Some(gas_price.0)),
+		None => (
+			transaction.max_fee_per_gas.as_ref().map(|fee| fee.0),
+			transaction
+				.max_priority_fee_per_gas
+				.as_ref()
+				.map(|fee| fee.0),
+		),
+	};
+	let source = match &transaction.sender {
+		Some(sender) => sender.0,
+		None => sender(&transaction.secret_key.0)?,
+	};
+	let nonce = Some(transaction.nonce.0);
+
+	let storage = frame_system::GenesisConfig::default().build_storage::<Runtime>()?;
+	sp_io::TestExternalities::new(storage).execute_with(|| {
+		set_environment(&test.env)?;
+		set_pre_state(&test.pre)?;
+
+		let result = match transaction.to.0 {
+			Some(target) => <Runtime as pallet_evm::Config>::Runner::call(
+				source,
+				target,
+				input,
+				value,
+				gas_limit,
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
+				nonce,
+				access_list,
+				true,
+				true,
+				None,
+				None,
+				config,
+			)
+			.map(|info| info.logs),
+			None => <Runtime as pallet_evm::Config>::Runner::create(
+				source,
+				input,
+				value,
+				gas_limit,
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
+				nonce,
+				access_list,
+				true,
+				true,
+				None,
+				None,
+				config,
+			)
+			.map(|info| info.logs),
+		};
+
+		let logs = match (result, &post.expect_exception) {
+			(Ok(logs), None) => logs,
+			(Err(_), Some(_)) => Vec::new(),
+			(Ok(_), Some(exception)) => {
+				return Err(format!("expected {exception}, transaction was executed"))
+			}
+			(Err(e), None) => return Err(format!("transaction was rejected: {:?}", e.error)),
+		};
+
+		let logs_hash = H256(keccak_256(&rlp::encode_list::<Log, _>(&logs)));
+		if logs_hash != post.logs.0 {
+			return Err(format!(
+				"logs hash {logs_hash:?}, expected {:?}",
+				post.logs.0
+			));
+		}
+
+		let state_root = state_root();
+		if state_root != post.hash.0 {
+			return Err(format!(
+				"state root {state_root:?}, expected {:?}",
+				post.hash.0
+			));
+		}
+
+		Ok(())
+	})
+}
+
+/// Address of the account of a secret key.
+fn sender(secret_key: &H256) -> Result<H160, String> {
+	let secret_key = libsecp256k1::SecretKey::parse(secret_key.as_fixed_bytes())
+		.map_err(|e| format!("invalid secret key: {e:?}"))?;
+	let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key).serialize();
+	Ok(H160::from(H256(keccak_256(&public_key[1..65]))))
+}
+
+fn set_environment(env: &Environment) -> Result<(), String> {
+	let number =
+		u64::try_from(env.current_number.0).map_err(|_| "block number does not fit u64")?;
+	let timestamp =
+		u64::try_from(env.current_timestamp.0).map_err(|_| "timestamp does not fit u64")?;
+
+	System::set_block_number(number);
+	// The EVM pallet reads the timestamp in milliseconds.
+	Timestamp::set_timestamp(timestamp.saturating_mul(1000));
+	runtime::BaseFee::set(
+		env.current_base_fee
+			.as_ref()
+			.map(|base_fee| base_fee.0)
+			.unwrap_or_default(),
+	);
+	runtime::Coinbase::set(env.current_coinbase.0);
+	runtime::BlockGasLimit::set(env.current_gas_limit.0);
+
+	Ok(())
+}
+
+fn set_pre_state(pre: &BTreeMap<Hex<H160>, PreAccount>) -> Result<(), String> {
+	for (Hex(address), account) in pre {
+		let balance = u128::try_from(account.balance.0)
+			.map_err(|_| format!("balance of {address:?} does not fit u128"))?;
+		let nonce = u64::try_from(account.nonce.0)
+			.map_err(|_| format!("nonce of {address:?} does not fit u64"))?;
+
+		let _ = Balances::deposit_creating(address, balance);
+		frame_system::Account::<Runtime>::mutate(address, |info| info.nonce = nonce);
+		EVM::create_account(*address, account.code.0.clone());
+		for (Hex(key), Hex(value)) in &account.storage {
+			if !value.is_zero() {
+				AccountStorages::<Runtime>::insert(address, key, value);
+			}
+		}
+	}
+
+	Ok(())
+}
+
+/// Ethereum state root of the accounts known to the EVM pallet. Empty accounts are left out, as
+/// they are removed since the Spurious Dragon hard fork.
+fn state_root() -> H256 {
+	let mut addresses = frame_system::Account::<Runtime>::iter_keys().collect::<Vec<_>>();
+	addresses.extend(AccountCodes::<Runtime>::iter_keys());
+	addresses.sort();
+	addresses.dedup();
+
+	let accounts = addresses
+		.into_iter()
+		.filter(|address| !EVM::is_account_empty(address))
+		.map(|address| {
+			let (account, _) = EVM::account_basic(&address);
+			let storage_root = ethereum::util::sec_trie_root(
+				AccountStorages::<Runtime>::iter_prefix(address)
+					.map(|(key, value)| (key, rlp::encode(&U256::from_big_endian(&value[..])))),
+			);
+			let code_hash = H256(keccak_256(&AccountCodes::<Runtime>::get(address)));
+
+			let mut stream = rlp::RlpStream::new_list(4);
+			stream.append(&account.nonce);
+			stream.append(&account.balance);
+			stream.append(&storage_root);
+			stream.append(&code_hash);
+			(address, stream.out())
+		});
+
+	ethereum::util::sec_trie_root(accounts)
+}
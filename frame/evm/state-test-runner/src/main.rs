@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{path::Path, process::ExitCode};
+
+use pallet_evm_state_test_runner::run_directory;
+
+fn main() -> ExitCode {
+	let Some(path) = std::env::args().nth(1) else {
+		eprintln!("Usage: pallet-evm-state-test-runner <GeneralStateTests directory>");
+		return ExitCode::FAILURE;
+	};
+
+	match run_directory(Path::new(&path)) {
+		Ok(report) => {
+			print!("{report}");
+			if report.is_success() {
+				ExitCode::SUCCESS
+			} else {
+				ExitCode::FAILURE
+			}
+		}
+		Err(e) => {
+			eprintln!("{e}");
+			ExitCode::FAILURE
+		}
+	}
+}
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime the fixtures are executed in.
+//!
+//! The block environment of a fixture (coinbase, base fee and gas limit) is read from thread-local
+//! parameters, set before each execution.
+
+use frame_support::{
+	parameter_types,
+	traits::{ConstU32, ConstU64, FindAuthor},
+	weights::Weight,
+	ConsensusEngineId,
+};
+use sp_core::{H160, H256, U256};
+use sp_runtime::{
+	generic,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+// Frontier
+use fp_evm::{
+	AcceptDelegateCall, AddressU64, CallableByContract, PrecompileAt, PrecompileSetBuilder,
+};
+use pallet_evm::{EnsureAddressNever, EnsureAddressRoot, FeeCalculator, IdentityAddressMapping};
+use pallet_evm_precompile_blake2::Blake2F;
+use pallet_evm_precompile_bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
+use pallet_evm_precompile_modexp::Modexp;
+use pallet_evm_precompile_simple::{ECRecover, Identity, Ripemd160, Sha256};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+frame_support::construct_runtime! {
+	pub enum Runtime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Timestamp: pallet_timestamp::{Pallet, Call, Storage},
+		EVM: pallet_evm::{Pallet, Call, Storage, Config, Event<T>},
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Runtime {
+	type BaseCallFilter = frame_support::traits::Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type RuntimeCall = RuntimeCall;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = H160;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = generic::Header<u64, BlakeTwo256>;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = BlockHashCount;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u128>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+	// Ethereum accounts are only removed when empty, including their balance.
+	pub const ExistentialDeposit: u128 = 0;
+}
+impl pallet_balances::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = ();
+	type Balance = u128;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type ReserveIdentifier = ();
+	type HoldIdentifier = ();
+	type FreezeIdentifier = ();
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type MaxHolds = ();
+	type MaxFreezes = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1000;
+}
+impl pallet_timestamp::Config for Runtime {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+/// Base fee of the fixture environment.
+pub struct EnvironmentBaseFee;
+impl FeeCalculator for EnvironmentBaseFee {
+	fn min_gas_price() -> (U256, Weight) {
+		(BaseFee::get(), Weight::zero())
+	}
+}
+
+/// Coinbase of the fixture environment.
+pub struct EnvironmentCoinbase;
+impl FindAuthor<H160> for EnvironmentCoinbase {
+	fn find_author<'a, I>(_digests: I) -> Option<H160>
+	where
+		I: 'a + IntoIterator<Item = (ConsensusEngineId, &'a [u8])>,
+	{
+		Some(Coinbase::get())
+	}
+}
+
+type EthereumPrecompile = (CallableByContract, AcceptDelegateCall);
+
+/// The precompiles of Ethereum up to the Shanghai hard fork.
+pub type EthereumPrecompiles<R> = PrecompileSetBuilder<
+	pallet_evm::Pallet<R>,
+	(
+		PrecompileAt<AddressU64<1>, ECRecover, EthereumPrecompile>,
+		PrecompileAt<AddressU64<2>, Sha256, EthereumPrecompile>,
+		PrecompileAt<AddressU64<3>, Ripemd160, EthereumPrecompile>,
+		PrecompileAt<AddressU64<4>, Identity, EthereumPrecompile>,
+		PrecompileAt<AddressU64<5>, Modexp, EthereumPrecompile>,
+		PrecompileAt<AddressU64<6>, Bn128Add, EthereumPrecompile>,
+		PrecompileAt<AddressU64<7>, Bn128Mul, EthereumPrecompile>,
+		PrecompileAt<AddressU64<8>, Bn128Pairing, EthereumPrecompile>,
+		PrecompileAt<AddressU64<9>, Blake2F, EthereumPrecompile>,
+	),
+>;
+
+const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+const MAX_POV_SIZE: u64 = 5 * 1024 * 1024;
+
+parameter_types! {
+	pub static BaseFee: U256 = U256::zero();
+	pub static Coinbase: H160 = H160::zero();
+	pub static BlockGasLimit: U256 = U256::from(BLOCK_GAS_LIMIT);
+	pub const GasLimitPovSizeRatio: u64 = BLOCK_GAS_LIMIT.saturating_div(MAX_POV_SIZE);
+	pub WeightPerGas: Weight = Weight::from_parts(20_000, 0);
+	pub PrecompilesValue: EthereumPrecompiles<Runtime> = EthereumPrecompiles::<_>::new();
+}
+impl pallet_evm::Config for Runtime {
+	type FeeCalculator = EnvironmentBaseFee;
+	type GasWeightMapping = pallet_evm::FixedGasWeightMapping<Self>;
+	type WeightPerGas = WeightPerGas;
+
+	type BlockHashMapping = pallet_evm::SubstrateBlockHashMapping<Self>;
+	type CallOrigin = EnsureAddressRoot<Self::AccountId>;
+
+	type WithdrawOrigin = EnsureAddressNever<Self::AccountId>;
+	type AddressMapping = IdentityAddressMapping;
+	type Currency = Balances;
+
+	type RuntimeEvent = RuntimeEvent;
+	type PrecompilesType = EthereumPrecompiles<Self>;
+	type PrecompilesValue = PrecompilesValue;
+	type ChainId = ConstU64<1>;
+	type BlockGasLimit = BlockGasLimit;
+	type Runner = pallet_evm::runner::stack::Runner<Self>;
+	type OnChargeTransaction = ();
+	type OnCreate = ();
+	type StorageDeposit = ();
+	type EventRevertDataLimit = ();
+	type FindAuthor = EnvironmentCoinbase;
+	type GasLimitPovSizeRatio = GasLimitPovSizeRatio;
+	type Timestamp = Timestamp;
+	type WeightInfo = ();
+}
@@ -0,0 +1,60 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use crate::fixture::FromHex;
+
+#[test]
+fn sender_is_derived_from_secret_key() -> Result<(), String> {
+	let secret_key =
+		H256::from_hex("0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8");
+	let address = H160::from_hex("0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+	assert_eq!(sender(&secret_key?), address);
+	Ok(())
+}
+
+#[test]
+fn fixture_is_checked_per_fork() -> Result<(), String> {
+	// Hand-written fixture covering the outcomes of the runner, not a conformance test.
+	let mut report = Report::default();
+	run_file(Path::new("testdata/storeOne.json"), &mut report)?;
+
+	// The second value index expects a wrong state root.
+	let london = &report.forks["London"];
+	assert_eq!(london.passed, 1);
+	assert_eq!(london.failures.len(), 1);
+	assert_eq!(london.failures[0].indexes.value, 1);
+	assert!(london.failures[0].reason.starts_with("state root"));
+
+	// Forks before Istanbul are not supported.
+	assert_eq!(report.forks["Frontier"].skipped, 1);
+	assert!(!report.is_success());
+	Ok(())
+}
+
+#[test]
+fn blockchain_tests_are_rejected() {
+	let mut report = Report::default();
+	assert_eq!(
+		run_fixtures(
+			r#"{"block":{"blocks":[],"genesisBlockHeader":{}}}"#,
+			&mut report
+		),
+		Err("block is a BlockchainTests fixture, which is not supported".to_owned())
+	);
+	assert!(report.forks.is_empty());
+}
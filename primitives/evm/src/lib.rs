@@ -19,6 +19,7 @@
 #![deny(unused_crate_dependencies)]
 
 mod precompile;
+mod precompile_set;
 mod validation;
 
 use frame_support::weights::{constants::WEIGHT_REF_TIME_PER_MILLIS, Weight};
@@ -41,6 +42,11 @@ pub use self::{
 		Precompile, PrecompileFailure, PrecompileHandle, PrecompileOutput, PrecompileResult,
 		PrecompileSet, Transfer,
 	},
+	precompile_set::{
+		AcceptDelegateCall, AddressU64, AllowRecursion, CallableByContract, IsContract,
+		Permissions, PrecompileAt, PrecompileOptions, PrecompileSetBuilder, PrecompileSetFragment,
+		PrecompileSetStartingWith,
+	},
 	validation::{
 		CheckEvmTransaction, CheckEvmTransactionConfig, CheckEvmTransactionInput,
 		InvalidEvmTransactionError,
@@ -63,10 +69,17 @@ pub const ACCOUNT_BASIC_PROOF_SIZE: u64 = 96;
 pub const ACCOUNT_CODES_METADATA_PROOF_SIZE: u64 = 76;
 /// 16 (hash1) + 20 (key1) + 16 (hash2) + 32 (key2) + 32 (value)
 pub const ACCOUNT_STORAGE_PROOF_SIZE: u64 = 116;
+/// `AccountCodes` read without the code, 16 (hash) + 20 (key) + 5 (max compact length prefix).
+pub const ACCOUNT_CODES_KEY_PROOF_SIZE: u64 = 41;
 /// Fixed trie 32 byte hash.
 pub const WRITE_PROOF_SIZE: u64 = 32;
 /// Account basic proof size + 5 bytes max of `decode_len` call.
 pub const IS_EMPTY_CHECK_PROOF_SIZE: u64 = 93;
+/// `AccountStorageDeposits` read, 16 (hash1) + 20 (key1) + 16 (hash2) + 20 (key2) + 16 (u128 balance).
+pub const ACCOUNT_STORAGE_DEPOSIT_PROOF_SIZE: u64 = 88;
+/// `AccountStorageDepositors` read, 16 (hash1) + 20 (key1) + 16 (hash2) + 32 (key2) + 20 (payer)
+/// + 16 (u128 balance).
+pub const ACCOUNT_STORAGE_DEPOSITOR_PROOF_SIZE: u64 = 120;
 
 pub enum AccessedStorage {
 	AccountCodes(H160),
@@ -242,6 +255,10 @@ pub fn weight_per_gas(
 	weight_per_gas
 }
 
+// Used by the integration tests only.
+#[cfg(test)]
+use pallet_evm_test_vector_support as _;
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -0,0 +1,385 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Declarative composition of precompiles into a [`PrecompileSet`].
+//!
+//! ```ignore
+//! pub type Precompiles<R> = PrecompileSetBuilder<
+//!     pallet_evm::Pallet<R>,
+//!     (
+//!         PrecompileAt<AddressU64<1>, ECRecover, (CallableByContract, AcceptDelegateCall)>,
+//!         PrecompileAt<AddressU64<1024>, Sha3FIPS256, CallableByContract>,
+//!         PrecompileSetStartingWith<ForeignAssetPrefix, ForeignAssets<R>, CallableByContract>,
+//!     ),
+//! >;
+//! ```
+
+use core::{cell::RefCell, marker::PhantomData};
+
+use frame_support::traits::Get;
+use impl_trait_for_tuples::impl_for_tuples;
+use sp_core::H160;
+use sp_std::vec::Vec;
+
+use crate::{
+	ExitError, ExitRevert, IsPrecompileResult, Precompile, PrecompileFailure, PrecompileHandle,
+	PrecompileResult, PrecompileSet,
+};
+
+/// Call permissions of a precompile.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Permissions {
+	/// Whether the precompile can be executed through `DELEGATECALL` or `CALLCODE`.
+	pub delegate_call: bool,
+	/// Whether the precompile can be called by smart contracts, and not only by EOAs.
+	pub callable_by_contract: bool,
+	/// Whether the precompile can be called again while already executing.
+	pub recursion: bool,
+}
+
+impl Permissions {
+	/// Permissions granted by either `self` or `other`.
+	pub const fn union(self, other: Self) -> Self {
+		Self {
+			delegate_call: self.delegate_call || other.delegate_call,
+			callable_by_contract: self.callable_by_contract || other.callable_by_contract,
+			recursion: self.recursion || other.recursion,
+		}
+	}
+}
+
+/// Type level precompile options. `()` only allows direct calls from EOAs, options can be
+/// combined in tuples.
+pub trait PrecompileOptions {
+	const PERMISSIONS: Permissions;
+}
+
+impl PrecompileOptions for () {
+	const PERMISSIONS: Permissions = Permissions {
+		delegate_call: false,
+		callable_by_contract: false,
+		recursion: false,
+	};
+}
+
+/// Allow the precompile to be executed through `DELEGATECALL` or `CALLCODE`.
+pub struct AcceptDelegateCall;
+
+impl PrecompileOptions for AcceptDelegateCall {
+	const PERMISSIONS: Permissions = Permissions {
+		delegate_call: true,
+		callable_by_contract: false,
+		recursion: false,
+	};
+}
+
+/// Allow the precompile to be called by smart contracts and other precompiles.
+pub struct CallableByContract;
+
+impl PrecompileOptions for CallableByContract {
+	const PERMISSIONS: Permissions = Permissions {
+		delegate_call: false,
+		callable_by_contract: true,
+		recursion: false,
+	};
+}
+
+/// Allow the precompile to be called again while already executing, e.g. through one of its
+/// own subcalls.
+pub struct AllowRecursion;
+
+impl PrecompileOptions for AllowRecursion {
+	const PERMISSIONS: Permissions = Permissions {
+		delegate_call: false,
+		callable_by_contract: false,
+		recursion: true,
+	};
+}
+
+impl<A: PrecompileOptions, B: PrecompileOptions> PrecompileOptions for (A, B) {
+	const PERMISSIONS: Permissions = A::PERMISSIONS.union(B::PERMISSIONS);
+}
+
+impl<A: PrecompileOptions, B: PrecompileOptions, C: PrecompileOptions> PrecompileOptions
+	for (A, B, C)
+{
+	const PERMISSIONS: Permissions = A::PERMISSIONS.union(B::PERMISSIONS).union(C::PERMISSIONS);
+}
+
+/// Tells whether an address holds contract code, recording the cost of the lookup.
+pub trait IsContract {
+	fn is_contract(handle: &mut impl PrecompileHandle, address: H160) -> Result<bool, ExitError>;
+}
+
+/// Precompile address from its low 8 bytes.
+pub struct AddressU64<const N: u64>;
+
+impl<const N: u64> Get<H160> for AddressU64<N> {
+	fn get() -> H160 {
+		H160::from_low_u64_be(N)
+	}
+}
+
+/// Part of a [`PrecompileSetBuilder`], handling one or more precompile addresses.
+pub trait PrecompileSetFragment {
+	/// Instantiate the fragment.
+	fn new() -> Self;
+
+	/// Permissions of the precompile at `address`, or `None` if not handled by this fragment.
+	fn permissions(&self, address: H160) -> Option<Permissions>;
+
+	/// Execute the precompile at `handle.code_address()`, if handled by this fragment.
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult>;
+
+	/// Whether `address` is a precompile of this fragment.
+	fn is_precompile(&self, address: H160, remaining_gas: u64) -> IsPrecompileResult;
+
+	/// Fixed addresses of this fragment. Address ranges are not listed.
+	fn used_addresses() -> Vec<H160>;
+}
+
+#[impl_for_tuples(1, 32)]
+impl PrecompileSetFragment for Tuple {
+	fn new() -> Self {
+		for_tuples!( ( #( Tuple::new() ),* ) )
+	}
+
+	fn permissions(&self, address: H160) -> Option<Permissions> {
+		for_tuples!( #(
+			if let Some(permissions) = self.Tuple.permissions(address) {
+				return Some(permissions);
+			}
+		)* );
+		None
+	}
+
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		for_tuples!( #(
+			if let Some(result) = self.Tuple.execute(handle) {
+				return Some(result);
+			}
+		)* );
+		None
+	}
+
+	fn is_precompile(&self, address: H160, remaining_gas: u64) -> IsPrecompileResult {
+		let mut extra_cost = 0u64;
+		for_tuples!( #(
+			match self.Tuple.is_precompile(address, remaining_gas.saturating_sub(extra_cost)) {
+				IsPrecompileResult::Answer { is_precompile: true, extra_cost: cost } => {
+					return IsPrecompileResult::Answer {
+						is_precompile: true,
+						extra_cost: extra_cost.saturating_add(cost),
+					};
+				}
+				IsPrecompileResult::Answer { is_precompile: false, extra_cost: cost } => {
+					extra_cost = extra_cost.saturating_add(cost);
+				}
+				out_of_gas => return out_of_gas,
+			}
+		)* );
+		IsPrecompileResult::Answer {
+			is_precompile: false,
+			extra_cost,
+		}
+	}
+
+	fn used_addresses() -> Vec<H160> {
+		let mut addresses = Vec::new();
+		for_tuples!( #( addresses.extend(Tuple::used_addresses()); )* );
+		addresses
+	}
+}
+
+/// Precompile `P` at the fixed address `A`.
+pub struct PrecompileAt<A, P, O = ()>(PhantomData<(A, P, O)>);
+
+impl<A, P, O> PrecompileSetFragment for PrecompileAt<A, P, O>
+where
+	A: Get<H160>,
+	P: Precompile,
+	O: PrecompileOptions,
+{
+	fn new() -> Self {
+		Self(PhantomData)
+	}
+
+	fn permissions(&self, address: H160) -> Option<Permissions> {
+		(address == A::get()).then_some(O::PERMISSIONS)
+	}
+
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		(handle.code_address() == A::get()).then(|| P::execute(handle))
+	}
+
+	fn is_precompile(&self, address: H160, _remaining_gas: u64) -> IsPrecompileResult {
+		IsPrecompileResult::Answer {
+			is_precompile: address == A::get(),
+			extra_cost: 0,
+		}
+	}
+
+	fn used_addresses() -> Vec<H160> {
+		sp_std::vec![A::get()]
+	}
+}
+
+/// Precompile set `S` handling every address starting with the bytes of `Prefix`.
+pub struct PrecompileSetStartingWith<Prefix, S, O = ()> {
+	inner: S,
+	_marker: PhantomData<(Prefix, O)>,
+}
+
+impl<Prefix, S, O> PrecompileSetFragment for PrecompileSetStartingWith<Prefix, S, O>
+where
+	Prefix: Get<&'static [u8]>,
+	S: PrecompileSet + Default,
+	O: PrecompileOptions,
+{
+	fn new() -> Self {
+		Self {
+			inner: Default::default(),
+			_marker: PhantomData,
+		}
+	}
+
+	fn permissions(&self, address: H160) -> Option<Permissions> {
+		address
+			.as_bytes()
+			.starts_with(Prefix::get())
+			.then_some(O::PERMISSIONS)
+	}
+
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		if !handle.code_address().as_bytes().starts_with(Prefix::get()) {
+			return None;
+		}
+		self.inner.execute(handle)
+	}
+
+	fn is_precompile(&self, address: H160, remaining_gas: u64) -> IsPrecompileResult {
+		if !address.as_bytes().starts_with(Prefix::get()) {
+			return IsPrecompileResult::Answer {
+				is_precompile: false,
+				extra_cost: 0,
+			};
+		}
+		self.inner.is_precompile(address, remaining_gas)
+	}
+
+	fn used_addresses() -> Vec<H160> {
+		Vec::new()
+	}
+}
+
+/// [`PrecompileSet`] built from the fragments `P`, enforcing their call permissions.
+/// `R` is used to find out whether a caller is a smart contract.
+pub struct PrecompileSetBuilder<R, P> {
+	inner: P,
+	/// Addresses of the precompiles currently executing, innermost last.
+	executing: RefCell<Vec<H160>>,
+	_marker: PhantomData<R>,
+}
+
+impl<R: IsContract, P: PrecompileSetFragment> PrecompileSetBuilder<R, P> {
+	pub fn new() -> Self {
+		Self {
+			inner: P::new(),
+			executing: RefCell::new(Vec::new()),
+			_marker: PhantomData,
+		}
+	}
+
+	/// Fixed addresses of the precompiles in the set.
+	pub fn used_addresses() -> Vec<H160> {
+		P::used_addresses()
+	}
+
+	fn check_permissions(
+		&self,
+		handle: &mut impl PrecompileHandle,
+		address: H160,
+		permissions: Permissions,
+	) -> Result<(), PrecompileFailure> {
+		if !permissions.delegate_call && handle.context().address != address {
+			return Err(revert("Cannot be called with DELEGATECALL or CALLCODE"));
+		}
+
+		if !permissions.recursion && self.executing.borrow().contains(&address) {
+			return Err(revert("Cannot be called recursively"));
+		}
+
+		if !permissions.callable_by_contract {
+			let caller = handle.context().caller;
+			let is_precompile = self.inner.permissions(caller).is_some();
+			if is_precompile || R::is_contract(handle, caller)? {
+				return Err(revert("Cannot be called by a smart contract"));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl<R: IsContract, P: PrecompileSetFragment> Default for PrecompileSetBuilder<R, P> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<R: IsContract, P: PrecompileSetFragment> PrecompileSet for PrecompileSetBuilder<R, P> {
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		let address = handle.code_address();
+		let permissions = self.inner.permissions(address)?;
+		if let Err(failure) = self.check_permissions(handle, address, permissions) {
+			return Some(Err(failure));
+		}
+
+		self.executing.borrow_mut().push(address);
+		let result = self.inner.execute(handle);
+		self.executing.borrow_mut().pop();
+		result
+	}
+
+	fn is_precompile(&self, address: H160, remaining_gas: u64) -> IsPrecompileResult {
+		self.inner.is_precompile(address, remaining_gas)
+	}
+}
+
+/// Revert with `message` as a Solidity `Error(string)` reason, encoded like
+/// `precompile_utils::solidity::revert` does.
+fn revert(message: &str) -> PrecompileFailure {
+	let word = |value: usize| {
+		let mut word = [0u8; 32];
+		word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+		word
+	};
+
+	let message = message.as_bytes();
+	let padded_len = (message.len() + 31) / 32 * 32;
+	let mut output = Vec::with_capacity(4 + 64 + padded_len);
+	output.extend_from_slice(&0x08c379a0u32.to_be_bytes());
+	output.extend_from_slice(&word(32));
+	output.extend_from_slice(&word(message.len()));
+	output.extend_from_slice(message);
+	output.resize(4 + 64 + padded_len, 0);
+
+	PrecompileFailure::Revert {
+		exit_status: ExitRevert::Reverted,
+		output,
+	}
+}
@@ -0,0 +1,248 @@
+// SPDX-License-Identifier: Apache-2.0
+// This file is part of Frontier.
+//
+// Copyright (c) 2020-2022 Parity Technologies (UK) Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::traits::Get;
+use sp_core::{H160, U256};
+
+// Frontier
+use fp_evm::{
+	AcceptDelegateCall, AddressU64, AllowRecursion, CallableByContract, Context, ExitError,
+	ExitReason, ExitRevert, ExitSucceed, IsContract, IsPrecompileResult, Precompile, PrecompileAt,
+	PrecompileFailure, PrecompileHandle, PrecompileOutput, PrecompileResult, PrecompileSet,
+	PrecompileSetBuilder, PrecompileSetStartingWith,
+};
+use pallet_evm_test_vector_support::{MockHandle, Subcall};
+
+const EOA: H160 = H160::repeat_byte(0xaa);
+const CONTRACT: H160 = H160::repeat_byte(0xcc);
+
+struct Contracts;
+
+impl IsContract for Contracts {
+	fn is_contract(_: &mut impl PrecompileHandle, address: H160) -> Result<bool, ExitError> {
+		Ok(address == CONTRACT)
+	}
+}
+
+/// Returns its input.
+struct Echo;
+
+impl Precompile for Echo {
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			output: handle.input().to_vec(),
+		})
+	}
+}
+
+/// Calls itself once, returning the output of the inner call.
+struct CallSelf;
+
+impl Precompile for CallSelf {
+	fn execute(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+		if !handle.input().is_empty() {
+			return Ok(PrecompileOutput {
+				exit_status: ExitSucceed::Returned,
+				output: b"inner".to_vec(),
+			});
+		}
+
+		let address = handle.code_address();
+		let context = Context {
+			address,
+			caller: address,
+			apparent_value: U256::zero(),
+		};
+		match handle.call(address, None, vec![1], None, false, &context) {
+			(ExitReason::Succeed(exit_status), output) => Ok(PrecompileOutput {
+				exit_status,
+				output,
+			}),
+			(_, output) => Err(PrecompileFailure::Revert {
+				exit_status: ExitRevert::Reverted,
+				output,
+			}),
+		}
+	}
+}
+
+/// Every address not ending with a zero byte is a precompile.
+#[derive(Default)]
+struct EchoSet;
+
+impl PrecompileSet for EchoSet {
+	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+		(handle.code_address()[19] != 0).then(|| Echo::execute(handle))
+	}
+
+	fn is_precompile(&self, address: H160, _: u64) -> IsPrecompileResult {
+		IsPrecompileResult::Answer {
+			is_precompile: address[19] != 0,
+			extra_cost: 0,
+		}
+	}
+}
+
+struct Prefix;
+
+impl Get<&'static [u8]> for Prefix {
+	fn get() -> &'static [u8] {
+		&[0xff, 0xff]
+	}
+}
+
+type TestPrecompiles = PrecompileSetBuilder<
+	Contracts,
+	(
+		PrecompileAt<AddressU64<1>, Echo, (CallableByContract, AcceptDelegateCall)>,
+		PrecompileAt<AddressU64<2>, Echo>,
+		PrecompileAt<AddressU64<3>, CallSelf, CallableByContract>,
+		PrecompileAt<AddressU64<4>, CallSelf, (CallableByContract, AllowRecursion)>,
+		PrecompileSetStartingWith<Prefix, EchoSet>,
+	),
+>;
+
+std::thread_local! {
+	static PRECOMPILES: TestPrecompiles = TestPrecompiles::new();
+}
+
+fn new_handle(code_address: H160, input: Vec<u8>, context: Context) -> MockHandle {
+	let mut handle = MockHandle::new(input, None, context);
+	handle.code_address = code_address;
+	handle.subcall_handler = Some(Box::new(execute_subcall));
+	handle
+}
+
+/// Executes a subcall with the precompiles of the set, like the EVM would.
+fn execute_subcall(subcall: &Subcall) -> (ExitReason, Vec<u8>) {
+	let mut handle = new_handle(
+		subcall.address,
+		subcall.input.clone(),
+		subcall.context.clone(),
+	);
+	match PRECOMPILES.with(|precompiles| precompiles.execute(&mut handle)) {
+		Some(Ok(output)) => (ExitReason::Succeed(output.exit_status), output.output),
+		Some(Err(PrecompileFailure::Revert {
+			exit_status,
+			output,
+		})) => (ExitReason::Revert(exit_status), output),
+		_ => unreachable!(),
+	}
+}
+
+fn execute(code_address: H160, address: H160, caller: H160) -> Option<PrecompileResult> {
+	let context = Context {
+		address,
+		caller,
+		apparent_value: U256::zero(),
+	};
+	let mut handle = new_handle(code_address, Vec::new(), context);
+	PRECOMPILES.with(|precompiles| precompiles.execute(&mut handle))
+}
+
+fn assert_returned(result: Option<PrecompileResult>, expected: &[u8]) {
+	match result {
+		Some(Ok(output)) => assert_eq!(output.output, expected),
+		other => panic!("unexpected result: {:?}", other),
+	}
+}
+
+fn assert_reverted(result: Option<PrecompileResult>, expected: &str) {
+	match result {
+		Some(Err(PrecompileFailure::Revert { output, .. })) => {
+			let len = expected.len();
+			assert_eq!(output[..4], [0x08, 0xc3, 0x79, 0xa0]);
+			assert_eq!(U256::from_big_endian(&output[4..36]), U256::from(32));
+			assert_eq!(U256::from_big_endian(&output[36..68]), U256::from(len));
+			assert_eq!(&output[68..68 + len], expected.as_bytes());
+			assert_eq!(output.len(), 68 + (len + 31) / 32 * 32);
+			assert!(output[68 + len..].iter().all(|byte| *byte == 0));
+		}
+		other => panic!("unexpected result: {:?}", other),
+	}
+}
+
+fn hash(a: u64) -> H160 {
+	H160::from_low_u64_be(a)
+}
+
+fn prefixed(last: u8) -> H160 {
+	let mut address = H160::zero();
+	address.as_bytes_mut()[..2].copy_from_slice(&[0xff, 0xff]);
+	address.as_bytes_mut()[19] = last;
+	address
+}
+
+#[test]
+fn is_precompile_is_derived_from_fragments() {
+	let is_precompile = |address| {
+		PRECOMPILES.with(|precompiles| match precompiles.is_precompile(address, 0) {
+			IsPrecompileResult::Answer { is_precompile, .. } => is_precompile,
+			IsPrecompileResult::OutOfGas => unreachable!(),
+		})
+	};
+
+	assert!((1..=4).all(|n| is_precompile(hash(n))));
+	assert!(!is_precompile(hash(5)));
+	assert!(is_precompile(prefixed(1)));
+	assert!(!is_precompile(prefixed(0)));
+	assert_eq!(
+		TestPrecompiles::used_addresses(),
+		vec![hash(1), hash(2), hash(3), hash(4)]
+	);
+}
+
+#[test]
+fn unknown_address_is_not_executed() {
+	assert!(execute(hash(5), hash(5), EOA).is_none());
+	assert!(execute(prefixed(0), prefixed(0), EOA).is_none());
+	assert_returned(execute(prefixed(1), prefixed(1), EOA), &[]);
+}
+
+#[test]
+fn delegate_call_requires_permission() {
+	assert_returned(execute(hash(1), CONTRACT, EOA), &[]);
+	assert_reverted(
+		execute(hash(2), CONTRACT, EOA),
+		"Cannot be called with DELEGATECALL or CALLCODE",
+	);
+}
+
+#[test]
+fn contract_caller_requires_permission() {
+	assert_returned(execute(hash(1), hash(1), CONTRACT), &[]);
+	assert_returned(execute(hash(2), hash(2), EOA), &[]);
+	assert_reverted(
+		execute(hash(2), hash(2), CONTRACT),
+		"Cannot be called by a smart contract",
+	);
+	// Precompiles are treated as contracts.
+	assert_reverted(
+		execute(hash(2), hash(2), hash(1)),
+		"Cannot be called by a smart contract",
+	);
+}
+
+#[test]
+fn recursion_requires_permission() {
+	assert_reverted(
+		execute(hash(3), hash(3), EOA),
+		"Cannot be called recursively",
+	);
+	assert_returned(execute(hash(4), hash(4), EOA), b"inner");
+}
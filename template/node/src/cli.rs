@@ -65,4 +65,7 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	FrontierDb(fc_cli::FrontierDbCmd),
+
+	/// Convert a Geth state dump into the EVM genesis accounts of a chain spec.
+	ImportGethState(fc_cli::ImportGethStateCmd),
 }
@@ -11,11 +11,12 @@ use sc_client_api::{BlockchainEvents, StateBackendFor};
 use sc_executor::NativeExecutionDispatch;
 use sc_network_sync::SyncingService;
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use sc_transaction_pool::{FullChainApi, Pool};
 use sp_api::ConstructRuntimeApi;
 use sp_runtime::traits::BlakeTwo256;
 // Frontier
 pub use fc_consensus::FrontierBlockImport;
-use fc_rpc::{EthTask, OverrideHandle};
+use fc_rpc::{EthTask, OverrideHandle, PendingBlockCache};
 pub use fc_rpc_core::types::{FeeHistoryCache, FeeHistoryCacheLimit, FilterPool};
 // Local
 use frontier_template_runtime::opaque::Block;
@@ -53,6 +54,15 @@ pub struct EthConfiguration {
 	#[arg(long)]
 	pub enable_dev_signer: bool,
 
+	/// Directory of encrypted JSON keyfiles whose accounts are served by the signing RPCs.
+	#[arg(long, requires = "unsafe_eth_signing_rpc")]
+	pub eth_keystore_path: Option<PathBuf>,
+
+	/// Enable `eth_sign`, `eth_signTransaction`, `eth_signTypedData_v4` and the `personal_*`
+	/// RPCs. Any RPC client can then sign with the unlocked accounts.
+	#[arg(long)]
+	pub unsafe_eth_signing_rpc: bool,
+
 	/// The dynamic-fee pallet target gas price set by block author
 	#[arg(long, default_value = "1")]
 	pub target_gas_price: u64,
@@ -62,6 +72,19 @@ pub struct EthConfiguration {
 	#[arg(long, default_value = "10")]
 	pub execute_gas_limit_multiplier: u64,
 
+	/// Minimum percentage by which a transaction must raise the fees of a pooled transaction
+	/// with the same sender and nonce to replace it.
+	///
+	/// Only enforced for transactions submitted through the Ethereum RPC of this node: a
+	/// replacement gossiped by peers or submitted with `author_submitExtrinsic` only needs a
+	/// higher priority, that is a higher tip.
+	#[arg(long, default_value = "10", value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub tx_replacement_fee_bump: u8,
+
+	/// Interval in milliseconds at which the pending block is rebuilt from the transaction pool.
+	#[arg(long, default_value = "1000", value_parser = clap::value_parser!(u64).range(1..))]
+	pub pending_block_refresh_interval: u64,
+
 	/// Size in bytes of the LRU cache for block data.
 	#[arg(long, default_value = "50")]
 	pub eth_log_block_cache: usize,
@@ -96,6 +119,7 @@ pub struct FrontierPartialComponents {
 	pub filter_pool: Option<FilterPool>,
 	pub fee_history_cache: FeeHistoryCache,
 	pub fee_history_cache_limit: FeeHistoryCacheLimit,
+	pub pending_block: PendingBlockCache<Block>,
 }
 
 pub fn new_frontier_partial(
@@ -105,6 +129,7 @@ pub fn new_frontier_partial(
 		filter_pool: Some(Arc::new(Mutex::new(BTreeMap::new()))),
 		fee_history_cache: Arc::new(Mutex::new(BTreeMap::new())),
 		fee_history_cache_limit: config.fee_history_limit,
+		pending_block: PendingBlockCache::new(),
 	})
 }
 
@@ -136,6 +161,9 @@ pub async fn spawn_frontier_tasks<RuntimeApi, Executor>(
 	overrides: Arc<OverrideHandle<Block>>,
 	fee_history_cache: FeeHistoryCache,
 	fee_history_cache_limit: FeeHistoryCacheLimit,
+	graph: Arc<Pool<FullChainApi<FullClient<RuntimeApi, Executor>, Block>>>,
+	pending_block: PendingBlockCache<Block>,
+	pending_block_refresh_interval: Duration,
 	sync: Arc<SyncingService<Block>>,
 	pubsub_notification_sinks: Arc<
 		fc_mapping_sync::EthereumBlockNotificationSinks<
@@ -203,6 +231,18 @@ pub async fn spawn_frontier_tasks<RuntimeApi, Executor>(
 		);
 	}
 
+	// Spawn Frontier pending block maintenance task.
+	task_manager.spawn_essential_handle().spawn(
+		"frontier-pending-block",
+		Some("frontier"),
+		EthTask::pending_block_task(
+			client.clone(),
+			graph,
+			pending_block,
+			pending_block_refresh_interval,
+		),
+	);
+
 	// Spawn Frontier FeeHistory cache maintenance task.
 	task_manager.spawn_essential_handle().spawn(
 		"frontier-fee-history",
@@ -15,9 +15,12 @@ use sp_api::{CallApiAt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_core::H256;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::{traits::Block as BlockT, Percent};
 // Frontier
-pub use fc_rpc::{EthBlockDataCacheTask, EthConfig, OverrideHandle, StorageOverride, TxPool};
+pub use fc_rpc::{
+	EthBlockDataCacheTask, EthConfig, EthKeystoreSigner, OverrideHandle, PendingBlockCache,
+	StorageOverride, TxPool,
+};
 pub use fc_rpc_core::types::{FeeHistoryCache, FeeHistoryCacheLimit, FilterPool};
 pub use fc_storage::overrides_handle;
 use fp_rpc::{ConvertTransaction, ConvertTransactionRuntimeApi, EthereumRuntimeRPCApi};
@@ -36,6 +39,10 @@ pub struct EthDeps<C, P, A: ChainApi, CT, B: BlockT> {
 	pub is_authority: bool,
 	/// Whether to enable dev signer
 	pub enable_dev_signer: bool,
+	/// Signer for the accounts of the local keystore.
+	pub keystore_signer: Option<EthKeystoreSigner>,
+	/// Whether to serve the signing RPCs.
+	pub unsafe_signing_rpc: bool,
 	/// Network service
 	pub network: Arc<NetworkService<B, B::Hash>>,
 	/// Chain syncing service
@@ -54,9 +61,14 @@ pub struct EthDeps<C, P, A: ChainApi, CT, B: BlockT> {
 	pub fee_history_cache: FeeHistoryCache,
 	/// Maximum fee history cache size.
 	pub fee_history_cache_limit: FeeHistoryCacheLimit,
+	/// Pending block built from the ready transactions.
+	pub pending_block: PendingBlockCache<B>,
 	/// Maximum allowed gas limit will be ` block.gas_limit * execute_gas_limit_multiplier` when
 	/// using eth_call/eth_estimateGas.
 	pub execute_gas_limit_multiplier: u64,
+	/// Minimum fee increase for a transaction replacing a pooled one with the same nonce,
+	/// submitted through the Ethereum RPC. Other replacements only need a higher pool priority.
+	pub replacement_fee_bump: Percent,
 	/// Mandated parent hashes for a given block hash.
 	pub forced_parent_hashes: Option<BTreeMap<H256, H256>>,
 }
@@ -70,6 +82,8 @@ impl<C, P, A: ChainApi, CT: Clone, B: BlockT> Clone for EthDeps<C, P, A, CT, B>
 			converter: self.converter.clone(),
 			is_authority: self.is_authority,
 			enable_dev_signer: self.enable_dev_signer,
+			keystore_signer: self.keystore_signer.clone(),
+			unsafe_signing_rpc: self.unsafe_signing_rpc,
 			network: self.network.clone(),
 			sync: self.sync.clone(),
 			frontier_backend: self.frontier_backend.clone(),
@@ -79,7 +93,9 @@ impl<C, P, A: ChainApi, CT: Clone, B: BlockT> Clone for EthDeps<C, P, A, CT, B>
 			max_past_logs: self.max_past_logs,
 			fee_history_cache: self.fee_history_cache.clone(),
 			fee_history_cache_limit: self.fee_history_cache_limit,
+			pending_block: self.pending_block.clone(),
 			execute_gas_limit_multiplier: self.execute_gas_limit_multiplier,
+			replacement_fee_bump: self.replacement_fee_bump,
 			forced_parent_hashes: self.forced_parent_hashes.clone(),
 		}
 	}
@@ -109,7 +125,8 @@ where
 {
 	use fc_rpc::{
 		Eth, EthApiServer, EthDevSigner, EthFilter, EthFilterApiServer, EthPubSub,
-		EthPubSubApiServer, EthSigner, Net, NetApiServer, TxPoolApiServer, Web3, Web3ApiServer,
+		EthPubSubApiServer, EthSigner, Net, NetApiServer, Personal, PersonalApiServer,
+		TxPoolApiServer, Web3, Web3ApiServer,
 	};
 
 	let EthDeps {
@@ -119,6 +136,8 @@ where
 		converter,
 		is_authority,
 		enable_dev_signer,
+		keystore_signer,
+		unsafe_signing_rpc,
 		network,
 		sync,
 		frontier_backend,
@@ -128,7 +147,9 @@ where
 		max_past_logs,
 		fee_history_cache,
 		fee_history_cache_limit,
+		pending_block,
 		execute_gas_limit_multiplier,
+		replacement_fee_bump,
 		forced_parent_hashes,
 	} = deps;
 
@@ -136,6 +157,9 @@ where
 	if enable_dev_signer {
 		signers.push(Box::new(EthDevSigner::new()) as Box<dyn EthSigner>);
 	}
+	if let Some(keystore_signer) = &keystore_signer {
+		signers.push(Box::new(keystore_signer.clone()) as Box<dyn EthSigner>);
+	}
 
 	io.merge(
 		Eth::new(
@@ -145,13 +169,16 @@ where
 			converter,
 			sync.clone(),
 			signers,
+			unsafe_signing_rpc,
 			overrides.clone(),
 			frontier_backend.clone(),
 			is_authority,
 			block_data_cache.clone(),
+			pending_block.clone(),
 			fee_history_cache,
 			fee_history_cache_limit,
 			execute_gas_limit_multiplier,
+			replacement_fee_bump,
 			forced_parent_hashes,
 		)
 		.replace_config::<EC>()
@@ -169,6 +196,7 @@ where
 				500_usize, // max stored filters
 				max_past_logs,
 				block_data_cache,
+				pending_block,
 			)
 			.into_rpc(),
 		)?;
@@ -199,5 +227,9 @@ where
 	io.merge(Web3::new(client).into_rpc())?;
 	io.merge(tx_pool.into_rpc())?;
 
+	if let Some(keystore_signer) = keystore_signer {
+		io.merge(Personal::new(keystore_signer).into_rpc())?;
+	}
+
 	Ok(io)
 }
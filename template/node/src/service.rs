@@ -14,7 +14,7 @@ use sc_telemetry::{Telemetry, TelemetryHandle, TelemetryWorker};
 use sp_api::{ConstructRuntimeApi, TransactionFor};
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 use sp_core::U256;
-use sp_runtime::traits::BlakeTwo256;
+use sp_runtime::{traits::BlakeTwo256, Percent};
 use sp_trie::PrefixedMemoryDB;
 // Runtime
 use frontier_template_runtime::{opaque::Block, Hash, TransactionConverter};
@@ -307,6 +307,7 @@ where
 		filter_pool,
 		fee_history_cache,
 		fee_history_cache_limit,
+		pending_block,
 	} = new_frontier_partial(&eth_config)?;
 
 	let mut net_config = sc_network::config::FullNetworkConfiguration::new(&config.network);
@@ -369,6 +370,12 @@ where
 	> = Default::default();
 	let pubsub_notification_sinks = Arc::new(pubsub_notification_sinks);
 
+	let keystore_signer = eth_config
+		.eth_keystore_path
+		.as_deref()
+		.map(fc_rpc::EthKeystoreSigner::open)
+		.transpose()?;
+
 	// for ethereum-compatibility rpc.
 	config.rpc_id_provider = Some(Box::new(fc_rpc::EthereumSubIdProvider));
 	let eth_rpc_params = crate::rpc::EthDeps {
@@ -378,6 +385,8 @@ where
 		converter: Some(TransactionConverter),
 		is_authority: config.role.is_authority(),
 		enable_dev_signer: eth_config.enable_dev_signer,
+		keystore_signer,
+		unsafe_signing_rpc: eth_config.unsafe_eth_signing_rpc,
 		network: network.clone(),
 		sync: sync_service.clone(),
 		frontier_backend: match frontier_backend.clone() {
@@ -396,7 +405,9 @@ where
 		max_past_logs: eth_config.max_past_logs,
 		fee_history_cache: fee_history_cache.clone(),
 		fee_history_cache_limit,
+		pending_block: pending_block.clone(),
 		execute_gas_limit_multiplier: eth_config.execute_gas_limit_multiplier,
+		replacement_fee_bump: Percent::from_percent(eth_config.tx_replacement_fee_bump),
 		forced_parent_hashes: None,
 	};
 
@@ -451,6 +462,9 @@ where
 		overrides,
 		fee_history_cache,
 		fee_history_cache_limit,
+		transaction_pool.pool().clone(),
+		pending_block,
+		Duration::from_millis(eth_config.pending_block_refresh_interval),
 		sync_service.clone(),
 		pubsub_notification_sinks,
 	)
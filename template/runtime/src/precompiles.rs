@@ -1,61 +1,80 @@
-use pallet_evm::{
-	IsPrecompileResult, Precompile, PrecompileHandle, PrecompileResult, PrecompileSet,
+use frame_support::traits::ConstU32;
+
+use fp_evm::{
+	AcceptDelegateCall, AddressU64, CallableByContract, PrecompileAt, PrecompileSetBuilder,
 };
-use sp_core::H160;
-use sp_std::marker::PhantomData;
 
+use pallet_evm_precompile_balances_erc20::{Erc20BalancesPrecompile, Erc20Metadata};
+use pallet_evm_precompile_batch::BatchPrecompile;
+use pallet_evm_precompile_block_hash_history::BlockHashHistoryPrecompile;
+use pallet_evm_precompile_call_permit::CallPermitPrecompile;
 use pallet_evm_precompile_modexp::Modexp;
+use pallet_evm_precompile_p256verify::P256Verify;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
+use pallet_evm_precompile_storage_reader::{StorageReaderPrecompile, StorageWhitelist};
 
-pub struct FrontierPrecompiles<R>(PhantomData<R>);
+/// Metadata of the native currency exposed as an ERC-20.
+pub struct NativeErc20Metadata;
 
-impl<R> FrontierPrecompiles<R>
-where
-	R: pallet_evm::Config,
-{
-	pub fn new() -> Self {
-		Self(Default::default())
-	}
-	pub fn used_addresses() -> [H160; 7] {
-		[
-			hash(1),
-			hash(2),
-			hash(3),
-			hash(4),
-			hash(5),
-			hash(1024),
-			hash(1025),
-		]
+impl Erc20Metadata for NativeErc20Metadata {
+	fn name() -> &'static str {
+		"Unit"
 	}
-}
-impl<R> PrecompileSet for FrontierPrecompiles<R>
-where
-	R: pallet_evm::Config,
-{
-	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
-		match handle.code_address() {
-			// Ethereum precompiles :
-			a if a == hash(1) => Some(ECRecover::execute(handle)),
-			a if a == hash(2) => Some(Sha256::execute(handle)),
-			a if a == hash(3) => Some(Ripemd160::execute(handle)),
-			a if a == hash(4) => Some(Identity::execute(handle)),
-			a if a == hash(5) => Some(Modexp::execute(handle)),
-			// Non-Frontier specific nor Ethereum precompiles :
-			a if a == hash(1024) => Some(Sha3FIPS256::execute(handle)),
-			a if a == hash(1025) => Some(ECRecoverPublicKey::execute(handle)),
-			_ => None,
-		}
+
+	fn symbol() -> &'static str {
+		"UNIT"
 	}
 
-	fn is_precompile(&self, address: H160, _gas: u64) -> IsPrecompileResult {
-		IsPrecompileResult::Answer {
-			is_precompile: Self::used_addresses().contains(&address),
-			extra_cost: 0,
-		}
+	fn decimals() -> u8 {
+		18
 	}
 }
 
-fn hash(a: u64) -> H160 {
-	H160::from_low_u64_be(a)
+/// Runtime storage readable by contracts.
+pub struct ReadableStorage;
+
+impl StorageWhitelist for ReadableStorage {
+	fn is_allowed(key: &[u8]) -> bool {
+		key == frame_support::storage::storage_prefix(b"Timestamp", b"Now")
+	}
 }
+
+/// Ethereum precompiles are callable in any way, like on Ethereum.
+type EthereumPrecompile = (CallableByContract, AcceptDelegateCall);
+
+pub type FrontierPrecompiles<R> = PrecompileSetBuilder<
+	pallet_evm::Pallet<R>,
+	(
+		// Ethereum precompiles :
+		PrecompileAt<AddressU64<1>, ECRecover, EthereumPrecompile>,
+		PrecompileAt<AddressU64<2>, Sha256, EthereumPrecompile>,
+		PrecompileAt<AddressU64<3>, Ripemd160, EthereumPrecompile>,
+		PrecompileAt<AddressU64<4>, Identity, EthereumPrecompile>,
+		PrecompileAt<AddressU64<5>, Modexp, EthereumPrecompile>,
+		// RIP-7212 precompile, at the address used by rollups.
+		PrecompileAt<AddressU64<256>, P256Verify, EthereumPrecompile>,
+		// Non-Frontier specific nor Ethereum precompiles :
+		PrecompileAt<AddressU64<1024>, Sha3FIPS256, CallableByContract>,
+		PrecompileAt<AddressU64<1025>, ECRecoverPublicKey, CallableByContract>,
+		PrecompileAt<
+			AddressU64<2050>,
+			Erc20BalancesPrecompile<R, NativeErc20Metadata>,
+			CallableByContract,
+		>,
+		// Subcalls are made on behalf of the caller, so it must not accept delegate calls.
+		PrecompileAt<AddressU64<2056>, BatchPrecompile<R>>,
+		PrecompileAt<AddressU64<2058>, CallPermitPrecompile<R>, CallableByContract>,
+		PrecompileAt<
+			AddressU64<2060>,
+			// `Timestamp::Now` is a `u64`.
+			StorageReaderPrecompile<R, ReadableStorage, ConstU32<8>>,
+			CallableByContract,
+		>,
+		PrecompileAt<
+			AddressU64<2062>,
+			BlockHashHistoryPrecompile<R, pallet_ethereum::HistoricalBlockHashMapping<R>>,
+			CallableByContract,
+		>,
+	),
+>;
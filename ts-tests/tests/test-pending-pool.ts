@@ -130,3 +130,42 @@ describeWithFrontier("Frontier RPC (Pending Transaction Count)", (context) => {
 		}
 	});
 });
+
+describeWithFrontier("Frontier RPC (Pending Block)", (context) => {
+	const TEST_ACCOUNT = "0x1111111111111111111111111111111111111111";
+
+	it("should serve the same pending block to block, receipt and state queries", async function () {
+		this.timeout(15000);
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: TEST_ACCOUNT,
+				value: "0x200", // Must be higher than ExistentialDeposit
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		const txHash = (await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction])).result;
+
+		const pending = (await customRequest(context.web3, "eth_getBlockByNumber", ["pending", false])).result;
+		expect(pending.transactions).to.be.deep.eq([txHash]);
+
+		const receipt = (await customRequest(context.web3, "eth_getTransactionReceipt", [txHash])).result;
+		expect(receipt).to.include({
+			blockHash: null,
+			blockNumber: pending.number,
+			transactionHash: txHash,
+			status: "0x1",
+		});
+
+		expect(await context.web3.eth.getBalance(TEST_ACCOUNT, "pending")).to.eq("512");
+		expect(await context.web3.eth.getBalance(TEST_ACCOUNT, "latest")).to.eq("0");
+
+		await createAndFinalizeBlock(context.web3);
+
+		const mined = (await customRequest(context.web3, "eth_getTransactionReceipt", [txHash])).result;
+		expect(mined.blockHash).to.not.be.null;
+		expect(mined.blockNumber).to.eq(pending.number);
+	});
+});
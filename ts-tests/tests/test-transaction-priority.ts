@@ -25,25 +25,69 @@ describeWithFrontier("Frontier RPC (Priority)", (context) => {
 		return tx;
 	}
 
+	step("should reject replacements that do not bump the fees", async function () {
+		await sendTransaction(context, "0x3B9ACA00");
+		for (var gasPrice of ["0x3B9ACA00", "0x3B9ACA01", "0x4190AAFF"]) {
+			const tx = await context.web3.eth.accounts.signTransaction(
+				{
+					from: GENESIS_ACCOUNT,
+					data: TEST_CONTRACT_BYTECODE,
+					value: "0x00",
+					gasPrice: gasPrice,
+					gas: "0x100000",
+					nonce: 0,
+				},
+				GENESIS_ACCOUNT_PRIVATE_KEY
+			);
+			const result = await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+			expect(result.error.message).to.be.equal("replacement transaction underpriced");
+		}
+	});
+
 	step("should prioritize transaction with the higher gasPrice", async function () {
 		this.timeout(15000);
-		const gasPrices = [
-			"0x3B9ACA01",
-			"0x3B9ACA00",
-			"0x3B9ACA04",
-			"0x3B9ACA06",
-			"0x3B9ACA01",
-			"0x3B9ACA07",
-			"0x3B9ACA02",
-			"0x3B9ACA05",
-			"0x3B9ACA03",
-		];
+		// Each replacement must raise the gas price of the pooled transaction by 10%.
+		const gasPrices = ["0x3B9ACA00", "0x35A4E900", "0x4190AB00", "0x448B9B80", "0x481F2280", "0x41CDB400"];
 		for (var gasPrice of gasPrices) {
 			await sendTransaction(context, gasPrice);
 		}
 		await createAndFinalizeBlock(context.web3);
 		const block = await context.web3.eth.getBlock("latest", true);
 		expect(block.transactions.length).to.be.eq(1);
-		expect(block.transactions[0].gasPrice).to.be.eq("1000000007");
+		expect(block.transactions[0].gasPrice).to.be.eq("1210000000");
+	});
+
+	step("should cancel a pooled transaction with a bumped replacement", async function () {
+		const tx = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				data: TEST_CONTRACT_BYTECODE,
+				value: "0x00",
+				gasPrice: "0x3B9ACA00",
+				gas: "0x100000",
+				nonce: 1,
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		await customRequest(context.web3, "eth_sendRawTransaction", [tx.rawTransaction]);
+
+		const cancel = await context.web3.eth.accounts.signTransaction(
+			{
+				from: GENESIS_ACCOUNT,
+				to: GENESIS_ACCOUNT,
+				value: "0x00",
+				gasPrice: "0x4190AB00",
+				gas: "0x5208",
+				nonce: 1,
+			},
+			GENESIS_ACCOUNT_PRIVATE_KEY
+		);
+		const result = await customRequest(context.web3, "eth_sendRawTransaction", [cancel.rawTransaction]);
+		expect(result.result).to.be.equal(cancel.transactionHash);
+
+		await createAndFinalizeBlock(context.web3);
+		const block = await context.web3.eth.getBlock("latest", true);
+		expect(block.transactions.length).to.be.eq(1);
+		expect(block.transactions[0].hash).to.be.eq(cancel.transactionHash);
 	});
 });
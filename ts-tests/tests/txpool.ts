@@ -56,4 +56,39 @@ describeWithFrontier("Frontier RPC (TxPoolApi)", (context) => {
 			"0x0000000000000000000000000000000000000000: 0 wei + 1048576 gas x 1000000000 wei"
 		);
 	});
+
+	step("txpool_contentFrom should return the sender transactions", async function () {
+		let txpoolContent = await customRequest(context.web3, "txpool_contentFrom", [GENESIS_ACCOUNT]);
+		expect(txpoolContent.result.pending["0x0"].hash).to.be.equal(pending_tx.transactionHash);
+		expect(txpoolContent.result.queued["0x3"].hash).to.be.equal(future_tx.transactionHash);
+
+		txpoolContent = await customRequest(context.web3, "txpool_contentFrom", [
+			"0x1111111111111111111111111111111111111111",
+		]);
+		expect(txpoolContent.result.pending).to.be.empty;
+		expect(txpoolContent.result.queued).to.be.empty;
+	});
+
+	step("txpool_inspectFrom should return the sender transactions", async function () {
+		let txpoolInspect = await customRequest(context.web3, "txpool_inspectFrom", [GENESIS_ACCOUNT]);
+		expect(txpoolInspect.result.pending["0x0"]).to.be.equal(
+			"0x0000000000000000000000000000000000000000: 0 wei + 1048576 gas x 1000000000 wei"
+		);
+		expect(txpoolInspect.result.queued["0x3"]).to.be.equal(
+			"0x0000000000000000000000000000000000000000: 0 wei + 1048576 gas x 1000000000 wei"
+		);
+	});
+
+	step("pending nonce should skip over the ready and future queues", async function () {
+		expect(await context.web3.eth.getTransactionCount(GENESIS_ACCOUNT, "pending")).to.eq(1);
+
+		// Filling the gap promotes the future transaction.
+		await sendTransaction(context, nonce + 1);
+		await sendTransaction(context, nonce + 2);
+		expect(await context.web3.eth.getTransactionCount(GENESIS_ACCOUNT, "pending")).to.eq(4);
+
+		const txpoolStatus = await customRequest(context.web3, "txpool_status", []);
+		expect(txpoolStatus.result.pending).to.be.equal("0x4");
+		expect(txpoolStatus.result.queued).to.be.equal("0x0");
+	});
 });